@@ -0,0 +1,4 @@
+//! Сущность Архива
+
+pub mod ui;
+pub mod model;
@@ -0,0 +1,337 @@
+use std::io::Read;
+use crate::share;
+use crate::share::text_tools::LegacyCharset;
+use crate::share::zip_format::FLAG_UTF8;
+
+/// Reader over the contents of an archive that was loaded into memory.
+pub type ArchiveReader = std::io::Cursor<std::rc::Rc<[u8]>>;
+
+/// ID of the extra field with the parameters of WinZip AES encryption.
+const AES_EXTRA_FIELD_ID: u16 = 0x9901;
+/// Error text of the zip crate when the CRC32 of the decompressed data does not match.
+const CRC_MISMATCH: &str = "Invalid checksum";
+/// Error text of the zip crate when the HMAC of AES-encrypted data does not match.
+const AUTH_CODE_MISMATCH: &str = "Invalid authentication code";
+/// Largest buffer reserved ahead for a decompressed entry: the size in the central
+/// directory of a damaged or crafted archive may be far larger than the data.
+const MAX_PREALLOCATED_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Encryption method used for an archive entry.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum EntryEncryption {
+    /// The entry is not encrypted.
+    None,
+    /// Traditional PKWARE encryption.
+    ZipCrypto,
+    /// WinZip AES encryption with the key length in bits.
+    Aes(u16),
+}
+
+impl EntryEncryption {
+    /// Detects the encryption method from the general purpose flags
+    /// and the central directory extra field of an entry.
+    fn detect(flags: u16, extra_field: &[u8]) -> Self {
+        if flags & 1 == 0 {
+            return Self::None;
+        }
+        let mut offset = 0;
+        while let (Some(id), Some(size)) = (
+            share::read_u16_le(extra_field, offset),
+            share::read_u16_le(extra_field, offset + 2),
+        ) {
+            let block = offset + 4;
+            if id == AES_EXTRA_FIELD_ID {
+                return match extra_field.get(block + 4) {
+                    Some(1) => Self::Aes(128),
+                    Some(2) => Self::Aes(192),
+                    _ => Self::Aes(256),
+                };
+            }
+            offset = block + size as usize;
+        }
+        Self::ZipCrypto
+    }
+
+    pub fn is_encrypted(&self) -> bool {
+        *self != Self::None
+    }
+}
+
+impl std::fmt::Display for EntryEncryption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "None"),
+            Self::ZipCrypto => write!(f, "ZipCrypto"),
+            Self::Aes(bits) => write!(f, "AES-{bits}"),
+        }
+    }
+}
+
+/// Information about an entry of an archive opened by the user.
+#[derive(yew::Properties, PartialEq, Clone)]
+pub struct InfoAboutArchiveEntry {
+    /// Index of the entry in the archive.
+    pub(crate) ind: usize,
+    /// Full path of the entry inside the archive.
+    pub(crate) entry_name: yew::virtual_dom::AttrValue,
+    /// A string containing the date the entry was last modified.
+    pub(crate) last_modified: yew::virtual_dom::AttrValue,
+    /// A string containing the uncompressed size of the entry.
+    pub(crate) entry_size: yew::virtual_dom::AttrValue,
+    /// Uncompressed size in bytes.
+    pub(crate) raw_size: u64,
+    /// Compressed size in bytes.
+    pub(crate) compressed_size: u64,
+    /// CRC32 of the uncompressed data.
+    pub(crate) crc32: u32,
+    /// Offset of the local file header in the archive.
+    pub(crate) header_start: u64,
+    /// Offset of the compressed data in the archive.
+    pub(crate) data_start: u64,
+    pub(crate) is_dir: bool,
+    pub(crate) encryption: EntryEncryption,
+    /// Comment of the entry from the central directory.
+    pub(crate) comment: yew::virtual_dom::AttrValue,
+}
+
+/// Raw name and comment of an entry, `None` if they are stored in UTF-8.
+fn legacy_texts<'a>(file: &'a zip::read::ZipFile, data: &'a [u8]) -> Option<(&'a [u8], &'a [u8])> {
+    let central_start = file.central_header_start() as usize;
+    let flags = share::read_u16_le(data, central_start + 8).unwrap_or(0);
+    if flags & FLAG_UTF8 != 0 {
+        return None;
+    }
+    Some((file.name_raw(), entry_comment_raw(central_start, data)))
+}
+
+/// Comment of the entry whose central directory record starts at the offset.
+fn entry_comment_raw(central_start: usize, data: &[u8]) -> &[u8] {
+    let (Some(name_length), Some(extra_length), Some(comment_length)) = (
+        share::read_u16_le(data, central_start + 28),
+        share::read_u16_le(data, central_start + 30),
+        share::read_u16_le(data, central_start + 32),
+    ) else {
+        return &[];
+    };
+    let comment_start = central_start + 46 + name_length as usize + extra_length as usize;
+    data.get(comment_start..comment_start + comment_length as usize).unwrap_or_default()
+}
+
+impl InfoAboutArchiveEntry {
+    fn from_zip_file(ind: usize, file: &zip::read::ZipFile, data: &[u8], charset: LegacyCharset) -> Self {
+        let modified = file.last_modified();
+        let last_modified = format!(
+            "{:04}-{:02}-{:02} {:02}:{:02}",
+            modified.year(),
+            modified.month(),
+            modified.day(),
+            modified.hour(),
+            modified.minute(),
+        );
+        let flags = share::read_u16_le(data, file.central_header_start() as usize + 8).unwrap_or(0);
+        let encryption = EntryEncryption::detect(flags, file.extra_data().unwrap_or(&[]));
+        let charset = if flags & FLAG_UTF8 != 0 { LegacyCharset::Utf8 } else { charset };
+        let comment = entry_comment_raw(file.central_header_start() as usize, data);
+
+        Self {
+            ind,
+            entry_name: yew::virtual_dom::AttrValue::from(charset.decode(file.name_raw())),
+            last_modified: yew::virtual_dom::AttrValue::from(last_modified),
+            entry_size: yew::virtual_dom::AttrValue::from(share::size_to_string(file.size() as f64)),
+            raw_size: file.size(),
+            compressed_size: file.compressed_size(),
+            crc32: file.crc32(),
+            header_start: file.header_start(),
+            data_start: file.data_start(),
+            is_dir: file.is_dir(),
+            encryption,
+            comment: yew::virtual_dom::AttrValue::from(charset.decode(comment)),
+        }
+    }
+
+    /// The last component of the entry path.
+    pub fn base_name(&self) -> &str {
+        self.entry_name
+            .trim_end_matches('/')
+            .rsplit('/')
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// Opens the entry for reading, decrypting it with the password if required.
+    pub fn open<'a, R: Read + std::io::Seek>(
+        &self,
+        zip: &'a mut zip::ZipArchive<R>,
+        password: &str,
+    ) -> Result<zip::read::ZipFile<'a>, (String, String)> {
+        let file = if self.encryption.is_encrypted() {
+            if password.is_empty() {
+                return Err((
+                    format!("Password required: {}.", self.entry_name),
+                    format!("The entry is encrypted with {}.", self.encryption),
+                ));
+            }
+            zip.by_index_decrypt(self.ind, password.as_bytes())
+        } else {
+            zip.by_index(self.ind)
+        };
+
+        file.map_err(|err| match err {
+            zip::result::ZipError::InvalidPassword => (
+                format!("Wrong password: {}.", self.entry_name),
+                format!("The password does not match the {} key of the entry.", self.encryption),
+            ),
+            err => (
+                format!("Failed to open entry: {}.", self.entry_name),
+                format!("{}", err)
+            ),
+        })
+    }
+
+    /// Separates integrity failures from other errors while reading the entry.
+    pub fn describe_read_error(&self, err: std::io::Error) -> (String, String) {
+        let detail = format!("{}", err);
+        if detail.contains(CRC_MISMATCH) {
+            let hint = if self.encryption == EntryEncryption::ZipCrypto {
+                " ZipCrypto checks only 8 bits of the password, so a wrong password can also cause this."
+            } else {
+                ""
+            };
+            (
+                format!("CRC check failed: {}.", self.entry_name),
+                format!("The entry data is damaged.{hint}"),
+            )
+        } else if detail.contains(AUTH_CODE_MISMATCH) {
+            (
+                format!("AES authentication failed: {}.", self.entry_name),
+                "The password is correct, but the encrypted data is damaged or was modified.".to_string(),
+            )
+        } else {
+            (format!("Error reading entry: {}.", self.entry_name), detail)
+        }
+    }
+
+    /// Whether the error is a CRC32 or AES authentication code mismatch
+    /// found after the whole entry was read.
+    pub fn is_integrity_error(&self, err: &std::io::Error) -> bool {
+        let detail = format!("{}", err);
+        detail.contains(CRC_MISMATCH) || detail.contains(AUTH_CODE_MISMATCH)
+    }
+}
+
+/// An archive loaded into memory from a file selected by the user.
+#[derive(Clone)]
+pub struct OpenedArchive {
+    /// User-specified archive name on disk.
+    pub(crate) file_name: yew::virtual_dom::AttrValue,
+    /// Entries in the order of the central directory.
+    pub(crate) entries: Vec<InfoAboutArchiveEntry>,
+    /// Comment of the whole archive.
+    pub(crate) comment: yew::virtual_dom::AttrValue,
+    /// Charset of the names and comments without the UTF-8 flag, `None` if they are all ASCII.
+    pub(crate) detected_charset: Option<LegacyCharset>,
+    /// Charset the names and comments without the UTF-8 flag were decoded with.
+    pub(crate) charset: LegacyCharset,
+    data: std::rc::Rc<[u8]>,
+    zip: zip::ZipArchive<ArchiveReader>,
+}
+
+impl PartialEq for OpenedArchive {
+    fn eq(&self, other: &Self) -> bool {
+        std::rc::Rc::ptr_eq(&self.data, &other.data) && self.entries == other.entries
+    }
+}
+
+impl OpenedArchive {
+    /// Reads the central directory of the archive.
+    pub fn from_bytes(
+        file_name: yew::AttrValue,
+        data: Vec<u8>,
+    ) -> Result<Self, (String, String)> {
+        Self::from_shared_bytes(file_name, std::rc::Rc::from(data))
+    }
+
+    /// Reads the central directory of an archive whose contents are shared with the caller.
+    pub fn from_shared_bytes(
+        file_name: yew::AttrValue,
+        data: std::rc::Rc<[u8]>,
+    ) -> Result<Self, (String, String)> {
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(data.clone())).map_err(|err| (
+            format!("Failed to open archive {}.", file_name),
+            format!("{}", err)
+        ))?;
+
+        let mut samples = vec![zip.comment().to_vec()];
+        for ind in 0..zip.len() {
+            let Ok(file) = zip.by_index_raw(ind) else { continue; };
+            if let Some((name, comment)) = legacy_texts(&file, &data) {
+                samples.extend([name.to_vec(), comment.to_vec()]);
+            }
+        }
+        let detected_charset = LegacyCharset::detect(samples.iter().map(Vec::as_slice));
+        let charset = detected_charset.unwrap_or(LegacyCharset::Cp437);
+        Self::read_entries(file_name, data, zip, detected_charset, charset)
+    }
+
+    /// Decodes the names and comments without the UTF-8 flag with another charset.
+    pub fn with_charset(&self, charset: LegacyCharset) -> Result<Self, (String, String)> {
+        Self::read_entries(self.file_name.clone(), self.data.clone(), self.zip(), self.detected_charset, charset)
+    }
+
+    fn read_entries(
+        file_name: yew::AttrValue,
+        data: std::rc::Rc<[u8]>,
+        mut zip: zip::ZipArchive<ArchiveReader>,
+        detected_charset: Option<LegacyCharset>,
+        charset: LegacyCharset,
+    ) -> Result<Self, (String, String)> {
+        let mut entries = Vec::with_capacity(zip.len());
+        for ind in 0..zip.len() {
+            let file = zip.by_index_raw(ind).map_err(|err| (
+                format!("Failed to read entry #{} of archive {}.", ind, file_name),
+                format!("{}", err)
+            ))?;
+            entries.push(InfoAboutArchiveEntry::from_zip_file(ind, &file, &data, charset));
+        }
+        let comment = yew::AttrValue::from(charset.decode(zip.comment()));
+
+        Ok(Self { file_name, entries, comment, detected_charset, charset, data, zip })
+    }
+
+    /// Returns an independent reader of the archive.
+    pub fn zip(&self) -> zip::ZipArchive<ArchiveReader> {
+        self.zip.clone()
+    }
+
+    /// Raw bytes of the archive.
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// Reads and decompresses an entry, decrypting it with the password if required.
+    pub fn read_entry(
+        &self,
+        entry: &InfoAboutArchiveEntry,
+        password: &str,
+    ) -> Result<Vec<u8>, (String, String)> {
+        let mut zip = self.zip();
+        let mut file = entry.open(&mut zip, password)?;
+        let mut data = Vec::with_capacity(entry.raw_size.min(MAX_PREALLOCATED_SIZE) as usize);
+        // One byte more than declared is enough to notice an entry that unpacks past its size.
+        (&mut file).take(entry.raw_size.saturating_add(1))
+            .read_to_end(&mut data)
+            .map_err(|err| entry.describe_read_error(err))?;
+        if data.len() as u64 > entry.raw_size {
+            return Err((
+                format!("Entry is larger than declared: {}.", entry.entry_name),
+                "The decompressed data exceeds the size in the central directory, which is typical for zip bombs.".to_owned(),
+            ));
+        }
+        Ok(data)
+    }
+
+    /// Whether at least one entry requires a password.
+    pub fn has_encrypted_entries(&self) -> bool {
+        self.entries.iter().any(|entry| entry.encryption.is_encrypted())
+    }
+}
@@ -0,0 +1,42 @@
+use yew::{Context, Html};
+use crate::entities::archive;
+
+#[derive(yew::Properties, PartialEq, Clone)]
+pub struct ArchiveEntryRowProps {
+    pub(crate) info_about_archive_entry: archive::model::InfoAboutArchiveEntry,
+}
+
+/// Component - A string containing information about an archive entry.
+pub struct ArchiveEntryRowComponent;
+
+impl yew::Component for ArchiveEntryRowComponent {
+    type Message = ();
+    type Properties = ArchiveEntryRowProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self
+    }
+
+
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let entry = &ctx.props().info_about_archive_entry;
+        let encryption = entry.encryption;
+        yew::html! {
+            <>
+                <div class={yew::classes!("d-flex", "w-100", "justify-content-between", "align-items-center")}>
+                    <h6 class={yew::classes!("mb-1", "text-truncate")}>{&entry.entry_name}</h6>
+                    if encryption.is_encrypted() {
+                        <span class={yew::classes!("badge", "bg-dark")}>{format!("{encryption}")}</span>
+                    }
+                </div>
+                <div class={yew::classes!("d-flex", "w-100", "justify-content-between", "align-items-center")}>
+                    <small>{&entry.last_modified}</small>
+                    <small>{&entry.entry_size}</small>
+                </div>
+                if !entry.comment.is_empty() {
+                    <small class={yew::classes!("d-block", "text-muted", "text-break")}>{&entry.comment}</small>
+                }
+            </>
+        }
+    }
+}
@@ -37,3 +37,42 @@ impl InfoAboutSelectedFile {
     }
 }
 
+
+/// Column by which the selected files are sorted.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum SortColumn {
+    Name,
+    Size,
+    Type,
+    Modified,
+}
+
+impl SortColumn {
+    pub const ALL: [Self; 4] = [Self::Name, Self::Size, Self::Type, Self::Modified];
+}
+
+impl std::fmt::Display for SortColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Name => write!(f, "Name"),
+            Self::Size => write!(f, "Size"),
+            Self::Type => write!(f, "Type"),
+            Self::Modified => write!(f, "Modified"),
+        }
+    }
+}
+
+impl InfoAboutSelectedFile {
+    /// Compares files by the column, then by name. Names and types are compared in natural order.
+    pub fn compare_by(&self, other: &Self, column: SortColumn) -> std::cmp::Ordering {
+        let by_name = share::natural_cmp(&self.file_name, &other.file_name);
+        match column {
+            SortColumn::Name => by_name,
+            SortColumn::Size => self.raw_size.cmp(&other.raw_size).then(by_name),
+            SortColumn::Type => share::natural_cmp(&self.file_type, &other.file_type).then(by_name),
+            SortColumn::Modified => self.js_file_obj.last_modified_time()
+                .cmp(&other.js_file_obj.last_modified_time())
+                .then(by_name),
+        }
+    }
+}
@@ -6,7 +6,7 @@ pub struct SelectedFileFileRowProps {
     pub(crate) info_about_selected_file: file::model::InfoAboutSelectedFile,
 }
 
-/// Component - A string containing information about the selected file.
+/// Component - cells of a table row with information about the selected file.
 pub struct SelectedFileFileRowComponent;
 
 impl yew::Component for SelectedFileFileRowComponent {
@@ -22,17 +22,20 @@ impl yew::Component for SelectedFileFileRowComponent {
         let file_name = &ctx.props().info_about_selected_file.file_name;
         let last_modified = &ctx.props().info_about_selected_file.last_modified;
         let file_size = &ctx.props().info_about_selected_file.file_size;
+        let file_type = &ctx.props().info_about_selected_file.file_type;
         yew::html! {
             <>
-                <div class={yew::classes!("d-flex", "w-100", "justify-content-between", "align-items-center")}>
-                    <h5 class={yew::classes!("mb-1", "text-truncate")}>{file_name}</h5>
-                </div>
-                <div class={yew::classes!("d-flex", "w-100", "justify-content-between", "align-items-center")}>
-                    <small>{last_modified}</small>
-                    <small>{file_size}</small>
-                </div>
-
+                <td class={yew::classes!("text-break")}>{file_name}</td>
+                <td class={yew::classes!("text-nowrap")}>{file_size}</td>
+                <td class={yew::classes!("text-break")}>
+                    if file_type.is_empty() {
+                        <span class={yew::classes!("text-muted")}>{"unknown"}</span>
+                    } else {
+                        {file_type}
+                    }
+                </td>
+                <td class={yew::classes!("text-nowrap")}>{last_modified}</td>
             </>
         }
     }
-}
\ No newline at end of file
+}
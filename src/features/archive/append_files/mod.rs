@@ -0,0 +1,7 @@
+//! Добавление новых файлов в существующий архив.
+
+mod ui;
+mod model;
+
+pub use ui::AppendFilesComponent;
+pub(crate) use model::{type_encryption_of, unique_name, ConflictResolution};
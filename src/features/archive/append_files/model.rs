@@ -0,0 +1,115 @@
+use std::io::Write;
+use crate::entities::archive::model::{EntryEncryption, OpenedArchive};
+use crate::features::archive::merge_archives::RawArchiveWriter;
+use crate::share::zip_tools::TypeEncryption;
+
+/// What to do with a new file whose name is already taken in the archive.
+#[derive(PartialEq, Copy, Clone)]
+pub(crate) enum ConflictResolution {
+    Replace,
+    KeepBoth,
+    Skip,
+}
+
+impl From<String> for ConflictResolution {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Replace" => { Self::Replace }
+            "Skip" => { Self::Skip }
+            _ => { Self::KeepBoth }
+        }
+    }
+}
+
+/// A file read from disk that should be added to the archive.
+pub(crate) struct NewEntry {
+    pub name: String,
+    pub data: Vec<u8>,
+    pub resolution: ConflictResolution,
+}
+
+/// Encryption for new entries, matching the entries already in the archive.
+pub(crate) fn type_encryption_of(archive: &OpenedArchive) -> TypeEncryption {
+    let encryption = archive.entries.iter()
+        .map(|entry| entry.encryption)
+        .find(EntryEncryption::is_encrypted);
+    match encryption {
+        Some(EntryEncryption::ZipCrypto) => TypeEncryption::ZipCrypto,
+        _ => TypeEncryption::Aes256,
+    }
+}
+
+/// Returns a name like `report (1).txt` that is not present in `names`.
+pub(crate) fn unique_name(name: &str, names: &std::collections::HashSet<String>) -> String {
+    (1..)
+        .map(|n| crate::share::numbered_name(name, n))
+        .find(|candidate| !names.contains(candidate))
+        .unwrap_or_default()
+}
+
+/// Adds new entries to the archive without recompressing the existing ones.
+///
+/// The remaining entries are copied byte for byte into a new archive, so encrypted
+/// entries keep their encryption. The new files are added at the end.
+pub(crate) fn append_files(
+    archive: &OpenedArchive,
+    new_entries: Vec<NewEntry>,
+    password: &str,
+) -> Result<Vec<u8>, (String, String)> {
+    let mut names: std::collections::HashSet<String> = archive.entries.iter()
+        .map(|entry| entry.entry_name.to_string())
+        .collect();
+    let mut replaced = std::collections::HashSet::new();
+    let mut to_write = Vec::with_capacity(new_entries.len());
+    for entry in new_entries {
+        let name = if names.contains(&entry.name) {
+            match entry.resolution {
+                ConflictResolution::Skip => continue,
+                ConflictResolution::Replace if !replaced.contains(&entry.name) => {
+                    replaced.insert(entry.name.clone());
+                    entry.name
+                }
+                _ => unique_name(&entry.name, &names),
+            }
+        } else {
+            entry.name
+        };
+        names.insert(name.clone());
+        to_write.push((name, entry.data));
+    }
+
+    let options = crate::share::zip_tools::file_options(password, type_encryption_of(archive));
+    let written = write_files(&to_write, options)?;
+    let mut writer = RawArchiveWriter::with_capacity(archive.data().len() + written.data().len());
+    for entry in &archive.entries {
+        if replaced.contains(entry.entry_name.as_str()) { continue; }
+        writer.copy_entry(archive, entry, entry.entry_name.as_str())?;
+    }
+    for entry in &written.entries {
+        writer.copy_entry(&written, entry, entry.entry_name.as_str())?;
+    }
+    writer.finish(&archive.comment)
+}
+
+/// Writes the new files into a temporary archive.
+fn write_files(
+    files: &[(String, Vec<u8>)],
+    options: zip::write::FileOptions<'_, ()>,
+) -> Result<OpenedArchive, (String, String)> {
+    let mut zip_writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (name, data) in files {
+        zip_writer.start_file(name.as_str(), options).map_err(|err| (
+            format!("Failed to add file meta information to archive: {}.", name),
+            format!("{}", err)
+        ))?;
+        zip_writer.write_all(data).map_err(|err| (
+            format!("Error writing file to archive: {}.", name),
+            format!("{}", err)
+        ))?;
+    }
+
+    let data = zip_writer.finish()
+        .map_err(|err| ("Failed to create archive.".to_string(), format!("{}", err)))?
+        .into_inner();
+    OpenedArchive::from_bytes(yew::AttrValue::default(), data)
+}
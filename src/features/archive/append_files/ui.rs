@@ -0,0 +1,268 @@
+use yew::TargetCast;
+use crate::entities::archive::model::OpenedArchive;
+use crate::entities::file::model::InfoAboutSelectedFile;
+use crate::features::archive::append_files::model::{self, ConflictResolution};
+
+/// State of the appending process.
+#[derive(PartialEq)]
+enum AppendingState {
+    /// Waiting for the user to select files.
+    WaitFiles,
+    /// Waiting for the user to resolve name conflicts.
+    WaitConfirm,
+    /// Files are being read and written to the archive.
+    InProcess,
+    /// Process completed.
+    Done,
+    /// The process did not complete successfully.
+    Fail,
+}
+
+pub enum AppendFilesMsg {
+    /// Files selected by the user.
+    Select(gloo_file::FileList),
+    ChangeResolution(usize, String),
+    StartAppend,
+    /// The file has been read from disk.
+    LoadedFile(usize, Vec<u8>),
+    FailedAppend((String, String)),
+    Reset,
+    Pass,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct AppendFilesProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    /// Password for the new entries.
+    pub password: yew::AttrValue,
+    /// Callback with the archive that contains the new entries.
+    pub on_archive_updated: yew::Callback<OpenedArchive>,
+}
+
+/// Component - adds files selected by the user to an opened archive.
+pub struct AppendFilesComponent {
+    state: AppendingState,
+    files: Vec<InfoAboutSelectedFile>,
+    resolutions: std::collections::HashMap<usize, ConflictResolution>,
+    file_reading_tasks: std::collections::HashMap<usize, gloo_file::callbacks::FileReader>,
+    loaded_files: std::collections::HashMap<usize, Vec<u8>>,
+    err_msg: (String, String),
+    blob_result: Option<gloo_file::Blob>,
+}
+
+impl AppendFilesComponent {
+    fn get_obj_url_with_result(&self) -> Option<String> {
+        let blob = self.blob_result.as_ref()?;
+        let download_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok()?;
+        Some(download_url)
+    }
+
+    /// Indexes of the selected files whose names are already taken in the archive.
+    fn conflicts(&self, archive: &OpenedArchive) -> Vec<usize> {
+        self.files.iter().enumerate()
+            .filter(|(_, file)| archive.entries.iter().any(|entry| entry.entry_name == file.file_name))
+            .map(|(ind, _)| ind)
+            .collect()
+    }
+
+    fn finish(&mut self, ctx: &yew::Context<Self>) {
+        let new_entries = self.files.iter().enumerate().map(|(ind, file)| model::NewEntry {
+            name: file.file_name.to_string(),
+            data: self.loaded_files.remove(&ind).unwrap_or_default(),
+            resolution: self.resolutions.get(&ind).copied().unwrap_or(ConflictResolution::KeepBoth),
+        }).collect();
+        let props = ctx.props();
+        let res = model::append_files(&props.archive, new_entries, &props.password)
+            .and_then(|data| {
+                let blob = gloo_file::Blob::new(data.as_slice());
+                OpenedArchive::from_bytes(props.archive.file_name.clone(), data).map(|archive| (blob, archive))
+            });
+        match res {
+            Ok((blob, archive)) => {
+                self.state = AppendingState::Done;
+                self.blob_result = Some(blob);
+                props.on_archive_updated.emit(archive);
+            }
+            Err(err) => {
+                ctx.link().send_message(AppendFilesMsg::FailedAppend(err));
+            }
+        }
+    }
+}
+
+impl yew::Component for AppendFilesComponent {
+    type Message = AppendFilesMsg;
+    type Properties = AppendFilesProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            state: AppendingState::WaitFiles,
+            files: vec![],
+            resolutions: std::collections::HashMap::new(),
+            file_reading_tasks: std::collections::HashMap::new(),
+            loaded_files: std::collections::HashMap::new(),
+            err_msg: ("".to_string(), "".to_string()),
+            blob_result: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            AppendFilesMsg::Select(file_list) => {
+                self.files = file_list.iter()
+                    .map(|file| InfoAboutSelectedFile::from_js_file(file.clone()))
+                    .collect();
+                self.resolutions = std::collections::HashMap::new();
+                self.state = if self.files.is_empty() {
+                    AppendingState::WaitFiles
+                } else {
+                    AppendingState::WaitConfirm
+                };
+                true
+            }
+            AppendFilesMsg::ChangeResolution(ind, value) => {
+                self.resolutions.insert(ind, ConflictResolution::from(value));
+                false
+            }
+            AppendFilesMsg::StartAppend => {
+                self.state = AppendingState::InProcess;
+                self.loaded_files = std::collections::HashMap::new();
+                let callback_loaded_file = ctx.link()
+                    .callback(|(ind, _, data)| AppendFilesMsg::LoadedFile(ind, data));
+                let callback_filed = ctx.link().callback(AppendFilesMsg::FailedAppend);
+                for (ind, file) in self.files.iter().enumerate() {
+                    let task = crate::share::fs_tools::read_file(
+                        ind,
+                        file.file_name.clone(),
+                        &file.js_file_obj,
+                        callback_loaded_file.clone(),
+                        callback_filed.clone(),
+                    );
+                    self.file_reading_tasks.insert(ind, task);
+                }
+                true
+            }
+            AppendFilesMsg::LoadedFile(ind, data) => {
+                if self.state != AppendingState::InProcess { return false; }
+                self.file_reading_tasks.remove(&ind);
+                self.loaded_files.insert(ind, data);
+                if self.loaded_files.len() == self.files.len() {
+                    self.finish(ctx);
+                }
+                true
+            }
+            AppendFilesMsg::FailedAppend(err) => {
+                self.state = AppendingState::Fail;
+                self.err_msg = err;
+                self.file_reading_tasks = std::collections::HashMap::new();
+                self.loaded_files = std::collections::HashMap::new();
+                true
+            }
+            AppendFilesMsg::Reset => {
+                self.state = AppendingState::WaitFiles;
+                self.files = vec![];
+                self.blob_result = None;
+                true
+            }
+            AppendFilesMsg::Pass => { false }
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let reset_onclick = &ctx.link().callback(|_| AppendFilesMsg::Reset);
+        match self.state {
+            AppendingState::WaitFiles => {
+                let onchange = &ctx.link().callback(move |e: web_sys::Event| {
+                    let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                    if let Some(fl) = input.files() {
+                        AppendFilesMsg::Select(fl.into())
+                    } else { AppendFilesMsg::Pass }
+                });
+                yew::html! {
+                    <span class="btn btn-sm btn-outline-dark"
+                          style="position: relative; overflow: hidden;"
+                    >
+                        {"Add files"}
+                        <input
+                            style="position: absolute; top: 0; right: 0; min-width: 100%; min-height: 100%; font-size: 100px; text-align: right; filter: alpha(opacity=0); opacity: 0; outline: none; cursor: inherit; display: block;"
+                            type="file"
+                            multiple={true}
+                            {onchange}
+                        />
+                    </span>
+                }
+            }
+            AppendingState::WaitConfirm => {
+                let start_onclick = &ctx.link().callback(|_| AppendFilesMsg::StartAppend);
+                let conflicts = self.conflicts(&ctx.props().archive);
+                yew::html! {
+                    <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                        { conflicts.iter().map(|ind| {
+                            let ind = *ind;
+                            let onchange = ctx.link().callback(move |e: web_sys::Event| {
+                                let select: web_sys::HtmlInputElement = e.target_unchecked_into();
+                                AppendFilesMsg::ChangeResolution(ind, select.value())
+                            });
+                            yew::html! {
+                                <div class={yew::classes!("input-group", "input-group-sm", "mb-1")} key={ind}>
+                                    <span class={yew::classes!("input-group-text", "text-truncate", "flex-fill")}>
+                                        {format!("Already exists: {}", self.files[ind].file_name)}
+                                    </span>
+                                    <select class={yew::classes!("form-select")} style="max-width: 135px;" {onchange}>
+                                        <option value={"KeepBoth"}>{"Keep both"}</option>
+                                        <option value={"Replace"}>{"Replace"}</option>
+                                        <option value={"Skip"}>{"Skip"}</option>
+                                    </select>
+                                </div>
+                            }
+                        }).collect::<yew::Html>() }
+                        <div class={yew::classes!("d-flex", "justify-content-end")}>
+                            <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")} onclick={reset_onclick}>
+                                {"Cancel"}
+                            </button>
+                            <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={start_onclick}>
+                                {format!("Append {} file(s)", self.files.len())}
+                            </button>
+                        </div>
+                    </div>
+                }
+            }
+            AppendingState::InProcess => {
+                yew::html! {
+                    <p class={yew::classes!("mb-0")}>{format!("Appending: {}/{}", self.loaded_files.len(), self.files.len())}</p>
+                }
+            }
+            AppendingState::Done => {
+                yew::html! {
+                    <div>
+                        if let Some(href) = self.get_obj_url_with_result() {
+                            <a
+                                class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")}
+                                href={href}
+                                download={ctx.props().archive.file_name.clone()}
+                            >
+                                {"Download updated archive"}
+                            </a>
+                        }
+                        <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                            {"Add more"}
+                        </button>
+                    </div>
+                }
+            }
+            AppendingState::Fail => {
+                let msg = self.err_msg.0.as_str();
+                let detail = self.err_msg.1.as_str();
+                yew::html! {
+                    <div
+                        class={yew::classes!("alert", "alert-danger", "mb-0")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.to_string()}
+                        onclick={reset_onclick}
+                    >{format!("Error: {msg}")}
+                    </div>
+                }
+            }
+        }
+    }
+}
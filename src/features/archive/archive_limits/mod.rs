@@ -0,0 +1,7 @@
+//! Ограничения на размер и число элементов открываемых архивов.
+
+mod ui;
+mod model;
+
+pub use model::{ArchiveLimits, check_limits, check_sevenz_limits, check_tar_limits};
+pub use ui::{ArchiveLimitsComponent, LimitWarningsComponent};
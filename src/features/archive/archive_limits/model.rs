@@ -0,0 +1,186 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::share::tar_tools::{self, TarCompression};
+
+/// Entries smaller than this are not checked for the compression ratio:
+/// small files of zeros compress well without being dangerous.
+const MIN_SIZE_FOR_RATIO: u64 = 1024 * 1024;
+/// Number of entry names listed in a warning.
+const LISTED_NAMES: usize = 3;
+
+/// Limits checked before any entry of an opened archive is decompressed.
+#[derive(PartialEq, Copy, Clone)]
+pub struct ArchiveLimits {
+    /// Maximum total uncompressed size of all entries in bytes.
+    pub max_total_size: u64,
+    /// Maximum ratio of uncompressed to compressed size of an entry.
+    pub max_ratio: u64,
+    pub max_entries: usize,
+}
+
+impl Default for ArchiveLimits {
+    fn default() -> Self {
+        Self {
+            max_total_size: 1024 * 1024 * 1024,
+            max_ratio: 100,
+            max_entries: 10_000,
+        }
+    }
+}
+
+impl ArchiveLimits {
+    /// Whether the data compressed to `compressed_size` unpacks to too much for its size.
+    fn exceeds_ratio(&self, raw_size: u64, compressed_size: u64) -> bool {
+        raw_size >= MIN_SIZE_FOR_RATIO && raw_size / compressed_size.max(1) > self.max_ratio
+    }
+
+    /// Checks data that is being decompressed and returns the warning for the first violated limit.
+    /// `written` is the size of the output so far, `read` is the compressed size consumed for it.
+    pub fn check_stream(&self, written: u64, read: u64) -> Option<String> {
+        if written > self.max_total_size {
+            Some(format!("The file unpacks to more than {}.", crate::share::size_to_string(self.max_total_size as f64)))
+        } else if self.exceeds_ratio(written, read) {
+            Some(format!("Compression ratio above {}:1.", self.max_ratio))
+        } else {
+            None
+        }
+    }
+
+    /// Checks an entry before it is decompressed, after `unpacked` bytes of other entries,
+    /// and returns the warning for the first violated limit.
+    pub fn check_entry(&self, unpacked: u64, raw_size: u64, compressed_size: u64) -> Option<String> {
+        if unpacked.saturating_add(raw_size) > self.max_total_size {
+            Some(format!("The entries unpack to more than {}.", crate::share::size_to_string(self.max_total_size as f64)))
+        } else if self.exceeds_ratio(raw_size, compressed_size) {
+            Some(format!("Compression ratio above {}:1.", self.max_ratio))
+        } else {
+            None
+        }
+    }
+}
+
+/// Names of the first entries and the number of the others.
+fn list_names<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    let names: Vec<&str> = names.collect();
+    let mut listed = names.iter().take(LISTED_NAMES).copied().collect::<Vec<_>>().join(", ");
+    if names.len() > LISTED_NAMES {
+        listed.push_str(&format!(" and {} more", names.len() - LISTED_NAMES));
+    }
+    listed
+}
+
+/// Number and sizes of the entries of a tar or 7z archive, read from their headers.
+pub struct ListedSizes {
+    pub entries: usize,
+    pub total_size: u64,
+    /// Size of the archive file. Entries are compressed together,
+    /// so the ratio is checked for the whole archive.
+    pub compressed_size: u64,
+    /// Listing stopped at the first exceeded limit, the numbers are lower bounds.
+    pub is_partial: bool,
+}
+
+fn entries_warning(entries: usize, is_partial: bool, limits: &ArchiveLimits) -> Option<String> {
+    if entries <= limits.max_entries {
+        return None;
+    }
+    Some(if is_partial {
+        format!("The archive has more than {} entries.", limits.max_entries)
+    } else {
+        format!("The archive has {} entries, the limit is {}.", entries, limits.max_entries)
+    })
+}
+
+fn total_size_warning(total_size: u64, is_partial: bool, limits: &ArchiveLimits) -> Option<String> {
+    if total_size <= limits.max_total_size {
+        return None;
+    }
+    let limit = crate::share::size_to_string(limits.max_total_size as f64);
+    Some(if is_partial {
+        format!("The entries unpack to more than {}.", limit)
+    } else {
+        format!("The entries unpack to {}, the limit is {}.", crate::share::size_to_string(total_size as f64), limit)
+    })
+}
+
+/// Checks the sizes read from the headers of a tar or 7z archive against the limits
+/// and returns a warning for every violated limit.
+pub fn check_listed_sizes(sizes: &ListedSizes, limits: &ArchiveLimits) -> Vec<String> {
+    let mut warnings: Vec<String> = [
+        entries_warning(sizes.entries, sizes.is_partial, limits),
+        total_size_warning(sizes.total_size, sizes.is_partial, limits),
+    ].into_iter().flatten().collect();
+    if limits.exceeds_ratio(sizes.total_size, sizes.compressed_size) {
+        warnings.push(format!("Compression ratio of the archive above {}:1.", limits.max_ratio));
+    }
+    warnings
+}
+
+/// Checks a tar archive against the limits by the headers of its entries, before it is unpacked.
+/// The archive is decompressed to read the headers, but the data of the entries is not kept.
+pub fn check_tar_limits(
+    compression: TarCompression,
+    data: &[u8],
+    limits: &ArchiveLimits,
+) -> Result<Vec<String>, (String, String)> {
+    let listing = tar_tools::list_tar(compression.decompress(data), limits.max_entries, limits.max_total_size)?;
+    Ok(check_listed_sizes(&ListedSizes {
+        entries: listing.entries,
+        total_size: listing.total_size,
+        compressed_size: data.len() as u64,
+        is_partial: listing.is_partial,
+    }, limits))
+}
+
+/// Checks a 7z archive against the limits by its headers, before any entry is decoded.
+pub fn check_sevenz_limits(data: &[u8], password: &str, limits: &ArchiveLimits) -> Result<Vec<String>, (String, String)> {
+    let (entries, total_size) = crate::share::zip_tools::sevenz_listing(data, password)?;
+    Ok(check_listed_sizes(&ListedSizes {
+        entries,
+        total_size,
+        compressed_size: data.len() as u64,
+        is_partial: false,
+    }, limits))
+}
+
+/// Checks the central directory against the limits and returns a warning for every violated limit.
+/// Nothing is decompressed, so the check is safe for any archive.
+pub fn check_limits(archive: &OpenedArchive, limits: &ArchiveLimits) -> Vec<String> {
+    let total_size = archive.entries.iter().fold(0u64, |total, entry| total.saturating_add(entry.raw_size));
+    let mut warnings: Vec<String> = [
+        entries_warning(archive.entries.len(), false, limits),
+        total_size_warning(total_size, false, limits),
+    ].into_iter().flatten().collect();
+
+    let compressed_too_much: Vec<&str> = archive.entries.iter()
+        .filter(|entry| limits.exceeds_ratio(entry.raw_size, entry.compressed_size))
+        .map(|entry| entry.entry_name.as_str())
+        .collect();
+    if !compressed_too_much.is_empty() {
+        warnings.push(format!(
+            "Compression ratio above {}:1 in {}.",
+            limits.max_ratio,
+            list_names(compressed_too_much.into_iter())
+        ));
+    }
+
+    // Entries that share compressed data make a small archive unpack to a huge size.
+    let mut ranges: Vec<_> = archive.entries.iter()
+        .map(|entry| (entry.header_start, entry.data_start.saturating_add(entry.compressed_size), entry))
+        .collect();
+    ranges.sort_by_key(|(start, _, _)| *start);
+    let mut covered_until = 0;
+    let mut overlapping = vec![];
+    for (start, end, entry) in ranges {
+        if start < covered_until {
+            overlapping.push(entry.entry_name.as_str());
+        }
+        covered_until = covered_until.max(end);
+    }
+    if !overlapping.is_empty() {
+        warnings.push(format!(
+            "Entries overlap other entries, which is typical for zip bombs: {}.",
+            list_names(overlapping.into_iter())
+        ));
+    }
+    warnings
+}
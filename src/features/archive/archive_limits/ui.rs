@@ -0,0 +1,119 @@
+use yew::TargetCast;
+use crate::features::archive::archive_limits::model::ArchiveLimits;
+
+/// Bytes in a megabyte, the unit of the size limit in the settings.
+const MEGABYTE: u64 = 1024 * 1024;
+
+/// A limit edited in its input field.
+pub enum ArchiveLimitsMsg {
+    TotalSize(String),
+    Ratio(String),
+    EntryCount(String),
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct ArchiveLimitsProps {
+    pub limits: ArchiveLimits,
+    pub on_limits_change: yew::Callback<ArchiveLimits>,
+}
+
+/// Component - settings of the limits checked when an archive is opened.
+pub struct ArchiveLimitsComponent {}
+
+impl yew::Component for ArchiveLimitsComponent {
+    type Message = ArchiveLimitsMsg;
+    type Properties = ArchiveLimitsProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {}
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        let mut limits = ctx.props().limits;
+        match msg {
+            ArchiveLimitsMsg::TotalSize(value) => {
+                let Ok(megabytes) = value.trim().parse::<u64>() else { return true; };
+                limits.max_total_size = megabytes.saturating_mul(MEGABYTE);
+            }
+            ArchiveLimitsMsg::Ratio(value) => {
+                let Ok(ratio) = value.trim().parse::<u64>() else { return true; };
+                limits.max_ratio = ratio;
+            }
+            ArchiveLimitsMsg::EntryCount(value) => {
+                let Ok(entries) = value.trim().parse::<usize>() else { return true; };
+                limits.max_entries = entries;
+            }
+        }
+        ctx.props().on_limits_change.emit(limits);
+        false
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let limits = &ctx.props().limits;
+        let input_value = |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            input.value()
+        };
+        let onchange_size = &ctx.link().callback(move |e| ArchiveLimitsMsg::TotalSize(input_value(e)));
+        let onchange_ratio = &ctx.link().callback(move |e| ArchiveLimitsMsg::Ratio(input_value(e)));
+        let onchange_entries = &ctx.link().callback(move |e| ArchiveLimitsMsg::EntryCount(input_value(e)));
+        yew::html! {
+            <details>
+                <summary><small>{"Limits for opened archives"}</small></summary>
+                <div class={yew::classes!("input-group", "input-group-sm", "mt-1")}>
+                    <span class={yew::classes!("input-group-text")}>{"Total size, MB"}</span>
+                    <input
+                        class={yew::classes!("form-control")}
+                        type="number" min="0"
+                        aria-label="Maximum total uncompressed size in megabytes"
+                        value={(limits.max_total_size / MEGABYTE).to_string()}
+                        onchange={onchange_size}
+                    />
+                    <span class={yew::classes!("input-group-text")}>{"Ratio"}</span>
+                    <input
+                        class={yew::classes!("form-control")}
+                        type="number" min="1"
+                        aria-label="Maximum compression ratio of an entry"
+                        title="Checked for entries larger than 1 MB."
+                        value={limits.max_ratio.to_string()}
+                        onchange={onchange_ratio}
+                    />
+                    <span class={yew::classes!("input-group-text")}>{"Entries"}</span>
+                    <input
+                        class={yew::classes!("form-control")}
+                        type="number" min="0"
+                        aria-label="Maximum number of entries"
+                        value={limits.max_entries.to_string()}
+                        onchange={onchange_entries}
+                    />
+                </div>
+            </details>
+        }
+    }
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct LimitWarningsProps {
+    pub warnings: Vec<String>,
+    /// Callback when the user decides to work with the archive despite the warnings.
+    pub on_continue: yew::Callback<()>,
+}
+
+/// Component - warns about violated limits before anything is decompressed.
+#[yew::function_component(LimitWarningsComponent)]
+pub fn limit_warnings(props: &LimitWarningsProps) -> yew::Html {
+    let onclick = props.on_continue.reform(|_| ());
+    yew::html! {
+        <div class={yew::classes!("alert", "alert-warning", "mb-0")}>
+            <p class={yew::classes!("mb-1")}>
+                {"The archive exceeds the limits. Unpacking it may freeze the page."}
+            </p>
+            <ul class={yew::classes!("mb-2")}>
+                { props.warnings.iter().map(|warning| yew::html! { <li>{warning}</li> }).collect::<yew::Html>() }
+            </ul>
+            <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} {onclick}>
+                {"Continue anyway"}
+            </button>
+        </div>
+    }
+}
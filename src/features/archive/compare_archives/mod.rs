@@ -0,0 +1,6 @@
+//! Сравнение архива с другим архивом или с выбранными файлами.
+
+mod ui;
+mod model;
+
+pub use ui::CompareArchivesComponent;
@@ -0,0 +1,194 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::entities::file::model::InfoAboutSelectedFile;
+
+/// Number of unchanged lines shown around each change of a text diff.
+const CONTEXT_LINES: usize = 3;
+
+/// Where the data of a compared entry comes from.
+#[derive(PartialEq, Clone)]
+pub(crate) enum EntryContent {
+    /// Index of the entry in the archive of the side.
+    Archive(usize),
+    /// Contents of a file read from disk.
+    Loaded(std::rc::Rc<Vec<u8>>),
+}
+
+/// An entry of one side of the comparison.
+#[derive(PartialEq, Clone)]
+pub(crate) struct ComparedEntry {
+    pub name: String,
+    pub size: u64,
+    pub crc32: u32,
+    /// Local time of the last modification, with minute precision.
+    pub last_modified: String,
+    pub content: EntryContent,
+}
+
+/// One of the two compared sets of entries.
+#[derive(PartialEq, Clone)]
+pub(crate) struct DiffSide {
+    pub title: String,
+    pub archive: Option<std::rc::Rc<OpenedArchive>>,
+    pub entries: Vec<ComparedEntry>,
+}
+
+impl DiffSide {
+    pub fn from_archive(archive: std::rc::Rc<OpenedArchive>) -> Self {
+        let entries = archive.entries.iter()
+            .filter(|entry| !entry.is_dir)
+            .map(|entry| ComparedEntry {
+                name: entry.entry_name.to_string(),
+                size: entry.raw_size,
+                crc32: entry.crc32,
+                last_modified: entry.last_modified.to_string(),
+                content: EntryContent::Archive(entry.ind),
+            })
+            .collect();
+        Self { title: archive.file_name.to_string(), archive: Some(archive), entries }
+    }
+
+    /// Files selected by the user, with their contents already read from disk.
+    ///
+    /// The files are named after the files at the root of the archive they match,
+    /// so that a file compressed as `{ind}-{name}` is compared with its entry.
+    pub fn from_files(archive: &OpenedArchive, files: Vec<(std::rc::Rc<InfoAboutSelectedFile>, Vec<u8>)>) -> Self {
+        let entry_names: Vec<&str> = archive.entries.iter()
+            .filter(|entry| !entry.is_dir && !entry.entry_name.contains('/'))
+            .map(|entry| entry.entry_name.as_str())
+            .collect();
+        let file_names: Vec<&str> = files.iter().map(|(file, _)| file.file_name.as_str()).collect();
+        let names: Vec<String> = crate::share::match_names(&entry_names, &file_names).into_iter()
+            .zip(&file_names)
+            .map(|(ind, file_name)| ind.map_or(*file_name, |ind| entry_names[ind]).to_string())
+            .collect();
+        let entries = files.into_iter().zip(names)
+            .map(|((file, data), name)| {
+                let last_modified: chrono::DateTime<chrono::Local> = file.js_file_obj.last_modified_time().into();
+                ComparedEntry {
+                    name,
+                    size: data.len() as u64,
+                    crc32: crc32fast::hash(&data),
+                    last_modified: last_modified.format("%Y-%m-%d %H:%M").to_string(),
+                    content: EntryContent::Loaded(std::rc::Rc::new(data)),
+                }
+            })
+            .collect();
+        Self { title: "Selected files".to_string(), archive: None, entries }
+    }
+
+    /// Decompressed data of the entry.
+    pub fn read(&self, entry: &ComparedEntry, password: &str) -> Result<Vec<u8>, (String, String)> {
+        match (&entry.content, &self.archive) {
+            (EntryContent::Loaded(data), _) => Ok(data.to_vec()),
+            (EntryContent::Archive(ind), Some(archive)) => {
+                let info = archive.entries.get(*ind).ok_or((
+                    format!("Entry not found: {}.", entry.name),
+                    "".to_string(),
+                ))?;
+                archive.read_entry(info, password)
+            }
+            (EntryContent::Archive(_), None) => Err((format!("Entry not found: {}.", entry.name), "".to_string())),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) enum DiffStatus {
+    /// Only on the right side.
+    Added,
+    /// Only on the left side.
+    Removed,
+    Changed {
+        size: bool,
+        crc32: bool,
+        last_modified: bool,
+    },
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct EntryDiff {
+    pub name: String,
+    pub status: DiffStatus,
+    pub left: Option<ComparedEntry>,
+    pub right: Option<ComparedEntry>,
+}
+
+/// Lists the entries that differ between the sides, sorted by path.
+pub(crate) fn compare(left: &DiffSide, right: &DiffSide) -> Vec<EntryDiff> {
+    let left_entries: std::collections::BTreeMap<&str, &ComparedEntry> = left.entries.iter()
+        .map(|entry| (entry.name.as_str(), entry))
+        .collect();
+    let right_entries: std::collections::BTreeMap<&str, &ComparedEntry> = right.entries.iter()
+        .map(|entry| (entry.name.as_str(), entry))
+        .collect();
+    let names: std::collections::BTreeSet<&str> = left_entries.keys().chain(right_entries.keys()).copied().collect();
+
+    names.into_iter().filter_map(|name| {
+        let left = left_entries.get(name).copied();
+        let right = right_entries.get(name).copied();
+        let status = match (left, right) {
+            (None, Some(_)) => DiffStatus::Added,
+            (Some(_), None) => DiffStatus::Removed,
+            (Some(left), Some(right)) => {
+                let status = DiffStatus::Changed {
+                    size: left.size != right.size,
+                    crc32: left.crc32 != right.crc32,
+                    last_modified: left.last_modified != right.last_modified,
+                };
+                if status == (DiffStatus::Changed { size: false, crc32: false, last_modified: false }) {
+                    return None;
+                }
+                status
+            }
+            (None, None) => return None,
+        };
+        Some(EntryDiff { name: name.to_string(), status, left: left.cloned(), right: right.cloned() })
+    }).collect()
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub(crate) enum DiffLineTag {
+    /// Header of a group of changes.
+    Hunk,
+    Equal,
+    Delete,
+    Insert,
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct DiffLine {
+    pub tag: DiffLineTag,
+    pub text: String,
+}
+
+/// Line diff of two text entries, or `None` if one of them is binary.
+pub(crate) fn text_diff(left: &[u8], right: &[u8]) -> Option<Vec<DiffLine>> {
+    let (left, _) = crate::share::text_tools::decode_text(left)?;
+    let (right, _) = crate::share::text_tools::decode_text(right)?;
+    let diff = similar::TextDiff::from_lines(&left, &right);
+
+    let mut lines = vec![];
+    for group in diff.grouped_ops(CONTEXT_LINES) {
+        let (Some(first), Some(last)) = (group.first(), group.last()) else { continue; };
+        let old_range = first.old_range().start..last.old_range().end;
+        let new_range = first.new_range().start..last.new_range().end;
+        lines.push(DiffLine {
+            tag: DiffLineTag::Hunk,
+            text: format!(
+                "@@ -{},{} +{},{} @@",
+                old_range.start + 1, old_range.len(), new_range.start + 1, new_range.len()
+            ),
+        });
+        for op in group.iter() {
+            for change in diff.iter_changes(op) {
+                let tag = match change.tag() {
+                    similar::ChangeTag::Equal => DiffLineTag::Equal,
+                    similar::ChangeTag::Delete => DiffLineTag::Delete,
+                    similar::ChangeTag::Insert => DiffLineTag::Insert,
+                };
+                lines.push(DiffLine { tag, text: change.value().trim_end_matches(['\r', '\n']).to_string() });
+            }
+        }
+    }
+    Some(lines)
+}
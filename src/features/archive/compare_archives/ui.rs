@@ -0,0 +1,294 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::entities::file::model::InfoAboutSelectedFile;
+use crate::features::archive::archive_limits::ArchiveLimits;
+use crate::features::archive::compare_archives::model::{self, DiffLineTag, DiffSide, DiffStatus, EntryDiff};
+
+/// Text diff of an entry, `None` for binary data.
+type TextDiffResult = Result<Option<Vec<model::DiffLine>>, (String, String)>;
+
+pub enum CompareArchivesMsg {
+    ChooseSource,
+    CompareWithArchive(OpenedArchive),
+    CompareWithSelection,
+    /// A selected file has been read from disk.
+    LoadedFile(usize, Vec<u8>),
+    FailedCompare((String, String)),
+    ShowTextDiff(usize),
+    Close,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct CompareArchivesProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    /// Password entered for the archive, also used for the compared archive.
+    pub password: yew::AttrValue,
+    /// Files currently selected for a new archive.
+    pub selected_files: Vec<std::rc::Rc<InfoAboutSelectedFile>>,
+    /// Limits checked before the entries of the compared archive are read.
+    pub limits: ArchiveLimits,
+}
+
+/// Component - lists the differences between the archive and another archive or the selected files.
+pub struct CompareArchivesComponent {
+    is_choosing: bool,
+    right: Option<DiffSide>,
+    diffs: Vec<EntryDiff>,
+    /// Index of the entry difference and its text diff.
+    text_diff: Option<(usize, TextDiffResult)>,
+    file_reading_tasks: std::collections::HashMap<usize, gloo_file::callbacks::FileReader>,
+    loaded_files: std::collections::HashMap<usize, Vec<u8>>,
+    err_msg: Option<(String, String)>,
+}
+
+impl CompareArchivesComponent {
+    fn set_right(&mut self, ctx: &yew::Context<Self>, right: DiffSide) {
+        let left = DiffSide::from_archive(ctx.props().archive.clone());
+        self.diffs = model::compare(&left, &right);
+        self.right = Some(right);
+        self.text_diff = None;
+        self.is_choosing = false;
+    }
+
+    fn view_status(diff: &EntryDiff) -> yew::Html {
+        match &diff.status {
+            DiffStatus::Added => yew::html! { <span class={yew::classes!("badge", "bg-success")}>{"Added"}</span> },
+            DiffStatus::Removed => yew::html! { <span class={yew::classes!("badge", "bg-danger")}>{"Removed"}</span> },
+            DiffStatus::Changed { size, crc32, last_modified } => {
+                let (Some(left), Some(right)) = (&diff.left, &diff.right) else { return yew::html! {}; };
+                let mut details = vec![];
+                if *size {
+                    details.push(format!(
+                        "size {} → {}",
+                        crate::share::size_to_string(left.size as f64),
+                        crate::share::size_to_string(right.size as f64)
+                    ));
+                }
+                if *crc32 {
+                    details.push(format!("CRC32 {:08x} → {:08x}", left.crc32, right.crc32));
+                }
+                if *last_modified {
+                    details.push(format!("modified {} → {}", left.last_modified, right.last_modified));
+                }
+                yew::html! {
+                    <span>
+                        <small class={yew::classes!("text-muted", "me-2")}>{details.join(", ")}</small>
+                        <span class={yew::classes!("badge", "bg-warning", "text-dark")}>{"Changed"}</span>
+                    </span>
+                }
+            }
+        }
+    }
+
+    fn view_text_diff(&self, ind: usize) -> yew::Html {
+        let Some((shown, text_diff)) = &self.text_diff else { return yew::html! {}; };
+        if *shown != ind {
+            return yew::html! {};
+        }
+        match text_diff {
+            Err((msg, detail)) => yew::html! {
+                <small class={yew::classes!("text-danger")} title={detail.clone()}>{msg}</small>
+            },
+            Ok(None) => yew::html! {
+                <small class={yew::classes!("text-muted")}>{"Binary entries differ."}</small>
+            },
+            Ok(Some(lines)) => yew::html! {
+                <pre class={yew::classes!("border", "bg-white", "p-2", "mt-1", "mb-0", "overflow-auto")} style="max-height: 40vh;">
+                    { lines.iter().map(|line| {
+                        let (class, prefix) = match line.tag {
+                            DiffLineTag::Hunk => ("text-primary", ""),
+                            DiffLineTag::Equal => ("", " "),
+                            DiffLineTag::Delete => ("text-danger", "-"),
+                            DiffLineTag::Insert => ("text-success", "+"),
+                        };
+                        yew::html! { <div class={yew::classes!(class)}>{format!("{prefix}{}", line.text)}</div> }
+                    }).collect::<yew::Html>() }
+                </pre>
+            },
+        }
+    }
+}
+
+impl yew::Component for CompareArchivesComponent {
+    type Message = CompareArchivesMsg;
+    type Properties = CompareArchivesProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            is_choosing: false,
+            right: None,
+            diffs: vec![],
+            text_diff: None,
+            file_reading_tasks: std::collections::HashMap::new(),
+            loaded_files: std::collections::HashMap::new(),
+            err_msg: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            CompareArchivesMsg::ChooseSource => {
+                self.is_choosing = true;
+                self.err_msg = None;
+                true
+            }
+            CompareArchivesMsg::CompareWithArchive(archive) => {
+                self.set_right(ctx, DiffSide::from_archive(std::rc::Rc::new(archive)));
+                true
+            }
+            CompareArchivesMsg::CompareWithSelection => {
+                self.loaded_files = std::collections::HashMap::new();
+                let callback_loaded_file = ctx.link()
+                    .callback(|(ind, _, data)| CompareArchivesMsg::LoadedFile(ind, data));
+                let callback_filed = ctx.link().callback(CompareArchivesMsg::FailedCompare);
+                for (ind, file) in ctx.props().selected_files.iter().enumerate() {
+                    let task = crate::share::fs_tools::read_file(
+                        ind,
+                        file.file_name.clone(),
+                        &file.js_file_obj,
+                        callback_loaded_file.clone(),
+                        callback_filed.clone(),
+                    );
+                    self.file_reading_tasks.insert(ind, task);
+                }
+                true
+            }
+            CompareArchivesMsg::LoadedFile(ind, data) => {
+                self.file_reading_tasks.remove(&ind);
+                self.loaded_files.insert(ind, data);
+                let selected_files = &ctx.props().selected_files;
+                if self.file_reading_tasks.is_empty() && self.loaded_files.len() == selected_files.len() {
+                    let files: Vec<_> = selected_files.iter().enumerate()
+                        .map(|(ind, file)| (file.clone(), self.loaded_files.remove(&ind).unwrap_or_default()))
+                        .collect();
+                    self.set_right(ctx, DiffSide::from_files(&ctx.props().archive, files));
+                }
+                true
+            }
+            CompareArchivesMsg::FailedCompare(err) => {
+                self.file_reading_tasks = std::collections::HashMap::new();
+                self.loaded_files = std::collections::HashMap::new();
+                self.err_msg = Some(err);
+                true
+            }
+            CompareArchivesMsg::ShowTextDiff(ind) => {
+                let (Some(right), Some(diff)) = (&self.right, self.diffs.get(ind)) else { return false; };
+                let (Some(left_entry), Some(right_entry)) = (&diff.left, &diff.right) else { return false; };
+                let password = ctx.props().password.as_str();
+                let left = DiffSide::from_archive(ctx.props().archive.clone());
+                let text_diff = left.read(left_entry, password)
+                    .and_then(|left_data| Ok((left_data, right.read(right_entry, password)?)))
+                    .map(|(left_data, right_data)| model::text_diff(&left_data, &right_data));
+                self.text_diff = Some((ind, text_diff));
+                true
+            }
+            CompareArchivesMsg::Close => {
+                self.is_choosing = false;
+                self.right = None;
+                self.diffs = vec![];
+                self.text_diff = None;
+                self.err_msg = None;
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, ctx: &yew::Context<Self>, old_props: &Self::Properties) -> bool {
+        if ctx.props().archive != old_props.archive {
+            if let Some(right) = self.right.take() {
+                self.set_right(ctx, right);
+            }
+        }
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let close_onclick = &ctx.link().callback(|_| CompareArchivesMsg::Close);
+        let err = match &self.err_msg {
+            Some((msg, detail)) => yew::html! {
+                <div
+                    class={yew::classes!("alert", "alert-danger", "mt-2", "mb-0")}
+                    data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                    title={detail.clone()}
+                >{format!("Error: {msg}")}
+                </div>
+            },
+            None => yew::html! {},
+        };
+
+        if !self.file_reading_tasks.is_empty() {
+            return yew::html! {
+                <p class={yew::classes!("mb-0")}>
+                    {format!("Reading files: {}/{}", self.loaded_files.len(), ctx.props().selected_files.len())}
+                </p>
+            };
+        }
+
+        let Some(right) = &self.right else {
+            if !self.is_choosing {
+                let onclick = &ctx.link().callback(|_| CompareArchivesMsg::ChooseSource);
+                return yew::html! {
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} {onclick}>
+                        {"Compare..."}
+                    </button>
+                };
+            }
+            let on_archive_opened = &ctx.link().callback(CompareArchivesMsg::CompareWithArchive);
+            let selection_onclick = &ctx.link().callback(|_| CompareArchivesMsg::CompareWithSelection);
+            let selected_count = ctx.props().selected_files.len();
+            return yew::html! {
+                <div class={yew::classes!("d-flex", "flex-column")}>
+                    <div class={yew::classes!("d-flex", "align-items-center", "gap-2")}>
+                        <crate::features::archive::open_archive::OpenArchiveComponent {on_archive_opened} limits={ctx.props().limits} />
+                        <button
+                            type="button"
+                            class={yew::classes!("btn", "btn-outline-dark")}
+                            disabled={selected_count == 0}
+                            onclick={selection_onclick}
+                        >
+                            {format!("Selected files ({selected_count})")}
+                        </button>
+                        <button type="button" class={yew::classes!("btn", "btn-outline-dark")} onclick={close_onclick}>
+                            {"Cancel"}
+                        </button>
+                    </div>
+                    {err}
+                </div>
+            };
+        };
+
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center", "mb-1")}>
+                    <span class={yew::classes!("text-truncate")}>
+                        {format!("{} ↔ {}: {} difference(s)", ctx.props().archive.file_name, right.title, self.diffs.len())}
+                    </span>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={close_onclick}>
+                        {"Close"}
+                    </button>
+                </div>
+                <div class={yew::classes!("list-group", "overflow-auto")} style="max-height: 50vh;">
+                    { self.diffs.iter().enumerate().map(|(ind, diff)| {
+                        let onclick = ctx.link().callback(move |_| CompareArchivesMsg::ShowTextDiff(ind));
+                        yew::html! {
+                            <div class={yew::classes!("list-group-item", "p-1")} key={diff.name.clone()}>
+                                <div class={yew::classes!("d-flex", "w-100", "justify-content-between", "align-items-center")}>
+                                    <small class={yew::classes!("text-truncate")}>{&diff.name}</small>
+                                    <span class={yew::classes!("text-nowrap")}>
+                                        {Self::view_status(diff)}
+                                        if matches!(diff.status, DiffStatus::Changed { .. }) {
+                                            <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "ms-2")} {onclick}>
+                                                {"Text diff"}
+                                            </button>
+                                        }
+                                    </span>
+                                </div>
+                                {self.view_text_diff(ind)}
+                            </div>
+                        }
+                    }).collect::<yew::Html>() }
+                </div>
+                {err}
+            </div>
+        }
+    }
+}
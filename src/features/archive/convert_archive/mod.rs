@@ -0,0 +1,6 @@
+//! Преобразование архивов между форматами zip и tar.
+
+mod ui;
+mod model;
+
+pub use ui::ConvertArchiveComponent;
@@ -0,0 +1,170 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::archive_limits::{self, ArchiveLimits};
+use crate::share::tar_tools::TarCompression;
+use crate::share::zip_tools::{self, TypeEncryption};
+
+/// Unix file type bits of the mode.
+const FILE_TYPE_MASK: u32 = 0o170000;
+const SYMLINK_TYPE: u32 = 0o120000;
+
+/// Format of a whole archive file.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub(crate) enum ArchiveFormat {
+    Zip,
+    Tar(TarCompression),
+}
+
+impl ArchiveFormat {
+    pub const ALL: [Self; 6] = [
+        Self::Zip,
+        Self::Tar(TarCompression::None),
+        Self::Tar(TarCompression::Gzip),
+        Self::Tar(TarCompression::Bzip2),
+        Self::Tar(TarCompression::Xz),
+        Self::Tar(TarCompression::Zstd),
+    ];
+
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        if file_name.to_ascii_lowercase().ends_with(".zip") {
+            Some(Self::Zip)
+        } else {
+            TarCompression::from_file_name(file_name).map(Self::Tar)
+        }
+    }
+
+    fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::Zip => &[".zip"],
+            Self::Tar(compression) => compression.extensions(),
+        }
+    }
+
+    /// Name of the format shown in the list of targets.
+    pub fn label(&self) -> String {
+        match self {
+            // ruzstd compresses only with its fastest level, the files are larger than from `zstd`.
+            Self::Tar(TarCompression::Zstd) => format!("{self} (fastest)"),
+            _ => self.to_string(),
+        }
+    }
+
+    /// Name of the converted archive: the extension of the source replaced with the new one.
+    pub fn file_name_for(&self, source_name: &str, source_format: ArchiveFormat) -> String {
+        let lowercase = source_name.to_ascii_lowercase();
+        let stem = source_format.extensions().iter()
+            .find(|extension| lowercase.ends_with(*extension))
+            .map(|extension| &source_name[..source_name.len() - extension.len()])
+            .unwrap_or(source_name);
+        format!("{stem}{}", self.extensions()[0])
+    }
+}
+
+impl std::fmt::Display for ArchiveFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Zip => write!(f, "zip"),
+            Self::Tar(compression) => write!(f, "{compression}"),
+        }
+    }
+}
+
+impl From<String> for ArchiveFormat {
+    fn from(value: String) -> Self {
+        Self::ALL.into_iter()
+            .find(|format| format.to_string() == value)
+            .unwrap_or(Self::Zip)
+    }
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct ConvertOptions {
+    /// Password of the encrypted entries of a source ZIP archive.
+    pub password: String,
+    /// Password for the entries of a new ZIP archive, empty to leave them unencrypted.
+    pub new_password: String,
+    pub type_encryption: TypeEncryption,
+}
+
+/// Writes the entries of a ZIP archive into a tar archive, keeping paths, times and modes.
+fn zip_to_tar(archive: &OpenedArchive, password: &str) -> Result<Vec<u8>, (String, String)> {
+    let write_err = |err: std::io::Error| ("Failed to write tar archive.".to_string(), format!("{}", err));
+    let mut builder = tar::Builder::new(Vec::with_capacity(archive.data().len()));
+    for entry in &archive.entries {
+        let mut zip = archive.zip();
+        let mut file = entry.open(&mut zip, password)?;
+        let mode = file.unix_mode();
+        let mut header = tar::Header::new_gnu();
+        if let Some(mtime) = zip_tools::unix_time(file.last_modified()) {
+            header.set_mtime(mtime);
+        }
+
+        if entry.is_dir {
+            header.set_entry_type(tar::EntryType::Directory);
+            header.set_mode(mode.unwrap_or(0o755) & 0o7777);
+            header.set_size(0);
+            builder.append_data(&mut header, entry.entry_name.as_str(), std::io::empty()).map_err(write_err)?;
+        } else if mode.is_some_and(|mode| mode & FILE_TYPE_MASK == SYMLINK_TYPE) {
+            // The target of a symbolic link is stored as the entry data.
+            let mut target = String::new();
+            std::io::Read::read_to_string(&mut file, &mut target).map_err(|err| entry.describe_read_error(err))?;
+            header.set_entry_type(tar::EntryType::Symlink);
+            header.set_mode(mode.unwrap_or(0o777) & 0o7777);
+            header.set_size(0);
+            builder.append_link(&mut header, entry.entry_name.as_str(), target).map_err(write_err)?;
+        } else {
+            header.set_entry_type(tar::EntryType::Regular);
+            header.set_mode(mode.unwrap_or(0o644) & 0o7777);
+            header.set_size(entry.raw_size);
+            builder.append_data(&mut header, entry.entry_name.as_str(), &mut file)
+                .map_err(|err| entry.describe_read_error(err))?;
+        }
+    }
+    builder.into_inner().map_err(write_err)
+}
+
+/// Checks the archive against the limits before it is converted: a ZIP archive by its
+/// central directory, a tar archive by the headers of its entries.
+pub(crate) fn check_limits(
+    file_name: &yew::AttrValue,
+    data: &std::rc::Rc<[u8]>,
+    source: ArchiveFormat,
+    limits: &ArchiveLimits,
+) -> Result<Vec<String>, (String, String)> {
+    match source {
+        ArchiveFormat::Zip => {
+            let archive = OpenedArchive::from_shared_bytes(file_name.clone(), data.clone())?;
+            Ok(archive_limits::check_limits(&archive, limits))
+        }
+        ArchiveFormat::Tar(compression) => archive_limits::check_tar_limits(compression, data, limits),
+    }
+}
+
+/// Converts the archive into another format.
+pub(crate) fn convert_archive(
+    file_name: &yew::AttrValue,
+    data: std::rc::Rc<[u8]>,
+    source: ArchiveFormat,
+    target: ArchiveFormat,
+    options: &ConvertOptions,
+) -> Result<Vec<u8>, (String, String)> {
+    match (source, target) {
+        (ArchiveFormat::Zip, ArchiveFormat::Zip) => Ok(data.to_vec()),
+        (ArchiveFormat::Zip, ArchiveFormat::Tar(compression)) => {
+            let archive = OpenedArchive::from_shared_bytes(file_name.clone(), data)?;
+            compression.compress(zip_to_tar(&archive, &options.password)?)
+        }
+        (ArchiveFormat::Tar(compression), ArchiveFormat::Zip) => {
+            let file_options = zip_tools::file_options(&options.new_password, options.type_encryption)
+                .compression_method(zip::CompressionMethod::Deflated);
+            zip_tools::tar_to_zip(compression.decompress(&data), file_options)
+        }
+        (ArchiveFormat::Tar(source), ArchiveFormat::Tar(target)) => {
+            let mut tar = vec![];
+            std::io::copy(&mut source.decompress(&data), &mut tar).map_err(|err| (
+                format!("Failed to decompress {} archive.", source),
+                format!("{}", err)
+            ))?;
+            target.compress(tar)
+        }
+    }
+}
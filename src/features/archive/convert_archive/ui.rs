@@ -0,0 +1,357 @@
+use yew::TargetCast;
+use crate::features::archive::archive_limits::{ArchiveLimits, LimitWarningsComponent};
+use crate::features::archive::convert_archive::model::{self, ArchiveFormat, ConvertOptions};
+use crate::share::zip_tools::TypeEncryption;
+
+/// Extensions of the archives that can be converted.
+const ACCEPTED_EXTENSIONS: &str = ".zip,.tar,.tar.gz,.tgz,.tar.bz2,.tbz2,.tbz,.tar.xz,.txz,.tar.zst,.tzst";
+
+/// State of the conversion process.
+#[derive(PartialEq)]
+enum ConvertingState {
+    /// Waiting for the user to select an archive.
+    WaitFile,
+    /// Waiting for the user to choose the target format.
+    WaitConfirm,
+    /// The archive is being read and converted.
+    InProcess,
+    /// The archive exceeds the limits, waiting for the user to convert it anyway.
+    WaitAccept,
+    Done,
+    Fail,
+}
+
+pub enum ConvertArchiveMsg {
+    /// Archive selected by the user.
+    Select(gloo_file::FileList),
+    ChangeTarget(String),
+    EditPassword(String),
+    EditNewPassword(String),
+    ChangeTypeEncryption(String),
+    StartConvert,
+    /// The archive has been read from disk.
+    LoadedFile(yew::AttrValue, Vec<u8>),
+    FailedConvert((String, String)),
+    /// The user decided to convert the archive despite the violated limits.
+    AcceptWarnings,
+    Reset,
+    Pass,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct ConvertArchiveProps {
+    /// Limits checked before the entries of the archive are decompressed.
+    pub limits: ArchiveLimits,
+}
+
+/// Component - converts an archive selected by the user between ZIP and tar formats.
+pub struct ConvertArchiveComponent {
+    state: ConvertingState,
+    file: Option<gloo_file::File>,
+    source: ArchiveFormat,
+    target: ArchiveFormat,
+    options: ConvertOptions,
+    file_reading_task: Option<gloo_file::callbacks::FileReader>,
+    /// Name, contents and violated limits of an archive that is not converted until the user accepts them.
+    limited_archive: Option<(yew::AttrValue, std::rc::Rc<[u8]>, Vec<String>)>,
+    err_msg: (String, String),
+    result_name: String,
+    blob_result: Option<gloo_file::Blob>,
+}
+
+impl ConvertArchiveComponent {
+    fn get_obj_url_with_result(&self) -> Option<String> {
+        let blob = self.blob_result.as_ref()?;
+        let download_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok()?;
+        Some(download_url)
+    }
+
+    fn file_name(&self) -> String {
+        self.file.as_ref().map(|file| file.name()).unwrap_or_default()
+    }
+
+    /// Converts the archive. Unless the limits are accepted, the archive is checked
+    /// against them first and is kept aside if they are violated.
+    fn convert(&mut self, ctx: &yew::Context<Self>, file_name: yew::AttrValue, data: std::rc::Rc<[u8]>, accepted: bool) {
+        if !accepted {
+            match model::check_limits(&file_name, &data, self.source, &ctx.props().limits) {
+                Ok(warnings) if !warnings.is_empty() => {
+                    self.state = ConvertingState::WaitAccept;
+                    self.limited_archive = Some((file_name, data, warnings));
+                    return;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    ctx.link().send_message(ConvertArchiveMsg::FailedConvert(err));
+                    return;
+                }
+            }
+        }
+        match model::convert_archive(&file_name, data, self.source, self.target, &self.options) {
+            Ok(data) => {
+                self.state = ConvertingState::Done;
+                self.result_name = self.target.file_name_for(&file_name, self.source);
+                self.blob_result = Some(gloo_file::Blob::new(data.as_slice()));
+            }
+            Err(err) => {
+                ctx.link().send_message(ConvertArchiveMsg::FailedConvert(err));
+            }
+        }
+    }
+
+    fn view_options(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let onchange_target = &ctx.link().callback(move |e: web_sys::Event| {
+            let select: web_sys::HtmlInputElement = e.target_unchecked_into();
+            ConvertArchiveMsg::ChangeTarget(select.value())
+        });
+        let onchange_password = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            ConvertArchiveMsg::EditPassword(input.value())
+        });
+        let onchange_new_password = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            ConvertArchiveMsg::EditNewPassword(input.value())
+        });
+        let onchange_type = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            ConvertArchiveMsg::ChangeTypeEncryption(input.value())
+        });
+        let reset_onclick = &ctx.link().callback(|_| ConvertArchiveMsg::Reset);
+        let start_onclick = &ctx.link().callback(|_| ConvertArchiveMsg::StartConvert);
+        let type_encryption = self.options.type_encryption;
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "w-100", "gap-1")}>
+                <small class={yew::classes!("text-truncate")}>
+                    {format!("{} ({})", self.file_name(), self.source)}
+                </small>
+                <div class={yew::classes!("input-group", "input-group-sm")}>
+                    <span class={yew::classes!("input-group-text")}>{"Convert to"}</span>
+                    <select class={yew::classes!("form-select")} style="max-width: 135px;" onchange={onchange_target}>
+                        {for ArchiveFormat::ALL.into_iter().filter(|format| *format != self.source).map(|format| yew::html! {
+                            <option selected={format == self.target} value={format.to_string()}>{format.label()}</option>
+                        })}
+                    </select>
+                </div>
+                if self.source == ArchiveFormat::Zip {
+                    <input
+                        class={yew::classes!("form-control", "form-control-sm")}
+                        type="password"
+                        placeholder="Password of the archive (optional)" aria-label="Password of the archive"
+                        value={self.options.password.clone()}
+                        onchange={onchange_password}
+                    />
+                }
+                if self.target == ArchiveFormat::Zip {
+                    <div class={yew::classes!("input-group", "input-group-sm")}>
+                        <select class={yew::classes!("form-select", "form-select-sm")}
+                            style="max-width: 135px;"
+                            onchange={onchange_type}
+                        >
+                            <option selected={type_encryption == TypeEncryption::ZipCrypto} value={"ZipCrypto"}>{"ZipCrypto"}</option>
+                            <option selected={type_encryption == TypeEncryption::Aes256} value={"Aes256"}>{"Aes256"}</option>
+                        </select>
+                        <input
+                            class={yew::classes!("form-control")}
+                            type="password"
+                            placeholder="Password for the new archive (optional)" aria-label="Password for the new archive"
+                            value={self.options.new_password.clone()}
+                            onchange={onchange_new_password}
+                        />
+                    </div>
+                }
+                <div class={yew::classes!("d-flex", "justify-content-end")}>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")} onclick={reset_onclick}>
+                        {"Cancel"}
+                    </button>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={start_onclick}>
+                        {format!("Convert to {}", self.target.label())}
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl yew::Component for ConvertArchiveComponent {
+    type Message = ConvertArchiveMsg;
+    type Properties = ConvertArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            state: ConvertingState::WaitFile,
+            file: None,
+            source: ArchiveFormat::Zip,
+            target: ArchiveFormat::Zip,
+            options: ConvertOptions {
+                password: String::new(),
+                new_password: String::new(),
+                type_encryption: TypeEncryption::Aes256,
+            },
+            file_reading_task: None,
+            limited_archive: None,
+            err_msg: ("".to_string(), "".to_string()),
+            result_name: String::new(),
+            blob_result: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ConvertArchiveMsg::Select(file_list) => {
+                let Some(file) = file_list.first().cloned() else { return false; };
+                match ArchiveFormat::from_file_name(&file.name()) {
+                    Some(source) => {
+                        self.source = source;
+                        self.target = ArchiveFormat::ALL.into_iter()
+                            .find(|format| *format != source)
+                            .unwrap_or(ArchiveFormat::Zip);
+                        self.file = Some(file);
+                        self.state = ConvertingState::WaitConfirm;
+                    }
+                    None => {
+                        ctx.link().send_message(ConvertArchiveMsg::FailedConvert((
+                            format!("Unsupported archive format: {}.", file.name()),
+                            format!("Supported extensions: {}.", ACCEPTED_EXTENSIONS),
+                        )));
+                    }
+                }
+                true
+            }
+            ConvertArchiveMsg::ChangeTarget(value) => {
+                self.target = ArchiveFormat::from(value);
+                true
+            }
+            ConvertArchiveMsg::EditPassword(password) => {
+                self.options.password = password.trim().to_string();
+                true
+            }
+            ConvertArchiveMsg::EditNewPassword(password) => {
+                self.options.new_password = password.trim().to_string();
+                true
+            }
+            ConvertArchiveMsg::ChangeTypeEncryption(value) => {
+                self.options.type_encryption = TypeEncryption::from(value);
+                false
+            }
+            ConvertArchiveMsg::StartConvert => {
+                let Some(file) = self.file.as_ref() else { return false; };
+                self.state = ConvertingState::InProcess;
+                let callback_loaded_file = ctx.link()
+                    .callback(|(_, file_name, data)| ConvertArchiveMsg::LoadedFile(file_name, data));
+                let callback_failed = ctx.link().callback(ConvertArchiveMsg::FailedConvert);
+                self.file_reading_task = Some(crate::share::fs_tools::read_file(
+                    0,
+                    yew::AttrValue::from(file.name()),
+                    file,
+                    callback_loaded_file,
+                    callback_failed,
+                ));
+                true
+            }
+            ConvertArchiveMsg::LoadedFile(file_name, data) => {
+                if self.state != ConvertingState::InProcess { return false; }
+                self.file_reading_task = None;
+                self.convert(ctx, file_name, std::rc::Rc::from(data), false);
+                true
+            }
+            ConvertArchiveMsg::FailedConvert(err) => {
+                self.state = ConvertingState::Fail;
+                self.err_msg = err;
+                self.file_reading_task = None;
+                true
+            }
+            ConvertArchiveMsg::AcceptWarnings => {
+                let Some((file_name, data, _)) = self.limited_archive.take() else { return false; };
+                self.state = ConvertingState::InProcess;
+                self.convert(ctx, file_name, data, true);
+                true
+            }
+            ConvertArchiveMsg::Reset => {
+                self.state = ConvertingState::WaitFile;
+                self.file = None;
+                self.limited_archive = None;
+                self.blob_result = None;
+                true
+            }
+            ConvertArchiveMsg::Pass => { false }
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let reset_onclick = &ctx.link().callback(|_| ConvertArchiveMsg::Reset);
+        match self.state {
+            ConvertingState::WaitFile => {
+                let onchange = &ctx.link().callback(move |e: web_sys::Event| {
+                    let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                    if let Some(fl) = input.files() {
+                        ConvertArchiveMsg::Select(fl.into())
+                    } else { ConvertArchiveMsg::Pass }
+                });
+                yew::html! {
+                    <span class="btn btn-sm btn-outline-dark"
+                          style="position: relative; overflow: hidden;"
+                    >
+                        {"Convert archive"}
+                        <input
+                            style="position: absolute; top: 0; right: 0; min-width: 100%; min-height: 100%; font-size: 100px; text-align: right; filter: alpha(opacity=0); opacity: 0; outline: none; cursor: inherit; display: block;"
+                            type="file"
+                            accept={ACCEPTED_EXTENSIONS}
+                            {onchange}
+                        />
+                    </span>
+                }
+            }
+            ConvertingState::WaitConfirm => self.view_options(ctx),
+            ConvertingState::InProcess => {
+                yew::html! {
+                    <p class={yew::classes!("mb-0")}>{format!("Converting: {}", self.file_name())}</p>
+                }
+            }
+            ConvertingState::WaitAccept => {
+                let on_continue = &ctx.link().callback(|_| ConvertArchiveMsg::AcceptWarnings);
+                let warnings = self.limited_archive.as_ref().map(|(_, _, warnings)| warnings.clone()).unwrap_or_default();
+                yew::html! {
+                    <div class={yew::classes!("d-flex", "flex-column", "w-100", "gap-1")}>
+                        <LimitWarningsComponent {warnings} {on_continue} />
+                        <div>
+                            <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                                {"Cancel"}
+                            </button>
+                        </div>
+                    </div>
+                }
+            }
+            ConvertingState::Done => {
+                yew::html! {
+                    <div>
+                        if let Some(href) = self.get_obj_url_with_result() {
+                            <a
+                                class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")}
+                                href={href}
+                                download={self.result_name.clone()}
+                            >
+                                {format!("Download {}", self.result_name)}
+                            </a>
+                        }
+                        <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                            {"Back"}
+                        </button>
+                    </div>
+                }
+            }
+            ConvertingState::Fail => {
+                let msg = self.err_msg.0.as_str();
+                let detail = self.err_msg.1.as_str();
+                yew::html! {
+                    <div
+                        class={yew::classes!("alert", "alert-danger", "mb-0")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.to_string()}
+                        onclick={reset_onclick}
+                    >{format!("Error: {msg}")}
+                    </div>
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,5 @@
+//! Расшифровка и извлечение элементов открытого архива.
+
+mod ui;
+
+pub use ui::{ArchivePasswordComponent, DecryptEntryComponent};
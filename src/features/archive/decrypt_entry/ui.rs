@@ -0,0 +1,157 @@
+use yew::TargetCast;
+use crate::entities::archive::model::{InfoAboutArchiveEntry, OpenedArchive};
+
+pub enum DecryptEntryMsg {
+    /// Start reading the entry.
+    Decrypt,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct DecryptEntryProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    pub entry: InfoAboutArchiveEntry,
+    /// Password entered for the archive.
+    pub password: yew::AttrValue,
+}
+
+/// Component - decrypts an archive entry on demand and offers it for download.
+pub struct DecryptEntryComponent {
+    err_msg: Option<(String, String)>,
+    blob_result: Option<gloo_file::Blob>,
+}
+
+impl DecryptEntryComponent {
+    fn get_obj_url_with_result(&self) -> Option<String> {
+        let blob = self.blob_result.as_ref()?;
+        let download_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok()?;
+        Some(download_url)
+    }
+}
+
+impl yew::Component for DecryptEntryComponent {
+    type Message = DecryptEntryMsg;
+    type Properties = DecryptEntryProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            err_msg: None,
+            blob_result: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            DecryptEntryMsg::Decrypt => {
+                let props = ctx.props();
+                match props.archive.read_entry(&props.entry, &props.password) {
+                    Ok(data) => {
+                        self.err_msg = None;
+                        self.blob_result = Some(gloo_file::Blob::new(data.as_slice()));
+                    }
+                    Err(err) => { self.err_msg = Some(err); }
+                }
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, ctx: &yew::Context<Self>, old_props: &Self::Properties) -> bool {
+        if ctx.props().password != old_props.password || ctx.props().archive != old_props.archive {
+            self.err_msg = None;
+            self.blob_result = None;
+        }
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let entry = &ctx.props().entry;
+        if entry.is_dir {
+            return yew::html! {};
+        }
+        let onclick = &ctx.link().callback(|_| DecryptEntryMsg::Decrypt);
+        yew::html! {
+            <div class={yew::classes!("d-flex", "justify-content-end", "align-items-center", "mt-1")}>
+                if let Some((msg, detail)) = &self.err_msg {
+                    <small
+                        class={yew::classes!("text-danger", "me-2")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.clone()}
+                    >{msg}</small>
+                }
+                if let Some(href) = self.get_obj_url_with_result() {
+                    <a
+                        class={yew::classes!("btn", "btn-sm", "btn-outline-dark")}
+                        href={href}
+                        download={entry.base_name().to_string()}
+                    >
+                        {"Download"}
+                    </a>
+                } else {
+                    <button
+                        type="button"
+                        class={yew::classes!("btn", "btn-sm", "btn-outline-dark")}
+                        title="Decompress the entry."
+                        {onclick}
+                    >
+                        if entry.encryption.is_encrypted() { {"Decrypt"} } else { {"Extract"} }
+                    </button>
+                }
+            </div>
+        }
+    }
+}
+
+pub enum ArchivePasswordMsg {
+    EditPassword(String),
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct ArchivePasswordProps {
+    pub password: yew::AttrValue,
+    /// Callback with the password entered by the user.
+    pub on_password_change: yew::Callback<String>,
+}
+
+/// Component - password entry for encrypted archives.
+pub struct ArchivePasswordComponent;
+
+impl yew::Component for ArchivePasswordComponent {
+    type Message = ArchivePasswordMsg;
+    type Properties = ArchivePasswordProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ArchivePasswordMsg::EditPassword(value) => {
+                ctx.props().on_password_change.emit(value);
+                false
+            }
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let edit = move |input: web_sys::HtmlInputElement| {
+            ArchivePasswordMsg::EditPassword(input.value())
+        };
+        let onblur = &ctx.link().callback(move |e: web_sys::FocusEvent| edit(e.target_unchecked_into()));
+        let onkeypress = &ctx.link().batch_callback(move |e: web_sys::KeyboardEvent| {
+            (e.key() == "Enter").then(|| edit(e.target_unchecked_into()))
+        });
+        yew::html! {
+            <div class={yew::classes!("input-group", "input-group-sm")}>
+                <span class={yew::classes!("input-group-text")}>{"Password"}</span>
+                <input
+                    class={yew::classes!("form-control")}
+                    type="password"
+                    placeholder="Enter the archive password" aria-label="Enter the archive password"
+                    value={ctx.props().password.clone()}
+                    {onblur}
+                    {onkeypress}
+                />
+            </div>
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! Двухпанельный менеджер: перенос записей между архивами и выбранными файлами.
+
+mod ui;
+mod model;
+
+pub use ui::{PaneComponent, SavePaneComponent};
+pub(crate) use model::{Pane, PaneSide};
@@ -0,0 +1,218 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::entities::file::model::InfoAboutSelectedFile;
+use crate::features::archive::append_files::unique_name;
+use crate::features::archive::merge_archives::RawArchiveWriter;
+use crate::share::zip_tools::{self, TypeEncryption};
+
+/// Format of the drag data: the side and the index of the dragged item.
+const DRAG_DATA_SEPARATOR: char = ':';
+
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub(crate) enum PaneSide {
+    Left,
+    Right,
+}
+
+impl PaneSide {
+    pub fn other(&self) -> Self {
+        match self {
+            Self::Left => Self::Right,
+            Self::Right => Self::Left,
+        }
+    }
+
+    /// Text put into the `DataTransfer` when an item of the pane is dragged.
+    pub fn drag_data(&self, ind: usize) -> String {
+        format!("{self:?}{DRAG_DATA_SEPARATOR}{ind}")
+    }
+
+    /// Side and index of the dragged item, `None` for data not from a pane.
+    pub fn parse_drag_data(data: &str) -> Option<(Self, usize)> {
+        let (side, ind) = data.split_once(DRAG_DATA_SEPARATOR)?;
+        let side = match side {
+            "Left" => Self::Left,
+            "Right" => Self::Right,
+            _ => return None,
+        };
+        Some((side, ind.parse().ok()?))
+    }
+}
+
+/// Where the data of an item comes from.
+#[derive(PartialEq, Clone)]
+pub(crate) enum ItemSource {
+    /// Index of the entry in the archive.
+    Entry(std::rc::Rc<OpenedArchive>, usize),
+    File(std::rc::Rc<InfoAboutSelectedFile>),
+}
+
+/// An entry of the future archive.
+#[derive(PartialEq, Clone)]
+pub(crate) struct PaneItem {
+    /// Path in the archive; folders end with `/`.
+    pub name: String,
+    pub source: ItemSource,
+}
+
+impl PaneItem {
+    pub fn is_dir(&self) -> bool {
+        self.name.ends_with('/')
+    }
+
+    pub fn size(&self) -> yew::AttrValue {
+        match &self.source {
+            ItemSource::Entry(archive, ind) => archive.entries[*ind].entry_size.clone(),
+            ItemSource::File(file) => file.file_size.clone(),
+        }
+    }
+
+    /// Whether the item is copied from an archive entry that is encrypted.
+    pub fn is_encrypted(&self) -> bool {
+        match &self.source {
+            ItemSource::Entry(archive, ind) => archive.entries[*ind].encryption.is_encrypted(),
+            ItemSource::File(_) => false,
+        }
+    }
+}
+
+/// Contents of one pane: the items of the archive that is saved from it.
+#[derive(PartialEq, Clone)]
+pub(crate) struct Pane {
+    pub title: String,
+    /// File name offered when the pane is saved.
+    pub file_name: String,
+    pub items: Vec<PaneItem>,
+}
+
+impl Pane {
+    pub fn empty() -> Self {
+        Self { title: "New archive".to_string(), file_name: "new.zip".to_string(), items: vec![] }
+    }
+
+    pub fn from_archive(archive: std::rc::Rc<OpenedArchive>) -> Self {
+        let items = archive.entries.iter()
+            .map(|entry| PaneItem {
+                name: entry.entry_name.to_string(),
+                source: ItemSource::Entry(archive.clone(), entry.ind),
+            })
+            .collect();
+        Self { title: archive.file_name.to_string(), file_name: archive.file_name.to_string(), items }
+    }
+
+    pub fn from_files(files: &[std::rc::Rc<InfoAboutSelectedFile>]) -> Self {
+        let items = files.iter()
+            .map(|file| PaneItem { name: file.file_name.to_string(), source: ItemSource::File(file.clone()) })
+            .collect();
+        Self { title: "Selected files".to_string(), file_name: "archive.zip".to_string(), items }
+    }
+
+    /// Indexes of the item and, for a folder, of all items inside it.
+    fn with_children(&self, ind: usize) -> Vec<usize> {
+        let Some(item) = self.items.get(ind) else { return vec![]; };
+        if !item.is_dir() {
+            return vec![ind];
+        }
+        self.items.iter().enumerate()
+            .filter(|(_, child)| child.name.starts_with(&item.name))
+            .map(|(ind, _)| ind)
+            .collect()
+    }
+
+    /// Copies of the item and, for a folder, of everything inside it.
+    pub fn take_copies(&self, ind: usize) -> Vec<PaneItem> {
+        self.with_children(ind).into_iter().map(|ind| self.items[ind].clone()).collect()
+    }
+
+    /// Adds the items at the end. Folders that already exist are merged,
+    /// files with taken names get a number.
+    pub fn add_items(&mut self, items: Vec<PaneItem>) {
+        let mut names: std::collections::HashSet<String> = self.items.iter().map(|item| item.name.clone()).collect();
+        for mut item in items {
+            if names.contains(&item.name) {
+                if item.is_dir() { continue; }
+                item.name = unique_name(&item.name, &names);
+            }
+            names.insert(item.name.clone());
+            self.items.push(item);
+        }
+    }
+
+    /// Removes the item and, for a folder, everything inside it.
+    pub fn remove(&mut self, ind: usize) {
+        let removed = self.with_children(ind);
+        let mut position = 0;
+        self.items.retain(|_| {
+            let keep = !removed.contains(&position);
+            position += 1;
+            keep
+        });
+    }
+
+    /// Indexes of the items whose data must be read from disk before saving.
+    pub fn files_to_read(&self) -> Vec<usize> {
+        self.items.iter().enumerate()
+            .filter(|(_, item)| matches!(item.source, ItemSource::File(_)))
+            .map(|(ind, _)| ind)
+            .collect()
+    }
+}
+
+/// Writes the items of the pane into a new archive.
+///
+/// Archive entries are copied raw and keep their compression and encryption.
+/// Files from disk are compressed and encrypted with the password, if it is set.
+pub(crate) fn save_pane(
+    pane: &Pane,
+    mut loaded_files: std::collections::HashMap<usize, Vec<u8>>,
+    password: &str,
+    type_encryption: TypeEncryption,
+) -> Result<Vec<u8>, (String, String)> {
+    let mut zip_writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip_tools::file_options(password, type_encryption)
+        .compression_method(zip::CompressionMethod::Deflated);
+    for ind in pane.files_to_read() {
+        let item = &pane.items[ind];
+        let data = loaded_files.remove(&ind).ok_or((
+            format!("Failed to read file: {}.", item.name),
+            "The file has not been read from disk.".to_string(),
+        ))?;
+        let mut file_options = options.large_file(data.len() as u64 >= u32::MAX as u64);
+        if let ItemSource::File(file) = &item.source {
+            let modified = file.js_file_obj.last_modified_time()
+                .duration_since(std::time::UNIX_EPOCH)
+                .ok()
+                .and_then(|duration| zip_tools::zip_date_time(duration.as_secs()));
+            if let Some(modified) = modified {
+                file_options = file_options.last_modified_time(modified);
+            }
+        }
+        zip_writer.start_file(item.name.as_str(), file_options).map_err(|err| (
+            format!("Failed to add file meta information to archive: {}.", item.name),
+            format!("{}", err)
+        ))?;
+        std::io::Write::write_all(&mut zip_writer, &data).map_err(|err| (
+            format!("Error writing file to archive: {}.", item.name),
+            format!("{}", err)
+        ))?;
+    }
+    let data = zip_writer.finish()
+        .map_err(|err| ("Failed to create archive.".to_string(), format!("{}", err)))?
+        .into_inner();
+    let written = OpenedArchive::from_bytes(yew::AttrValue::default(), data)?;
+    let mut written_entries = written.entries.iter();
+
+    let mut writer = RawArchiveWriter::default();
+    for item in &pane.items {
+        match &item.source {
+            ItemSource::Entry(archive, ind) => writer.copy_entry(archive, &archive.entries[*ind], &item.name)?,
+            ItemSource::File(_) => {
+                let entry = written_entries.next().ok_or((
+                    format!("Failed to write file: {}.", item.name),
+                    "The entry is missing in the temporary archive.".to_string(),
+                ))?;
+                writer.copy_entry(&written, entry, &item.name)?;
+            }
+        }
+    }
+    writer.finish("")
+}
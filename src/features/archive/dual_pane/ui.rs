@@ -0,0 +1,306 @@
+use yew::TargetCast;
+use crate::features::archive::dual_pane::model::{self, Pane, PaneSide};
+use crate::share::zip_tools::TypeEncryption;
+
+/// Type of the drag data with the side and the index of the dragged item.
+const DRAG_DATA_TYPE: &str = "text/plain";
+
+#[derive(yew::Properties, PartialEq)]
+pub struct PaneProps {
+    pub pane: Pane,
+    pub side: PaneSide,
+    /// Callback with the side and the index of an item dropped onto the pane.
+    pub on_drop: yew::Callback<(PaneSide, usize)>,
+    /// Callback with the index of an item to copy to the other pane.
+    pub on_copy: yew::Callback<usize>,
+    pub on_remove: yew::Callback<usize>,
+}
+
+/// Component - the items of one pane, which can be dragged to the other pane.
+#[yew::function_component(PaneComponent)]
+pub fn pane(props: &PaneProps) -> yew::Html {
+    let side = props.side;
+    let is_drag_over = yew::use_state(|| false);
+    let ondrop = {
+        let on_drop = props.on_drop.clone();
+        let is_drag_over = is_drag_over.clone();
+        yew::Callback::from(move |event: web_sys::DragEvent| {
+            event.prevent_default();
+            is_drag_over.set(false);
+            let dragged = event.data_transfer()
+                .and_then(|dt| dt.get_data(DRAG_DATA_TYPE).ok())
+                .and_then(|data| PaneSide::parse_drag_data(&data));
+            if let Some((from, ind)) = dragged {
+                if from != side {
+                    on_drop.emit((from, ind));
+                }
+            }
+        })
+    };
+    let ondragover = {
+        let is_drag_over = is_drag_over.clone();
+        yew::Callback::from(move |event: web_sys::DragEvent| {
+            event.prevent_default();
+            is_drag_over.set(true);
+        })
+    };
+    let ondragleave = {
+        let is_drag_over = is_drag_over.clone();
+        yew::Callback::from(move |_: web_sys::DragEvent| is_drag_over.set(false))
+    };
+    let copy_label = match side {
+        PaneSide::Left => "→",
+        PaneSide::Right => "←",
+    };
+    let border = if *is_drag_over { "border-primary" } else { "border-light" };
+
+    yew::html! {
+        <div
+            class={yew::classes!("list-group", "overflow-auto", "border", border)}
+            style="height: 40vh;"
+            {ondrop}
+            {ondragover}
+            {ondragleave}
+        >
+            if props.pane.items.is_empty() {
+                <small class={yew::classes!("text-muted", "p-2")}>{"Drag entries here from the other pane."}</small>
+            }
+            { props.pane.items.iter().enumerate().map(|(ind, item)| {
+                let ondragstart = yew::Callback::from(move |event: web_sys::DragEvent| {
+                    if let Some(dt) = event.data_transfer() {
+                        let _ = dt.set_data(DRAG_DATA_TYPE, &side.drag_data(ind));
+                    }
+                });
+                let on_copy = props.on_copy.clone();
+                let on_remove = props.on_remove.clone();
+                yew::html! {
+                    <div class={yew::classes!("list-group-item", "py-1", "px-2", "d-flex", "align-items-center", "gap-2")}
+                        draggable="true"
+                        {ondragstart}
+                    >
+                        <span class={yew::classes!("flex-fill", "text-truncate")} title={item.name.clone()}>
+                            if item.is_dir() { <strong>{&item.name}</strong> } else { {&item.name} }
+                        </span>
+                        if item.is_encrypted() {
+                            <span class={yew::classes!("badge", "bg-dark")}>{"Encrypted"}</span>
+                        }
+                        if !item.is_dir() {
+                            <small class={yew::classes!("text-nowrap")}>{item.size()}</small>
+                        }
+                        <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "py-0")}
+                            title="Copy to the other pane"
+                            onclick={move |_| on_copy.emit(ind)}
+                        >{copy_label}</button>
+                        <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-danger", "py-0")}
+                            title="Remove from this pane"
+                            onclick={move |_| on_remove.emit(ind)}
+                        >{"×"}</button>
+                    </div>
+                }
+            }).collect::<yew::Html>() }
+        </div>
+    }
+}
+
+pub enum SavePaneMsg {
+    EditPassword(String),
+    ChangeTypeEncryption(String),
+    StartSave,
+    /// A file of the pane has been read from disk.
+    LoadedFile(usize, Vec<u8>),
+    FailedSave((String, String)),
+    Reset,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct SavePaneProps {
+    pub pane: Pane,
+}
+
+/// Component - writes the items of a pane into a new archive.
+pub struct SavePaneComponent {
+    password: String,
+    type_encryption: TypeEncryption,
+    /// Copy of the pane taken when saving started, so later changes of the pane
+    /// do not shift the indexes of the files being read.
+    saving_pane: Option<Pane>,
+    file_reading_tasks: std::collections::HashMap<usize, gloo_file::callbacks::FileReader>,
+    loaded_files: std::collections::HashMap<usize, Vec<u8>>,
+    err_msg: Option<(String, String)>,
+    blob_result: Option<gloo_file::Blob>,
+}
+
+impl SavePaneComponent {
+    fn get_obj_url_with_result(&self) -> Option<String> {
+        let blob = self.blob_result.as_ref()?;
+        let download_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok()?;
+        Some(download_url)
+    }
+
+    fn finish(&mut self) {
+        let loaded_files = std::mem::take(&mut self.loaded_files);
+        let Some(pane) = self.saving_pane.take() else { return; };
+        match model::save_pane(&pane, loaded_files, &self.password, self.type_encryption) {
+            Ok(data) => { self.blob_result = Some(gloo_file::Blob::new(data.as_slice())); }
+            Err(err) => { self.err_msg = Some(err); }
+        }
+    }
+}
+
+impl yew::Component for SavePaneComponent {
+    type Message = SavePaneMsg;
+    type Properties = SavePaneProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            password: String::new(),
+            type_encryption: TypeEncryption::Aes256,
+            saving_pane: None,
+            file_reading_tasks: std::collections::HashMap::new(),
+            loaded_files: std::collections::HashMap::new(),
+            err_msg: None,
+            blob_result: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            SavePaneMsg::EditPassword(value) => {
+                self.password = value.trim().to_string();
+                true
+            }
+            SavePaneMsg::ChangeTypeEncryption(value) => {
+                self.type_encryption = TypeEncryption::from(value);
+                false
+            }
+            SavePaneMsg::StartSave => {
+                let pane = ctx.props().pane.clone();
+                self.err_msg = None;
+                self.loaded_files = std::collections::HashMap::new();
+                let to_read = pane.files_to_read();
+                if to_read.is_empty() {
+                    self.saving_pane = Some(pane);
+                    self.finish();
+                    return true;
+                }
+                let callback_loaded_file = ctx.link()
+                    .callback(|(ind, _, data)| SavePaneMsg::LoadedFile(ind, data));
+                let callback_failed = ctx.link().callback(SavePaneMsg::FailedSave);
+                for ind in to_read {
+                    let model::ItemSource::File(file) = &pane.items[ind].source else { continue; };
+                    let task = crate::share::fs_tools::read_file(
+                        ind,
+                        file.file_name.clone(),
+                        &file.js_file_obj,
+                        callback_loaded_file.clone(),
+                        callback_failed.clone(),
+                    );
+                    self.file_reading_tasks.insert(ind, task);
+                }
+                self.saving_pane = Some(pane);
+                true
+            }
+            SavePaneMsg::LoadedFile(ind, data) => {
+                if self.saving_pane.is_none() { return false; }
+                self.file_reading_tasks.remove(&ind);
+                self.loaded_files.insert(ind, data);
+                if self.file_reading_tasks.is_empty() {
+                    self.finish();
+                }
+                true
+            }
+            SavePaneMsg::FailedSave(err) => {
+                self.saving_pane = None;
+                self.err_msg = Some(err);
+                self.file_reading_tasks = std::collections::HashMap::new();
+                self.loaded_files = std::collections::HashMap::new();
+                true
+            }
+            SavePaneMsg::Reset => {
+                self.err_msg = None;
+                self.blob_result = None;
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &yew::Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.blob_result = None;
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let reset_onclick = &ctx.link().callback(|_| SavePaneMsg::Reset);
+        if let Some(href) = self.get_obj_url_with_result() {
+            return yew::html! {
+                <div class={yew::classes!("d-flex", "justify-content-end")}>
+                    <a
+                        class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")}
+                        href={href}
+                        download={ctx.props().pane.file_name.clone()}
+                    >
+                        {format!("Download {}", ctx.props().pane.file_name)}
+                    </a>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                        {"Back"}
+                    </button>
+                </div>
+            };
+        }
+        if self.saving_pane.is_some() {
+            return yew::html! {
+                <p class={yew::classes!("mb-0")}>{format!("Reading files: {}", self.loaded_files.len())}</p>
+            };
+        }
+
+        let onchange_password = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            SavePaneMsg::EditPassword(input.value())
+        });
+        let onchange_type = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            SavePaneMsg::ChangeTypeEncryption(input.value())
+        });
+        let start_onclick = &ctx.link().callback(|_| SavePaneMsg::StartSave);
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                <div class={yew::classes!("input-group", "input-group-sm")}>
+                    if !ctx.props().pane.files_to_read().is_empty() {
+                        <select class={yew::classes!("form-select", "form-select-sm")}
+                            style="max-width: 110px;"
+                            onchange={onchange_type}
+                        >
+                            <option selected={self.type_encryption == TypeEncryption::ZipCrypto} value={"ZipCrypto"}>{"ZipCrypto"}</option>
+                            <option selected={self.type_encryption == TypeEncryption::Aes256} value={"Aes256"}>{"Aes256"}</option>
+                        </select>
+                        <input
+                            class={yew::classes!("form-control")}
+                            type="password"
+                            placeholder="Password for files (optional)" aria-label="Password for files"
+                            title="Files from disk are encrypted with this password. Archive entries are copied as they are."
+                            value={self.password.clone()}
+                            onchange={onchange_password}
+                        />
+                    }
+                    <button
+                        type="button"
+                        class={yew::classes!("btn", "btn-outline-dark", "ms-auto")}
+                        disabled={ctx.props().pane.items.is_empty()}
+                        onclick={start_onclick}
+                    >
+                        {"Save as new archive"}
+                    </button>
+                </div>
+                if let Some((msg, detail)) = &self.err_msg {
+                    <div
+                        class={yew::classes!("alert", "alert-danger", "mt-2", "mb-0")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.clone()}
+                        onclick={reset_onclick}
+                    >{format!("Error: {msg}")}
+                    </div>
+                }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,7 @@
+//! Удаление, переименование и перемещение элементов архива.
+
+mod ui;
+mod model;
+
+pub use model::EntryChange;
+pub use ui::{EditEntryComponent, SaveEditedArchiveComponent};
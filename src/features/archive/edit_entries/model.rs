@@ -0,0 +1,79 @@
+use crate::entities::archive::model::{InfoAboutArchiveEntry, OpenedArchive};
+use crate::features::archive::merge_archives::RawArchiveWriter;
+
+/// A pending change of an archive entry.
+#[derive(PartialEq, Clone, Debug)]
+pub enum EntryChange {
+    Delete,
+    /// New full path of the entry. Renaming a folder moves everything inside it.
+    Rename(String),
+}
+
+/// Path of the entry after all changes, or `None` if it is deleted.
+fn target_name(
+    entry: &InfoAboutArchiveEntry,
+    archive: &OpenedArchive,
+    changes: &std::collections::HashMap<usize, EntryChange>,
+) -> Option<String> {
+    let name = entry.entry_name.as_str();
+    let mut target = name.to_string();
+    let mut matched_len = 0;
+    for (ind, change) in changes.iter() {
+        let Some(dir) = archive.entries.get(*ind) else { continue; };
+        let is_own = dir.ind == entry.ind;
+        let is_parent = dir.is_dir && name.starts_with(dir.entry_name.as_str());
+        if !is_own && !is_parent {
+            continue;
+        }
+        match change {
+            EntryChange::Delete => return None,
+            // The deepest renamed folder wins.
+            EntryChange::Rename(new_name) if dir.entry_name.len() > matched_len => {
+                matched_len = dir.entry_name.len();
+                let mut new_name = new_name.trim().trim_start_matches('/').to_string();
+                if dir.is_dir && !new_name.ends_with('/') {
+                    new_name.push('/');
+                }
+                target = format!("{}{}", new_name, &name[dir.entry_name.len()..]);
+            }
+            EntryChange::Rename(_) => {}
+        }
+    }
+    Some(target)
+}
+
+/// Writes a new archive with the changes applied.
+///
+/// The compressed data of all remaining entries is copied raw, without recompression,
+/// so encrypted entries keep their encryption.
+pub(crate) fn apply_changes(
+    archive: &OpenedArchive,
+    changes: &std::collections::HashMap<usize, EntryChange>,
+) -> Result<Vec<u8>, (String, String)> {
+    let mut names = std::collections::HashSet::new();
+    let mut targets = Vec::with_capacity(archive.entries.len());
+    for entry in archive.entries.iter() {
+        let Some(target) = target_name(entry, archive, changes) else { continue; };
+        if target.is_empty() || target == "/" {
+            return Err((
+                format!("Empty name for entry: {}.", entry.entry_name),
+                "Enter a name for the entry.".to_string(),
+            ));
+        }
+        if !names.insert(target.clone()) {
+            return Err((
+                format!("Duplicate entry name: {}.", target),
+                "Two entries cannot have the same path.".to_string(),
+            ));
+        }
+        targets.push((entry, target));
+    }
+
+    let mut writer = RawArchiveWriter::with_capacity(archive.data().len());
+    for (entry, target) in targets {
+        // The target is written even for kept names: names without the UTF-8 flag
+        // are written in UTF-8, as they were decoded with the chosen charset.
+        writer.copy_entry(archive, entry, &target)?;
+    }
+    writer.finish(&archive.comment)
+}
@@ -0,0 +1,239 @@
+use yew::TargetCast;
+use crate::entities::archive::model::{InfoAboutArchiveEntry, OpenedArchive};
+use crate::features::archive::edit_entries::model::{self, EntryChange};
+
+pub enum EditEntryMsg {
+    StartRename,
+    /// New path from the input field.
+    EditName(String),
+    Delete,
+    /// Cancel the pending change.
+    Restore,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct EditEntryProps {
+    pub entry: InfoAboutArchiveEntry,
+    pub change: Option<EntryChange>,
+    /// Callback with the index of the entry and its new pending change.
+    pub on_change: yew::Callback<(usize, Option<EntryChange>)>,
+}
+
+/// Component - controls for deleting and renaming an archive entry.
+pub struct EditEntryComponent {
+    is_renaming: bool,
+}
+
+impl yew::Component for EditEntryComponent {
+    type Message = EditEntryMsg;
+    type Properties = EditEntryProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self { is_renaming: false }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        let props = ctx.props();
+        match msg {
+            EditEntryMsg::StartRename => {
+                self.is_renaming = true;
+                true
+            }
+            EditEntryMsg::EditName(value) => {
+                self.is_renaming = false;
+                let value = value.trim().to_string();
+                let change = (value != props.entry.entry_name.as_str()).then_some(EntryChange::Rename(value));
+                props.on_change.emit((props.entry.ind, change));
+                true
+            }
+            EditEntryMsg::Delete => {
+                self.is_renaming = false;
+                props.on_change.emit((props.entry.ind, Some(EntryChange::Delete)));
+                true
+            }
+            EditEntryMsg::Restore => {
+                props.on_change.emit((props.entry.ind, None));
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let props = ctx.props();
+        let restore_onclick = &ctx.link().callback(|_| EditEntryMsg::Restore);
+        if props.change == Some(EntryChange::Delete) {
+            return yew::html! {
+                <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center", "mt-1")}>
+                    <small class={yew::classes!("text-danger")}>{"Will be deleted"}</small>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={restore_onclick}>
+                        {"Restore"}
+                    </button>
+                </div>
+            };
+        }
+        if self.is_renaming {
+            let edit = move |input: web_sys::HtmlInputElement| EditEntryMsg::EditName(input.value());
+            let onblur = &ctx.link().callback(move |e: web_sys::FocusEvent| edit(e.target_unchecked_into()));
+            let onkeypress = &ctx.link().batch_callback(move |e: web_sys::KeyboardEvent| {
+                (e.key() == "Enter").then(|| edit(e.target_unchecked_into()))
+            });
+            let value = match &props.change {
+                Some(EntryChange::Rename(name)) => name.clone(),
+                _ => props.entry.entry_name.to_string(),
+            };
+            return yew::html! {
+                <input
+                    class={yew::classes!("form-control", "form-control-sm", "mt-1")}
+                    type="text"
+                    aria-label="New path of the entry"
+                    title="Full path inside the archive. Change the folder part to move the entry."
+                    {value}
+                    {onblur}
+                    {onkeypress}
+                />
+            };
+        }
+
+        let rename_onclick = &ctx.link().callback(|_| EditEntryMsg::StartRename);
+        let delete_onclick = &ctx.link().callback(|_| EditEntryMsg::Delete);
+        yew::html! {
+            <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center", "mt-1")}>
+                <small class={yew::classes!("text-primary", "text-truncate")}>
+                    if let Some(EntryChange::Rename(name)) = &props.change {
+                        {format!("→ {name}")}
+                    }
+                </small>
+                <div class={yew::classes!("btn-group", "btn-group-sm")}>
+                    if props.change.is_some() {
+                        <button type="button" class={yew::classes!("btn", "btn-outline-dark")} onclick={restore_onclick}>
+                            {"Undo"}
+                        </button>
+                    }
+                    <button type="button" class={yew::classes!("btn", "btn-outline-dark")} onclick={rename_onclick}>
+                        {"Rename"}
+                    </button>
+                    <button type="button" class={yew::classes!("btn", "btn-outline-dark")} onclick={delete_onclick}>
+                        {"Delete"}
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
+
+pub enum SaveEditedArchiveMsg {
+    Save,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct SaveEditedArchiveProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    /// Pending changes by entry index.
+    pub changes: std::collections::HashMap<usize, EntryChange>,
+    /// Callback with the archive after the changes were applied.
+    pub on_archive_saved: yew::Callback<OpenedArchive>,
+    pub on_discard: yew::Callback<()>,
+}
+
+/// Component - applies pending entry changes and offers the new archive for download.
+pub struct SaveEditedArchiveComponent {
+    err_msg: Option<(String, String)>,
+    blob_result: Option<gloo_file::Blob>,
+}
+
+impl SaveEditedArchiveComponent {
+    fn get_obj_url_with_result(&self) -> Option<String> {
+        let blob = self.blob_result.as_ref()?;
+        let download_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok()?;
+        Some(download_url)
+    }
+}
+
+impl yew::Component for SaveEditedArchiveComponent {
+    type Message = SaveEditedArchiveMsg;
+    type Properties = SaveEditedArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            err_msg: None,
+            blob_result: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            SaveEditedArchiveMsg::Save => {
+                let props = ctx.props();
+                let res = model::apply_changes(&props.archive, &props.changes).and_then(|data| {
+                    let blob = gloo_file::Blob::new(data.as_slice());
+                    OpenedArchive::from_bytes(props.archive.file_name.clone(), data).map(|archive| (blob, archive))
+                });
+                match res {
+                    Ok((blob, archive)) => {
+                        self.err_msg = None;
+                        self.blob_result = Some(blob);
+                        props.on_archive_saved.emit(archive);
+                    }
+                    Err(err) => { self.err_msg = Some(err); }
+                }
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, ctx: &yew::Context<Self>, _old_props: &Self::Properties) -> bool {
+        if !ctx.props().changes.is_empty() {
+            self.blob_result = None;
+        }
+        self.err_msg = None;
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let changes = &ctx.props().changes;
+        if changes.is_empty() {
+            return match self.get_obj_url_with_result() {
+                Some(href) => yew::html! {
+                    <a
+                        class={yew::classes!("btn", "btn-sm", "btn-outline-dark")}
+                        href={href}
+                        download={ctx.props().archive.file_name.clone()}
+                    >
+                        {"Download edited archive"}
+                    </a>
+                },
+                None => yew::html! {},
+            };
+        }
+        let save_onclick = &ctx.link().callback(|_| SaveEditedArchiveMsg::Save);
+        let discard_onclick = &ctx.props().on_discard.reform(|_| ());
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center")}>
+                    <span>{format!("Pending changes: {}", changes.len())}</span>
+                    <div>
+                        <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")} onclick={discard_onclick}>
+                            {"Discard"}
+                        </button>
+                        <button
+                            type="button"
+                            class={yew::classes!("btn", "btn-sm", "btn-outline-dark")}
+                            title="Write a new archive. Unchanged data is copied without recompression."
+                            onclick={save_onclick}
+                        >
+                            {"Save archive"}
+                        </button>
+                    </div>
+                </div>
+                if let Some((msg, detail)) = &self.err_msg {
+                    <div
+                        class={yew::classes!("alert", "alert-danger", "mt-2", "mb-0")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.clone()}
+                    >{format!("Error: {msg}")}
+                    </div>
+                }
+            </div>
+        }
+    }
+}
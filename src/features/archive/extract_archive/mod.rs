@@ -0,0 +1,6 @@
+//! Распаковка всех элементов архива в папку на диске пользователя.
+
+mod ui;
+mod model;
+
+pub use ui::ExtractArchiveComponent;
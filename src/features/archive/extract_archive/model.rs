@@ -0,0 +1,165 @@
+use std::io::Read;
+use crate::entities::archive::model::{InfoAboutArchiveEntry, OpenedArchive};
+use crate::share::fs_tools;
+
+/// Size of the chunks in which entries are written to disk.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// What to do with a file that already exists in the target folder.
+#[derive(PartialEq, Copy, Clone)]
+pub(crate) enum OverwritePolicy {
+    Overwrite,
+    Skip,
+    KeepBoth,
+}
+
+impl From<String> for OverwritePolicy {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Overwrite" => { Self::Overwrite }
+            "KeepBoth" => { Self::KeepBoth }
+            _ => { Self::Skip }
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Default)]
+pub(crate) struct ExtractProgress {
+    pub done_entries: usize,
+    pub total_entries: usize,
+    pub done_bytes: u64,
+    pub total_bytes: u64,
+    /// Path of the entry being written.
+    pub current: String,
+}
+
+#[derive(PartialEq, Clone, Default)]
+pub(crate) struct ExtractReport {
+    pub extracted: usize,
+    /// Entries skipped because the file already existed.
+    pub skipped: usize,
+    pub failed: Vec<(String, String)>,
+}
+
+/// Splits an entry path into folder names and the file name,
+/// dropping empty, `.` and `..` components so that nothing is written outside the target folder.
+fn safe_path(entry_name: &str) -> Vec<String> {
+    entry_name
+        .replace('\\', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+        .map(str::to_string)
+        .collect()
+}
+
+/// Folder handles by path, so that every folder is looked up only once.
+struct FolderCache {
+    root: web_sys::FileSystemDirectoryHandle,
+    folders: std::collections::HashMap<Vec<String>, web_sys::FileSystemDirectoryHandle>,
+}
+
+impl FolderCache {
+    async fn get(&mut self, path: &[String]) -> Result<web_sys::FileSystemDirectoryHandle, (String, String)> {
+        let mut folder = self.root.clone();
+        for depth in 1..=path.len() {
+            let key = path[..depth].to_vec();
+            folder = match self.folders.get(&key) {
+                Some(handle) => handle.clone(),
+                None => {
+                    let handle = fs_tools::get_or_create_directory(&folder, &path[depth - 1]).await?;
+                    self.folders.insert(key, handle.clone());
+                    handle
+                }
+            };
+        }
+        Ok(folder)
+    }
+}
+
+/// Streams one entry into the folder. Returns `false` if the entry was skipped.
+async fn extract_entry(
+    zip: &mut zip::ZipArchive<crate::entities::archive::model::ArchiveReader>,
+    entry: &InfoAboutArchiveEntry,
+    password: &str,
+    folders: &mut FolderCache,
+    policy: OverwritePolicy,
+    progress: &mut ExtractProgress,
+    on_progress: &yew::Callback<ExtractProgress>,
+) -> Result<bool, (String, String)> {
+    let mut path = safe_path(&entry.entry_name);
+    if entry.is_dir {
+        folders.get(&path).await?;
+        return Ok(true);
+    }
+    let Some(mut file_name) = path.pop() else { return Ok(false); };
+    let folder = folders.get(&path).await?;
+    let mut is_new_file = true;
+    if fs_tools::entry_exists(&folder, &file_name).await {
+        match policy {
+            OverwritePolicy::Overwrite => { is_new_file = false; }
+            OverwritePolicy::Skip => return Ok(false),
+            OverwritePolicy::KeepBoth => {
+                let mut n = 1;
+                while fs_tools::entry_exists(&folder, &crate::share::numbered_name(&file_name, n)).await {
+                    n += 1;
+                }
+                file_name = crate::share::numbered_name(&file_name, n);
+            }
+        }
+    }
+
+    let mut file = entry.open(zip, password)?;
+    let writer = fs_tools::create_file_writer(&folder, &file_name).await?;
+    let mut chunk = vec![0; CHUNK_SIZE];
+    let written: Result<(), (String, String)> = async {
+        loop {
+            let len = file.read(&mut chunk).map_err(|err| entry.describe_read_error(err))?;
+            if len == 0 {
+                return Ok(());
+            }
+            fs_tools::write_chunk(&writer, &chunk[..len]).await?;
+            progress.done_bytes += len as u64;
+            on_progress.emit(progress.clone());
+        }
+    }.await;
+    if let Err(err) = written {
+        // Without this the failed entry leaves an empty or partial file behind.
+        fs_tools::abort_writer(&folder, &file_name, &writer, is_new_file).await;
+        return Err(err);
+    }
+    fs_tools::close_writer(&writer).await?;
+    Ok(true)
+}
+
+/// Writes every entry of the archive into the folder, recreating the folder tree.
+/// Entries that fail are reported and do not stop the extraction.
+pub(crate) async fn extract_to_folder(
+    archive: std::rc::Rc<OpenedArchive>,
+    password: String,
+    root: web_sys::FileSystemDirectoryHandle,
+    policy: OverwritePolicy,
+    on_progress: yew::Callback<ExtractProgress>,
+) -> ExtractReport {
+    let mut zip = archive.zip();
+    let mut folders = FolderCache { root, folders: std::collections::HashMap::new() };
+    let mut report = ExtractReport::default();
+    let mut progress = ExtractProgress {
+        total_entries: archive.entries.len(),
+        total_bytes: archive.entries.iter().map(|entry| entry.raw_size).sum(),
+        ..ExtractProgress::default()
+    };
+
+    for entry in &archive.entries {
+        progress.current = entry.entry_name.to_string();
+        on_progress.emit(progress.clone());
+        let bytes_before = progress.done_bytes;
+        match extract_entry(&mut zip, entry, &password, &mut folders, policy, &mut progress, &on_progress).await {
+            Ok(true) => { report.extracted += 1; }
+            Ok(false) => { report.skipped += 1; }
+            Err(err) => { report.failed.push(err); }
+        }
+        progress.done_bytes = bytes_before + entry.raw_size;
+        progress.done_entries += 1;
+    }
+    report
+}
@@ -0,0 +1,184 @@
+use yew::TargetCast;
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::extract_archive::model::{self, ExtractProgress, ExtractReport, OverwritePolicy};
+
+pub enum ExtractArchiveMsg {
+    ChangePolicy(String),
+    /// Ask for a folder and start writing entries into it.
+    Start,
+    Progress(ExtractProgress),
+    /// Extraction has finished, `None` if the user closed the folder picker.
+    Finished(Result<Option<ExtractReport>, (String, String)>),
+    Reset,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct ExtractArchiveProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    /// Password entered for the archive.
+    pub password: yew::AttrValue,
+}
+
+enum ExtractState {
+    Idle,
+    InProcess(ExtractProgress),
+    Done(ExtractReport),
+    Fail((String, String)),
+}
+
+/// Component - extracts all entries of an archive into a folder chosen by the user.
+pub struct ExtractArchiveComponent {
+    policy: OverwritePolicy,
+    state: ExtractState,
+}
+
+impl yew::Component for ExtractArchiveComponent {
+    type Message = ExtractArchiveMsg;
+    type Properties = ExtractArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            policy: OverwritePolicy::Skip,
+            state: ExtractState::Idle,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ExtractArchiveMsg::ChangePolicy(value) => {
+                self.policy = OverwritePolicy::from(value);
+                true
+            }
+            ExtractArchiveMsg::Start => {
+                let archive = ctx.props().archive.clone();
+                let password = ctx.props().password.to_string();
+                let policy = self.policy;
+                let on_progress = ctx.link().callback(ExtractArchiveMsg::Progress);
+                let on_finished = ctx.link().callback(ExtractArchiveMsg::Finished);
+                wasm_bindgen_futures::spawn_local(async move {
+                    let res = match crate::share::fs_tools::pick_directory().await {
+                        Ok(Some(root)) => Ok(Some(model::extract_to_folder(archive, password, root, policy, on_progress).await)),
+                        Ok(None) => Ok(None),
+                        Err(err) => Err(err),
+                    };
+                    on_finished.emit(res);
+                });
+                self.state = ExtractState::InProcess(ExtractProgress::default());
+                true
+            }
+            ExtractArchiveMsg::Progress(progress) => {
+                self.state = ExtractState::InProcess(progress);
+                true
+            }
+            ExtractArchiveMsg::Finished(res) => {
+                self.state = match res {
+                    Ok(Some(report)) => ExtractState::Done(report),
+                    Ok(None) => ExtractState::Idle,
+                    Err(err) => ExtractState::Fail(err),
+                };
+                true
+            }
+            ExtractArchiveMsg::Reset => {
+                self.state = ExtractState::Idle;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let reset_onclick = &ctx.link().callback(|_| ExtractArchiveMsg::Reset);
+        match &self.state {
+            ExtractState::Idle => {
+                let onchange_policy = &ctx.link().callback(move |e: web_sys::Event| {
+                    let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                    ExtractArchiveMsg::ChangePolicy(input.value())
+                });
+                let start_onclick = &ctx.link().callback(|_| ExtractArchiveMsg::Start);
+                let is_supported = crate::share::fs_tools::is_directory_picker_supported();
+                let title = if is_supported {
+                    "Write every entry into a folder. Files get the current time: browsers do not allow setting modification times."
+                } else {
+                    "This browser does not support the File System Access API."
+                };
+                yew::html! {
+                    <div class={yew::classes!("input-group", "input-group-sm")}>
+                        <span class={yew::classes!("input-group-text")}>{"Existing files"}</span>
+                        <select class={yew::classes!("form-select", "form-select-sm")}
+                            style="max-width: 135px;"
+                            onchange={onchange_policy}
+                        >
+                            <option selected={self.policy == OverwritePolicy::Skip} value={"Skip"}>{"Skip"}</option>
+                            <option selected={self.policy == OverwritePolicy::Overwrite} value={"Overwrite"}>{"Overwrite"}</option>
+                            <option selected={self.policy == OverwritePolicy::KeepBoth} value={"KeepBoth"}>{"Keep both"}</option>
+                        </select>
+                        <button
+                            type="button"
+                            class={yew::classes!("btn", "btn-outline-dark")}
+                            disabled={!is_supported}
+                            {title}
+                            onclick={start_onclick}
+                        >
+                            {"Extract all to folder"}
+                        </button>
+                    </div>
+                }
+            }
+            ExtractState::InProcess(progress) => {
+                let percent = match (100 * progress.done_bytes).checked_div(progress.total_bytes) {
+                    Some(percent) => percent as usize,
+                    None => 100 * progress.done_entries / progress.total_entries.max(1),
+                };
+                yew::html! {
+                    <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                        <small class={yew::classes!("text-truncate")}>
+                            {format!(
+                                "Extracting {}/{}: {}",
+                                progress.done_entries + 1,
+                                progress.total_entries,
+                                progress.current
+                            )}
+                        </small>
+                        <div class={yew::classes!("progress")} role="progressbar" aria-label="Extraction progress">
+                            <div class={yew::classes!("progress-bar")} style={format!("width: {percent}%")}>
+                                {format!("{percent}%")}
+                            </div>
+                        </div>
+                    </div>
+                }
+            }
+            ExtractState::Done(report) => yew::html! {
+                <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                    <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center")}>
+                        <span class={yew::classes!(if report.failed.is_empty() { "text-success" } else { "text-danger" })}>
+                            {format!(
+                                "Extracted: {}, skipped: {}, failed: {}.",
+                                report.extracted,
+                                report.skipped,
+                                report.failed.len()
+                            )}
+                        </span>
+                        <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                            {"Back"}
+                        </button>
+                    </div>
+                    { report.failed.iter().map(|(msg, detail)| yew::html! {
+                        <small
+                            class={yew::classes!("d-block", "text-danger")}
+                            data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                            title={detail.clone()}
+                        >{msg}</small>
+                    }).collect::<yew::Html>() }
+                </div>
+            },
+            ExtractState::Fail((msg, detail)) => yew::html! {
+                <div
+                    class={yew::classes!("alert", "alert-danger", "mb-0")}
+                    data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                    title={detail.clone()}
+                    onclick={reset_onclick}
+                >{format!("Error: {msg}")}
+                </div>
+            },
+        }
+    }
+}
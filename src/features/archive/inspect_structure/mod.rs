@@ -0,0 +1,6 @@
+//! Низкоуровневый просмотр заголовков и служебных записей архива.
+
+mod ui;
+mod model;
+
+pub use ui::InspectStructureComponent;
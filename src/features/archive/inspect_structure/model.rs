@@ -0,0 +1,620 @@
+use crate::share::{read_u16_le, read_u32_le, read_u64_le};
+use crate::share::zip_format::{
+    has_signature, CENTRAL_HEADER_SIGNATURE, DATA_DESCRIPTOR_SIGNATURE, END_OF_CENTRAL_DIRECTORY_SIGNATURE,
+    END_OF_CENTRAL_DIRECTORY_SIZE, FLAG_DATA_DESCRIPTOR, LOCAL_HEADER_SIGNATURE, ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIGNATURE,
+    ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIZE, ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE, ZIP64_EXTRA_FIELD_ID,
+};
+
+/// A decoded extra field.
+#[derive(PartialEq, Clone)]
+pub(crate) struct ExtraField {
+    pub id: u16,
+    pub name: &'static str,
+    pub offset: u64,
+    pub size: u16,
+    pub values: Vec<(&'static str, String)>,
+}
+
+/// A header record with its fields in the order they are stored.
+#[derive(PartialEq, Clone)]
+pub(crate) struct Record {
+    pub title: &'static str,
+    pub offset: u64,
+    pub size: u64,
+    pub values: Vec<(&'static str, String)>,
+    pub extra_fields: Vec<ExtraField>,
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct EntryStructure {
+    pub name: String,
+    pub central: Record,
+    pub local: Option<Record>,
+    pub descriptor: Option<Record>,
+    /// Inconsistencies found in the records of the entry.
+    pub issues: Vec<String>,
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct ArchiveStructure {
+    pub end_records: Vec<Record>,
+    pub entries: Vec<EntryStructure>,
+    /// Inconsistencies of the archive as a whole.
+    pub issues: Vec<String>,
+}
+
+fn hex_bytes(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect::<Vec<_>>().join(" ")
+}
+
+fn dos_date_time(date: u16, time: u16) -> String {
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02} (0x{date:04x} 0x{time:04x})",
+        1980 + (date >> 9),
+        (date >> 5) & 0x0f,
+        date & 0x1f,
+        time >> 11,
+        (time >> 5) & 0x3f,
+        (time & 0x1f) * 2,
+    )
+}
+
+fn unix_time(seconds: u32) -> String {
+    chrono::DateTime::from_timestamp(seconds as i64, 0)
+        .map(|time| format!("{} UTC ({seconds})", time.format("%Y-%m-%d %H:%M:%S")))
+        .unwrap_or_else(|| seconds.to_string())
+}
+
+/// Windows FILETIME: 100 ns intervals since 1601-01-01.
+fn file_time(value: u64) -> String {
+    const SECONDS_FROM_1601_TO_1970: i64 = 11_644_473_600;
+    chrono::DateTime::from_timestamp((value / 10_000_000) as i64 - SECONDS_FROM_1601_TO_1970, 0)
+        .map(|time| format!("{} UTC", time.format("%Y-%m-%d %H:%M:%S")))
+        .unwrap_or_else(|| value.to_string())
+}
+
+fn method_name(method: u16) -> String {
+    let name = match method {
+        0 => "Stored",
+        1 => "Shrunk",
+        6 => "Imploded",
+        8 => "Deflated",
+        9 => "Deflate64",
+        12 => "BZIP2",
+        14 => "LZMA",
+        93 => "Zstandard",
+        95 => "XZ",
+        98 => "PPMd",
+        99 => "AES encrypted",
+        _ => "Unknown",
+    };
+    format!("{method} ({name})")
+}
+
+fn flags_description(flags: u16) -> String {
+    let names: Vec<&str> = [
+        (0, "encrypted"),
+        (3, "data descriptor"),
+        (6, "strong encryption"),
+        (11, "UTF-8 names"),
+        (13, "masked local header"),
+    ].iter()
+        .filter(|(bit, _)| flags & (1 << bit) != 0)
+        .map(|(_, name)| *name)
+        .collect();
+    format!("0x{flags:04x} ({})", if names.is_empty() { "none".to_string() } else { names.join(", ") })
+}
+
+fn version_description(version: u16) -> String {
+    format!("{}.{}", (version & 0xff) / 10, (version & 0xff) % 10)
+}
+
+fn version_made_by(version: u16) -> String {
+    let host = match version >> 8 {
+        0 => "MS-DOS",
+        3 => "Unix",
+        7 => "Macintosh",
+        10 => "Windows NTFS",
+        19 => "OS X",
+        _ => "other",
+    };
+    format!("{} on {host} (0x{version:04x})", version_description(version))
+}
+
+/// Values of a Zip64 extra field. The field contains only the values
+/// whose fixed-size counterparts in the header are saturated.
+fn zip64_values(block: &[u8], saturated: &[(&'static str, bool)]) -> Vec<(&'static str, String)> {
+    let mut values = vec![];
+    let mut offset = 0;
+    for (name, is_present) in saturated {
+        if !is_present {
+            continue;
+        }
+        match read_u64_le(block, offset) {
+            Some(value) => values.push((*name, value.to_string())),
+            None => break,
+        }
+        offset += 8;
+    }
+    if offset < block.len() {
+        values.push(("Other data", hex_bytes(&block[offset.min(block.len())..])));
+    }
+    values
+}
+
+/// Decodes the extra fields of a header.
+fn extra_fields(
+    extra: &[u8],
+    extra_offset: u64,
+    name: &[u8],
+    zip64_saturated: &[(&'static str, bool)],
+) -> (Vec<ExtraField>, Vec<String>) {
+    let mut fields = vec![];
+    let mut issues = vec![];
+    let mut offset = 0;
+    while offset < extra.len() {
+        let (Some(id), Some(size)) = (read_u16_le(extra, offset), read_u16_le(extra, offset + 2)) else {
+            issues.push(format!("{} trailing byte(s) in the extra field.", extra.len() - offset));
+            break;
+        };
+        let Some(block) = extra.get(offset + 4..offset + 4 + size as usize) else {
+            issues.push(format!("Extra field 0x{id:04x} is longer than the extra data."));
+            break;
+        };
+        let (name_of_field, values) = match id {
+            ZIP64_EXTRA_FIELD_ID => ("Zip64 extended information", zip64_values(block, zip64_saturated)),
+            0x000a => {
+                let mut values = vec![];
+                if let (Some(1), Some(mtime), Some(atime), Some(ctime)) = (
+                    read_u16_le(block, 4),
+                    read_u64_le(block, 8),
+                    read_u64_le(block, 16),
+                    read_u64_le(block, 24),
+                ) {
+                    values.push(("Modified", file_time(mtime)));
+                    values.push(("Accessed", file_time(atime)));
+                    values.push(("Created", file_time(ctime)));
+                }
+                ("NTFS times", values)
+            }
+            0x5455 => {
+                let mut values = vec![];
+                if let Some(flags) = block.first() {
+                    values.push(("Flags", format!("0x{flags:02x}")));
+                    // The times follow in the order of the flag bits; the central header keeps only the first.
+                    let labels = ["Modified", "Accessed", "Created"].into_iter()
+                        .enumerate()
+                        .filter(|(bit, _)| flags & (1 << bit) != 0)
+                        .map(|(_, label)| label);
+                    for (label, time) in labels.zip(block[1..].chunks_exact(4)) {
+                        values.push((label, unix_time(u32::from_le_bytes([time[0], time[1], time[2], time[3]]))));
+                    }
+                }
+                ("Extended timestamp", values)
+            }
+            0x7075 | 0x6375 => {
+                let mut values = vec![];
+                if let (Some(version), Some(crc32)) = (block.first(), read_u32_le(block, 1)) {
+                    values.push(("Version", version.to_string()));
+                    values.push(("CRC32 of the header value", format!("0x{crc32:08x}")));
+                    values.push(("UTF-8 value", String::from_utf8_lossy(&block[5..]).to_string()));
+                    if id == 0x7075 && crc32 != crc32fast::hash(name) {
+                        issues.push("The Unicode path field does not match the header name and is ignored by readers.".to_string());
+                    }
+                }
+                (if id == 0x7075 { "Info-ZIP Unicode path" } else { "Info-ZIP Unicode comment" }, values)
+            }
+            0x7875 => {
+                let mut values = vec![];
+                if let (Some(version), Some(uid_size)) = (block.first(), block.get(1)) {
+                    let uid_size = *uid_size as usize;
+                    let uid = block.get(2..2 + uid_size).map(hex_bytes).unwrap_or_default();
+                    let gid = block.get(3 + uid_size..).map(hex_bytes).unwrap_or_default();
+                    values.push(("Version", version.to_string()));
+                    values.push(("UID", uid));
+                    values.push(("GID", gid));
+                }
+                ("Info-ZIP Unix UID/GID", values)
+            }
+            0x9901 => {
+                let mut values = vec![];
+                if let (Some(vendor_version), Some(vendor_id), Some(strength), Some(method)) = (
+                    read_u16_le(block, 0),
+                    block.get(2..4),
+                    block.get(4),
+                    read_u16_le(block, 5),
+                ) {
+                    values.push(("Vendor version", format!("AE-{vendor_version}")));
+                    values.push(("Vendor ID", String::from_utf8_lossy(vendor_id).to_string()));
+                    let bits = match strength {
+                        1 => "128",
+                        2 => "192",
+                        3 => "256",
+                        _ => "unknown",
+                    };
+                    values.push(("Key length", format!("{bits} bits")));
+                    values.push(("Actual compression method", method_name(method)));
+                }
+                ("WinZip AES encryption", values)
+            }
+            0xcafe => ("JAR marker", vec![]),
+            _ => ("Unknown", vec![("Data", hex_bytes(block))]),
+        };
+        fields.push(ExtraField {
+            id,
+            name: name_of_field,
+            offset: extra_offset + offset as u64,
+            size,
+            values,
+        });
+        offset += 4 + size as usize;
+    }
+    (fields, issues)
+}
+
+/// Fields shared by the local and central headers, starting with the version needed to extract.
+struct CommonFields {
+    version_needed: u16,
+    flags: u16,
+    method: u16,
+    time: u16,
+    date: u16,
+    crc32: u32,
+    compressed_size: u64,
+    size: u64,
+    name: Vec<u8>,
+}
+
+impl CommonFields {
+    fn read(data: &[u8], offset: usize) -> Option<Self> {
+        Some(Self {
+            version_needed: read_u16_le(data, offset)?,
+            flags: read_u16_le(data, offset + 2)?,
+            method: read_u16_le(data, offset + 4)?,
+            time: read_u16_le(data, offset + 6)?,
+            date: read_u16_le(data, offset + 8)?,
+            crc32: read_u32_le(data, offset + 10)?,
+            compressed_size: read_u32_le(data, offset + 14)? as u64,
+            size: read_u32_le(data, offset + 18)? as u64,
+            name: vec![],
+        })
+    }
+
+    fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Version needed to extract", version_description(self.version_needed)),
+            ("General purpose flags", flags_description(self.flags)),
+            ("Compression method", method_name(self.method)),
+            ("Last modified", dos_date_time(self.date, self.time)),
+            ("CRC32", format!("0x{:08x}", self.crc32)),
+            ("Compressed size", self.compressed_size.to_string()),
+            ("Uncompressed size", self.size.to_string()),
+        ]
+    }
+
+    /// Replaces saturated sizes with the values of the Zip64 extra field.
+    fn apply_zip64(&mut self, extra_fields: &[ExtraField]) -> Option<u64> {
+        let zip64 = extra_fields.iter().find(|field| field.id == ZIP64_EXTRA_FIELD_ID)?;
+        let mut header_start = None;
+        for (name, value) in &zip64.values {
+            let Ok(value) = value.parse::<u64>() else { continue; };
+            match *name {
+                "Uncompressed size" => self.size = value,
+                "Compressed size" => self.compressed_size = value,
+                "Local header offset" => header_start = Some(value),
+                _ => {}
+            }
+        }
+        header_start
+    }
+}
+
+/// Finds the end of central directory record by scanning back from the end of the file.
+fn find_end_of_central_directory(data: &[u8]) -> Option<usize> {
+    let last = data.len().checked_sub(END_OF_CENTRAL_DIRECTORY_SIZE)?;
+    let first = last.saturating_sub(u16::MAX as usize);
+    (first..=last).rev().find(|pos| has_signature(data, *pos, END_OF_CENTRAL_DIRECTORY_SIGNATURE))
+}
+
+fn read_end_records(data: &[u8], issues: &mut Vec<String>) -> Option<(Vec<Record>, u64, u64)> {
+    let Some(pos) = find_end_of_central_directory(data) else {
+        issues.push("The end of central directory record was not found.".to_string());
+        return None;
+    };
+    let entries = read_u16_le(data, pos + 10)?;
+    let mut central_directory_size = read_u32_le(data, pos + 12)? as u64;
+    let mut central_directory_start = read_u32_le(data, pos + 16)? as u64;
+    let comment_len = read_u16_le(data, pos + 20)?;
+    let comment = data.get(pos + 22..pos + 22 + comment_len as usize).unwrap_or_default();
+    let record_end = pos + 22 + comment_len as usize;
+    if record_end > data.len() {
+        issues.push("The archive comment is truncated.".to_string());
+    } else if record_end < data.len() {
+        issues.push(format!("{} byte(s) after the end of central directory record.", data.len() - record_end));
+    }
+    let mut records = vec![Record {
+        title: "End of central directory",
+        offset: pos as u64,
+        size: 22 + comment_len as u64,
+        values: vec![
+            ("Disk number", read_u16_le(data, pos + 4)?.to_string()),
+            ("Disk with the central directory", read_u16_le(data, pos + 6)?.to_string()),
+            ("Entries on this disk", read_u16_le(data, pos + 8)?.to_string()),
+            ("Total entries", entries.to_string()),
+            ("Central directory size", central_directory_size.to_string()),
+            ("Central directory offset", central_directory_start.to_string()),
+            ("Comment", String::from_utf8_lossy(comment).to_string()),
+        ],
+        extra_fields: vec![],
+    }];
+
+    let locator = pos.checked_sub(ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIZE)
+        .filter(|locator| has_signature(data, *locator, ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIGNATURE));
+    if let Some(locator) = locator {
+        let zip64_start = read_u64_le(data, locator + 8)?;
+        records.push(Record {
+            title: "Zip64 end of central directory locator",
+            offset: locator as u64,
+            size: ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIZE as u64,
+            values: vec![
+                ("Disk with the Zip64 record", read_u32_le(data, locator + 4)?.to_string()),
+                ("Zip64 record offset", zip64_start.to_string()),
+                ("Total disks", read_u32_le(data, locator + 16)?.to_string()),
+            ],
+            extra_fields: vec![],
+        });
+        let zip64 = zip64_start as usize;
+        if has_signature(data, zip64, ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE) {
+            central_directory_size = read_u64_le(data, zip64 + 40)?;
+            central_directory_start = read_u64_le(data, zip64 + 48)?;
+            records.push(Record {
+                title: "Zip64 end of central directory",
+                offset: zip64_start,
+                size: read_u64_le(data, zip64 + 4)? + 12,
+                values: vec![
+                    ("Version made by", version_made_by(read_u16_le(data, zip64 + 12)?)),
+                    ("Version needed to extract", version_description(read_u16_le(data, zip64 + 14)?)),
+                    ("Entries on this disk", read_u64_le(data, zip64 + 24)?.to_string()),
+                    ("Total entries", read_u64_le(data, zip64 + 32)?.to_string()),
+                    ("Central directory size", central_directory_size.to_string()),
+                    ("Central directory offset", central_directory_start.to_string()),
+                ],
+                extra_fields: vec![],
+            });
+        } else {
+            issues.push(format!("The Zip64 end of central directory record is missing at offset {zip64_start}."));
+        }
+    }
+
+    // Data prepended to the archive, as in self-extracting archives, shifts all offsets.
+    let end_of_central_directory = records.last().map(|record| record.offset).unwrap_or(pos as u64);
+    let expected_start = end_of_central_directory.saturating_sub(central_directory_size);
+    if expected_start != central_directory_start {
+        issues.push(format!(
+            "The central directory is at offset {expected_start}, but the end record points to {central_directory_start}: \
+            {} byte(s) were prepended to the archive.",
+            expected_start as i64 - central_directory_start as i64,
+        ));
+    }
+    Some((records, expected_start, expected_start - central_directory_start.min(expected_start)))
+}
+
+/// Compares the local header with the central header and the data descriptor.
+fn compare_headers(central: &CommonFields, local: &CommonFields, descriptor: Option<(u32, u64, u64)>) -> Vec<String> {
+    let mut issues = vec![];
+    let mut check = |name: &str, central_value: String, local_value: String| {
+        if central_value != local_value {
+            issues.push(format!("{name} differs: central {central_value}, local {local_value}."));
+        }
+    };
+    check("Version needed", central.version_needed.to_string(), local.version_needed.to_string());
+    check("Flags", format!("0x{:04x}", central.flags), format!("0x{:04x}", local.flags));
+    check("Compression method", central.method.to_string(), local.method.to_string());
+    check("Modification time", dos_date_time(central.date, central.time), dos_date_time(local.date, local.time));
+    check("Name", String::from_utf8_lossy(&central.name).to_string(), String::from_utf8_lossy(&local.name).to_string());
+    if local.flags & FLAG_DATA_DESCRIPTOR == 0 {
+        check("CRC32", format!("0x{:08x}", central.crc32), format!("0x{:08x}", local.crc32));
+        check("Compressed size", central.compressed_size.to_string(), local.compressed_size.to_string());
+        check("Uncompressed size", central.size.to_string(), local.size.to_string());
+    }
+    match descriptor {
+        Some((crc32, compressed_size, size))
+            if (crc32, compressed_size, size) != (central.crc32, central.compressed_size, central.size) => {
+            issues.push(format!(
+                "Data descriptor differs from the central header: CRC32 0x{crc32:08x}, sizes {compressed_size}/{size}."
+            ));
+        }
+        None if local.flags & FLAG_DATA_DESCRIPTOR != 0 => {
+            issues.push("The data descriptor announced by the flags was not found.".to_string());
+        }
+        _ => {}
+    }
+    issues
+}
+
+/// Parses the local header and the data descriptor of an entry.
+/// Returns the records and the byte range occupied by the entry.
+fn read_local_records(
+    data: &[u8],
+    header_start: usize,
+    central: &CommonFields,
+    issues: &mut Vec<String>,
+) -> Option<(Record, Option<Record>, std::ops::Range<u64>)> {
+    if !has_signature(data, header_start, LOCAL_HEADER_SIGNATURE) {
+        issues.push(format!("No local header signature at offset {header_start}."));
+        return None;
+    }
+    let mut local = CommonFields::read(data, header_start + 4)?;
+    let name_len = read_u16_le(data, header_start + 26)? as usize;
+    let extra_len = read_u16_le(data, header_start + 28)? as usize;
+    let name_start = header_start + 30;
+    local.name = data.get(name_start..name_start + name_len)?.to_vec();
+    let extra = data.get(name_start + name_len..name_start + name_len + extra_len)?;
+    let saturated = [
+        ("Uncompressed size", local.size == u32::MAX as u64),
+        ("Compressed size", local.compressed_size == u32::MAX as u64),
+    ];
+    let (extra_fields, extra_issues) = extra_fields(extra, (name_start + name_len) as u64, &local.name, &saturated);
+    issues.extend(extra_issues);
+    local.apply_zip64(&extra_fields);
+    let is_zip64 = extra_fields.iter().any(|field| field.id == ZIP64_EXTRA_FIELD_ID);
+
+    let mut values = local.values();
+    values.push(("File name", String::from_utf8_lossy(&local.name).to_string()));
+    let data_start = name_start + name_len + extra_len;
+    values.push(("Data offset", data_start.to_string()));
+    let local_record = Record {
+        title: "Local file header",
+        offset: header_start as u64,
+        size: (30 + name_len + extra_len) as u64,
+        values,
+        extra_fields,
+    };
+
+    let data_end = data_start as u64 + central.compressed_size;
+    let mut end = data_end;
+    let mut descriptor = None;
+    let mut descriptor_values = None;
+    if local.flags & FLAG_DATA_DESCRIPTOR != 0 {
+        let mut pos = data_end as usize;
+        let has_descriptor_signature = has_signature(data, pos, DATA_DESCRIPTOR_SIGNATURE);
+        if has_descriptor_signature {
+            pos += 4;
+        }
+        let read_size = |offset: usize| if is_zip64 { read_u64_le(data, offset) } else { read_u32_le(data, offset).map(u64::from) };
+        let size_len = if is_zip64 { 8 } else { 4 };
+        if let (Some(crc32), Some(compressed_size), Some(size)) = (
+            read_u32_le(data, pos),
+            read_size(pos + 4),
+            read_size(pos + 4 + size_len),
+        ) {
+            end = (pos + 4 + 2 * size_len) as u64;
+            descriptor_values = Some((crc32, compressed_size, size));
+            descriptor = Some(Record {
+                title: "Data descriptor",
+                offset: data_end,
+                size: end - data_end,
+                values: vec![
+                    ("Signature", if has_descriptor_signature { "present" } else { "absent" }.to_string()),
+                    ("CRC32", format!("0x{crc32:08x}")),
+                    ("Compressed size", compressed_size.to_string()),
+                    ("Uncompressed size", size.to_string()),
+                ],
+                extra_fields: vec![],
+            });
+        }
+    }
+    if end > data.len() as u64 {
+        issues.push(format!("The entry data ends at offset {end}, after the end of the file."));
+    }
+    issues.extend(compare_headers(central, &local, descriptor_values));
+    Some((local_record, descriptor, header_start as u64..end))
+}
+
+/// Parses all headers of the archive and reports inconsistencies between them.
+pub(crate) fn inspect_structure(data: &[u8]) -> ArchiveStructure {
+    let mut issues = vec![];
+    let Some((end_records, central_directory_start, shift)) = read_end_records(data, &mut issues) else {
+        return ArchiveStructure { end_records: vec![], entries: vec![], issues };
+    };
+
+    let mut entries = vec![];
+    let mut ranges = vec![];
+    let mut pos = central_directory_start as usize;
+    while has_signature(data, pos, CENTRAL_HEADER_SIGNATURE) {
+        let Some(mut central) = CommonFields::read(data, pos + 6) else { break; };
+        let (Some(version_made_by_value), Some(name_len), Some(extra_len), Some(comment_len), Some(header_start)) = (
+            read_u16_le(data, pos + 4),
+            read_u16_le(data, pos + 28),
+            read_u16_le(data, pos + 30),
+            read_u16_le(data, pos + 32),
+            read_u32_le(data, pos + 42),
+        ) else { break; };
+        let (name_len, extra_len, comment_len) = (name_len as usize, extra_len as usize, comment_len as usize);
+        let name_start = pos + 46;
+        let (Some(name), Some(extra), Some(comment)) = (
+            data.get(name_start..name_start + name_len),
+            data.get(name_start + name_len..name_start + name_len + extra_len),
+            data.get(name_start + name_len + extra_len..name_start + name_len + extra_len + comment_len),
+        ) else {
+            issues.push(format!("The central directory is truncated at offset {pos}."));
+            break;
+        };
+        central.name = name.to_vec();
+
+        let mut entry_issues = vec![];
+        let saturated = [
+            ("Uncompressed size", central.size == u32::MAX as u64),
+            ("Compressed size", central.compressed_size == u32::MAX as u64),
+            ("Local header offset", header_start == u32::MAX),
+        ];
+        let (extra_fields, extra_issues) = extra_fields(extra, (name_start + name_len) as u64, name, &saturated);
+        entry_issues.extend(extra_issues);
+        let header_start = central.apply_zip64(&extra_fields).unwrap_or(header_start as u64);
+
+        let mut values = vec![("Version made by", version_made_by(version_made_by_value))];
+        values.extend(central.values());
+        values.extend([
+            ("File name", String::from_utf8_lossy(name).to_string()),
+            ("File comment", String::from_utf8_lossy(comment).to_string()),
+            ("Disk number", read_u16_le(data, pos + 34).unwrap_or(0).to_string()),
+            ("Internal attributes", format!("0x{:04x}", read_u16_le(data, pos + 36).unwrap_or(0))),
+            ("External attributes", format!("0x{:08x}", read_u32_le(data, pos + 38).unwrap_or(0))),
+            ("Local header offset", header_start.to_string()),
+        ]);
+        let central_record = Record {
+            title: "Central directory header",
+            offset: pos as u64,
+            size: (46 + name_len + extra_len + comment_len) as u64,
+            values,
+            extra_fields,
+        };
+
+        let (local, descriptor) = match read_local_records(data, (header_start + shift) as usize, &central, &mut entry_issues) {
+            Some((local, descriptor, range)) => {
+                ranges.push((range, entries.len()));
+                (Some(local), descriptor)
+            }
+            None => (None, None),
+        };
+        entries.push(EntryStructure {
+            name: String::from_utf8_lossy(name).to_string(),
+            central: central_record,
+            local,
+            descriptor,
+            issues: entry_issues,
+        });
+        pos = name_start + name_len + extra_len + comment_len;
+    }
+
+    let expected_entries = end_records.iter()
+        .rev()
+        .find_map(|record| record.values.iter().find(|(name, _)| *name == "Total entries"))
+        .and_then(|(_, value)| value.parse::<usize>().ok());
+    if let Some(expected_entries) = expected_entries.filter(|expected| *expected != entries.len()) {
+        issues.push(format!(
+            "The end record announces {expected_entries} entries, the central directory contains {}.",
+            entries.len()
+        ));
+    }
+
+    // Entries must not overlap, and the bytes between them are not used by any entry.
+    ranges.sort_by_key(|(range, _)| range.start);
+    let mut covered_until = shift;
+    for (range, ind) in &ranges {
+        if range.start < covered_until {
+            entries[*ind].issues.push(format!("The entry overlaps the previous entry by {} byte(s).", covered_until - range.start));
+        } else if range.start > covered_until {
+            issues.push(format!("{} unused byte(s) at offset {covered_until}.", range.start - covered_until));
+        }
+        covered_until = covered_until.max(range.end);
+    }
+    if central_directory_start > covered_until {
+        issues.push(format!(
+            "{} unused byte(s) before the central directory at offset {covered_until}.",
+            central_directory_start - covered_until
+        ));
+    }
+
+    ArchiveStructure { end_records, entries, issues }
+}
@@ -0,0 +1,152 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::inspect_structure::model::{self, ArchiveStructure, EntryStructure, Record};
+
+pub enum InspectStructureMsg {
+    Inspect,
+    Hide,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct InspectStructureProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+}
+
+/// Component - shows the headers of an archive and the inconsistencies between them.
+pub struct InspectStructureComponent {
+    structure: Option<ArchiveStructure>,
+}
+
+impl InspectStructureComponent {
+    fn view_record(record: &Record) -> yew::Html {
+        yew::html! {
+            <table class={yew::classes!("table", "table-sm", "mb-2")}>
+                <thead>
+                    <tr>
+                        <th colspan="2">
+                            {format!("{} at offset {} ({} bytes)", record.title, record.offset, record.size)}
+                        </th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { record.values.iter().map(|(name, value)| yew::html! {
+                        <tr>
+                            <td class={yew::classes!("text-muted", "text-nowrap")}>{name}</td>
+                            <td class={yew::classes!("font-monospace", "text-break")}>{value}</td>
+                        </tr>
+                    }).collect::<yew::Html>() }
+                    { record.extra_fields.iter().map(|field| yew::html! {
+                        <>
+                            <tr>
+                                <td colspan="2" class={yew::classes!("fst-italic")}>
+                                    {format!("Extra field 0x{:04x} {} at offset {} ({} bytes)", field.id, field.name, field.offset, field.size)}
+                                </td>
+                            </tr>
+                            { field.values.iter().map(|(name, value)| yew::html! {
+                                <tr>
+                                    <td class={yew::classes!("text-muted", "text-nowrap", "ps-4")}>{name}</td>
+                                    <td class={yew::classes!("font-monospace", "text-break")}>{value}</td>
+                                </tr>
+                            }).collect::<yew::Html>() }
+                        </>
+                    }).collect::<yew::Html>() }
+                </tbody>
+            </table>
+        }
+    }
+
+    fn view_entry(entry: &EntryStructure) -> yew::Html {
+        yew::html! {
+            <details class={yew::classes!("list-group-item", "p-1")}>
+                <summary class={yew::classes!("d-flex", "justify-content-between", "align-items-center")}>
+                    <small class={yew::classes!("text-truncate")}>{&entry.name}</small>
+                    if !entry.issues.is_empty() {
+                        <span class={yew::classes!("badge", "bg-warning", "text-dark")}>
+                            {format!("{} issue(s)", entry.issues.len())}
+                        </span>
+                    }
+                </summary>
+                { entry.issues.iter().map(|issue| yew::html! {
+                    <small class={yew::classes!("d-block", "text-danger")}>{issue}</small>
+                }).collect::<yew::Html>() }
+                {Self::view_record(&entry.central)}
+                if let Some(local) = &entry.local {
+                    {Self::view_record(local)}
+                }
+                if let Some(descriptor) = &entry.descriptor {
+                    {Self::view_record(descriptor)}
+                }
+            </details>
+        }
+    }
+}
+
+impl yew::Component for InspectStructureComponent {
+    type Message = InspectStructureMsg;
+    type Properties = InspectStructureProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self { structure: None }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            InspectStructureMsg::Inspect => {
+                self.structure = Some(model::inspect_structure(ctx.props().archive.data()));
+                true
+            }
+            InspectStructureMsg::Hide => {
+                self.structure = None;
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &yew::Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.structure = None;
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let Some(structure) = &self.structure else {
+            let onclick = &ctx.link().callback(|_| InspectStructureMsg::Inspect);
+            return yew::html! {
+                <button
+                    type="button"
+                    class={yew::classes!("btn", "btn-sm", "btn-outline-dark")}
+                    title="Show local headers, central directory records, extra fields and data descriptors."
+                    {onclick}
+                >
+                    {"Inspect structure"}
+                </button>
+            };
+        };
+
+        let onclick = &ctx.link().callback(|_| InspectStructureMsg::Hide);
+        let entry_issues: usize = structure.entries.iter().map(|entry| entry.issues.len()).sum();
+        let total_issues = structure.issues.len() + entry_issues;
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center", "mb-1")}>
+                    <span class={yew::classes!(if total_issues == 0 { "text-success" } else { "text-danger" })}>
+                        {format!("{} entries, {} inconsistencies found.", structure.entries.len(), total_issues)}
+                    </span>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} {onclick}>
+                        {"Hide"}
+                    </button>
+                </div>
+                { structure.issues.iter().map(|issue| yew::html! {
+                    <small class={yew::classes!("d-block", "text-danger")}>{issue}</small>
+                }).collect::<yew::Html>() }
+                <div class={yew::classes!("overflow-auto")} style="max-height: 50vh;">
+                    <details class={yew::classes!("mb-1")}>
+                        <summary><small>{"End of central directory records"}</small></summary>
+                        { structure.end_records.iter().map(Self::view_record).collect::<yew::Html>() }
+                    </details>
+                    <div class={yew::classes!("list-group")}>
+                        { structure.entries.iter().map(Self::view_entry).collect::<yew::Html>() }
+                    </div>
+                </div>
+            </div>
+        }
+    }
+}
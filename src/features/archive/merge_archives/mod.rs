@@ -0,0 +1,7 @@
+//! Объединение нескольких архивов в один.
+
+mod ui;
+mod model;
+
+pub use ui::MergeArchivesComponent;
+pub(crate) use model::RawArchiveWriter;
@@ -0,0 +1,379 @@
+use crate::entities::archive::model::{InfoAboutArchiveEntry, OpenedArchive};
+use crate::features::archive::append_files::{unique_name, ConflictResolution};
+use crate::features::archive::archive_limits::{self, ArchiveLimits};
+use crate::share::{self, zip_tools::{self, TypeEncryption}};
+use crate::share::zip_format::{
+    CENTRAL_HEADER_SIZE, DATA_DESCRIPTOR_SIGNATURE, END_OF_CENTRAL_DIRECTORY_SIGNATURE, FLAG_DATA_DESCRIPTOR, FLAG_UTF8,
+    LOCAL_HEADER_SIGNATURE, LOCAL_HEADER_SIZE, ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIGNATURE,
+    ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE, ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE, ZIP64_EXTRA_FIELD_ID, ZIP64_SIZE, ZIP64_VERSION,
+};
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct MergeOptions {
+    /// Put the entries of every archive into a folder named after it.
+    pub by_folder: bool,
+    /// What to do with an entry whose name is taken by an entry of a previous archive.
+    pub resolution: ConflictResolution,
+    /// Password for the unencrypted entries, empty to keep them unencrypted.
+    pub password: String,
+    pub type_encryption: TypeEncryption,
+}
+
+#[derive(PartialEq, Clone, Default)]
+pub(crate) struct MergeReport {
+    /// Entries copied without decompression.
+    pub copied_raw: usize,
+    /// Entries decompressed and encrypted with the new password.
+    pub encrypted: usize,
+    /// Entries written under a numbered name.
+    pub renamed: usize,
+    /// Entries dropped because of a name conflict.
+    pub skipped: usize,
+}
+
+/// Folder for the entries of the archive: its file name without the extension.
+/// Archives with the same name get folders like `export (1)`.
+fn folder_name(archive: &OpenedArchive, folders: &std::collections::HashSet<String>) -> String {
+    let file_name = archive.file_name.as_str();
+    let stem = match file_name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => file_name,
+    };
+    std::iter::once(stem.to_string())
+        .chain((1..).map(|n| format!("{stem} ({n})")))
+        .find(|folder| !folders.contains(folder))
+        .unwrap_or_default()
+}
+
+/// Decides where every entry goes, in the order of the archives and their entries.
+fn plan_merge<'a>(
+    sources: &'a [OpenedArchive],
+    options: &MergeOptions,
+    report: &mut MergeReport,
+) -> Vec<(&'a OpenedArchive, &'a InfoAboutArchiveEntry, String)> {
+    let mut plan: Vec<(&OpenedArchive, &InfoAboutArchiveEntry, String)> = vec![];
+    let mut positions = std::collections::HashMap::new();
+    let mut names = std::collections::HashSet::new();
+    let mut folders = std::collections::HashSet::new();
+    for archive in sources {
+        let prefix = if options.by_folder {
+            let folder = folder_name(archive, &folders);
+            folders.insert(folder.clone());
+            format!("{folder}/")
+        } else {
+            String::new()
+        };
+        for entry in &archive.entries {
+            let name = format!("{prefix}{}", entry.entry_name);
+            let Some(&position) = positions.get(&name) else {
+                positions.insert(name.clone(), plan.len());
+                names.insert(name.clone());
+                plan.push((archive, entry, name));
+                continue;
+            };
+            if entry.is_dir {
+                // The same folder in several archives is written once.
+                continue;
+            }
+            match options.resolution {
+                ConflictResolution::Skip => {
+                    report.skipped += 1;
+                }
+                ConflictResolution::Replace => {
+                    report.skipped += 1;
+                    plan[position] = (archive, entry, name);
+                }
+                ConflictResolution::KeepBoth => {
+                    let name = unique_name(&name, &names);
+                    report.renamed += 1;
+                    positions.insert(name.clone(), plan.len());
+                    names.insert(name.clone());
+                    plan.push((archive, entry, name));
+                }
+            }
+        }
+    }
+    plan
+}
+
+/// Writes an archive by copying local headers, data and data descriptors byte for byte.
+///
+/// Raw copies of the zip crate lose the encryption flag and the AES extra field,
+/// so encrypted entries would become unreadable.
+#[derive(Default)]
+pub(crate) struct RawArchiveWriter {
+    data: Vec<u8>,
+    central_directory: Vec<u8>,
+    entries: usize,
+}
+
+/// Finds the extra field with the ID and returns the offset of its data.
+fn find_extra_field(extra_field: &[u8], field_id: u16) -> Option<usize> {
+    let mut offset = 0;
+    while let (Some(id), Some(size)) = (
+        share::read_u16_le(extra_field, offset),
+        share::read_u16_le(extra_field, offset + 2),
+    ) {
+        if id == field_id {
+            return Some(offset + 4);
+        }
+        offset += 4 + size as usize;
+    }
+    None
+}
+
+/// Sets the offset of the local header in a central directory record.
+/// An offset that does not fit 32 bits is stored in the Zip64 extra field,
+/// which is added, or extended after the sizes it holds, if needed.
+fn set_header_offset(record: &mut Vec<u8>, offset: u64) -> Option<()> {
+    let in_zip64 = share::read_u32_le(record, 42)? == ZIP64_SIZE;
+    if !in_zip64 {
+        if let Some(offset) = u32::try_from(offset).ok().filter(|offset| *offset != ZIP64_SIZE) {
+            record.get_mut(42..46)?.copy_from_slice(&offset.to_le_bytes());
+            return Some(());
+        }
+    }
+    let extra_start = CENTRAL_HEADER_SIZE + share::read_u16_le(record, 28)? as usize;
+    let mut extra_len = share::read_u16_le(record, 30)? as usize;
+    let zip64_field = find_extra_field(record.get(extra_start..extra_start + extra_len)?, ZIP64_EXTRA_FIELD_ID);
+    // The offset follows the sizes that are also in the Zip64 extra field.
+    let mut position = zip64_field.map(|field| extra_start + field);
+    for size_offset in [24, 20] {
+        if share::read_u32_le(record, size_offset)? == ZIP64_SIZE {
+            position = position.map(|position| position + 8);
+        }
+    }
+    match (position, zip64_field) {
+        (Some(position), _) if in_zip64 => {
+            record.get_mut(position..position + 8)?.copy_from_slice(&offset.to_le_bytes());
+        }
+        (Some(position), Some(field)) => {
+            record.splice(position..position, offset.to_le_bytes());
+            let size_start = extra_start + field - 2;
+            let field_size = share::read_u16_le(record, size_start)? + 8;
+            record[size_start..size_start + 2].copy_from_slice(&field_size.to_le_bytes());
+            extra_len += 8;
+        }
+        (None, _) if in_zip64 => return None,
+        _ => {
+            let mut field = ZIP64_EXTRA_FIELD_ID.to_le_bytes().to_vec();
+            field.extend_from_slice(&8u16.to_le_bytes());
+            field.extend_from_slice(&offset.to_le_bytes());
+            record.splice(extra_start + extra_len..extra_start + extra_len, field);
+            extra_len += 12;
+        }
+    }
+    record[30..32].copy_from_slice(&u16::try_from(extra_len).ok()?.to_le_bytes());
+    record[42..46].copy_from_slice(&ZIP64_SIZE.to_le_bytes());
+    Some(())
+}
+
+impl RawArchiveWriter {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            data: Vec::with_capacity(capacity),
+            ..Self::default()
+        }
+    }
+
+    /// Copies the entry under a new name, keeping its compression and encryption.
+    pub fn copy_entry(
+        &mut self,
+        archive: &OpenedArchive,
+        entry: &InfoAboutArchiveEntry,
+        name: &str,
+    ) -> Result<(), (String, String)> {
+        let damaged = || (
+            format!("Failed to copy entry: {}.", entry.entry_name),
+            "The headers of the entry are damaged.".to_string(),
+        );
+        let data = archive.data();
+        let central_start = archive.zip().by_index_raw(entry.ind).map_err(|err| (
+            format!("Failed to read entry: {}.", entry.entry_name),
+            format!("{}", err)
+        ))?.central_header_start() as usize;
+
+        let header_start = entry.header_start as usize;
+        let read_u16 = |offset: usize| share::read_u16_le(data, offset).ok_or_else(damaged);
+        let local_extra_start = header_start + LOCAL_HEADER_SIZE + read_u16(header_start + 26)? as usize;
+        let local_extra = data.get(local_extra_start..entry.data_start as usize).ok_or_else(damaged)?;
+        let flags = read_u16(header_start + 6)?;
+        let data_end = (entry.data_start + entry.compressed_size) as usize;
+        let end = if flags & FLAG_DATA_DESCRIPTOR != 0 {
+            let signature_len = if data.get(data_end..data_end + 4) == Some(&DATA_DESCRIPTOR_SIGNATURE) { 4 } else { 0 };
+            let size_len = if find_extra_field(local_extra, ZIP64_EXTRA_FIELD_ID).is_some() { 8 } else { 4 };
+            data_end + signature_len + 4 + 2 * size_len
+        } else {
+            data_end
+        };
+        let body = data.get(entry.data_start as usize..end).ok_or_else(damaged)?;
+
+        // The name and the comment are written in UTF-8, whatever charset they were decoded from.
+        let comment = entry.comment.as_bytes();
+        let utf8_flag = if name.is_ascii() && comment.is_ascii() { 0 } else { FLAG_UTF8 };
+        let header_offset = self.data.len() as u64;
+
+        self.data.extend_from_slice(&LOCAL_HEADER_SIGNATURE);
+        self.data.extend_from_slice(&data[header_start + 4..header_start + 6]);
+        self.data.extend_from_slice(&((flags & !FLAG_UTF8) | utf8_flag).to_le_bytes());
+        self.data.extend_from_slice(&data[header_start + 8..header_start + 26]);
+        self.data.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        self.data.extend_from_slice(&(local_extra.len() as u16).to_le_bytes());
+        self.data.extend_from_slice(name.as_bytes());
+        self.data.extend_from_slice(local_extra);
+        self.data.extend_from_slice(body);
+
+        let central_name_len = read_u16(central_start + 28)? as usize;
+        let central_extra_len = read_u16(central_start + 30)? as usize;
+        let central_extra_start = central_start + CENTRAL_HEADER_SIZE + central_name_len;
+        let central_extra = data.get(central_extra_start..central_extra_start + central_extra_len).ok_or_else(damaged)?;
+        let central_flags = read_u16(central_start + 8)?;
+
+        let mut record = Vec::with_capacity(CENTRAL_HEADER_SIZE + name.len() + central_extra.len() + comment.len());
+        record.extend_from_slice(&data[central_start..central_start + 8]);
+        record.extend_from_slice(&((central_flags & !FLAG_UTF8) | utf8_flag).to_le_bytes());
+        record.extend_from_slice(&data[central_start + 10..central_start + 28]);
+        record.extend_from_slice(&(name.len() as u16).to_le_bytes());
+        record.extend_from_slice(&(central_extra.len() as u16).to_le_bytes());
+        record.extend_from_slice(&(comment.len() as u16).to_le_bytes());
+        // Disk number.
+        record.extend_from_slice(&[0; 2]);
+        // Internal and external attributes, and the offset that is set below.
+        record.extend_from_slice(&data[central_start + 36..central_start + 46]);
+        record.extend_from_slice(name.as_bytes());
+        record.extend_from_slice(central_extra);
+        record.extend_from_slice(comment);
+        set_header_offset(&mut record, header_offset).ok_or_else(damaged)?;
+        self.central_directory.append(&mut record);
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Appends an entry whose central directory record is made by the caller:
+    /// `local` holds the local header, the data and the data descriptor of the entry.
+    /// The offset of the local header in `central_record` is set here.
+    pub fn append_entry(&mut self, local: &[u8], mut central_record: Vec<u8>) -> Result<(), (String, String)> {
+        set_header_offset(&mut central_record, self.data.len() as u64).ok_or((
+            "Failed to write archive.".to_string(),
+            "The central directory record of an entry is damaged.".to_string(),
+        ))?;
+        self.data.extend_from_slice(local);
+        self.central_directory.append(&mut central_record);
+        self.entries += 1;
+        Ok(())
+    }
+
+    /// Writes the central directory and the archive comment.
+    /// Values that do not fit the end record are stored in the Zip64 end record,
+    /// the end record holds the maximum values instead.
+    pub fn finish(mut self, comment: &str) -> Result<Vec<u8>, (String, String)> {
+        let comment_length = u16::try_from(comment.len()).map_err(|_| (
+            "The archive comment is too long.".to_string(),
+            "The comment of a ZIP archive is limited to 65535 bytes.".to_string(),
+        ))?;
+        let entries = self.entries as u64;
+        let central_directory_size = self.central_directory.len() as u64;
+        let central_directory_start = self.data.len() as u64;
+        self.data.append(&mut self.central_directory);
+        if entries >= u16::MAX as u64 || central_directory_size >= ZIP64_SIZE as u64 || central_directory_start >= ZIP64_SIZE as u64 {
+            let zip64_end_start = self.data.len() as u64;
+            self.data.extend_from_slice(&ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+            self.data.extend_from_slice(&ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE.to_le_bytes());
+            // Version made by and version needed to extract.
+            self.data.extend_from_slice(&ZIP64_VERSION.to_le_bytes());
+            self.data.extend_from_slice(&ZIP64_VERSION.to_le_bytes());
+            // Number of this disk and of the disk with the central directory.
+            self.data.extend_from_slice(&[0; 8]);
+            self.data.extend_from_slice(&entries.to_le_bytes());
+            self.data.extend_from_slice(&entries.to_le_bytes());
+            self.data.extend_from_slice(&central_directory_size.to_le_bytes());
+            self.data.extend_from_slice(&central_directory_start.to_le_bytes());
+
+            self.data.extend_from_slice(&ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIGNATURE);
+            self.data.extend_from_slice(&[0; 4]);
+            self.data.extend_from_slice(&zip64_end_start.to_le_bytes());
+            // Total number of disks.
+            self.data.extend_from_slice(&1u32.to_le_bytes());
+        }
+        let entries = entries.min(u16::MAX as u64) as u16;
+        self.data.extend_from_slice(&END_OF_CENTRAL_DIRECTORY_SIGNATURE);
+        self.data.extend_from_slice(&[0; 4]);
+        self.data.extend_from_slice(&entries.to_le_bytes());
+        self.data.extend_from_slice(&entries.to_le_bytes());
+        self.data.extend_from_slice(&(central_directory_size.min(ZIP64_SIZE as u64) as u32).to_le_bytes());
+        self.data.extend_from_slice(&(central_directory_start.min(ZIP64_SIZE as u64) as u32).to_le_bytes());
+        self.data.extend_from_slice(&comment_length.to_le_bytes());
+        self.data.extend_from_slice(comment.as_bytes());
+        Ok(self.data)
+    }
+}
+
+/// Writes the unencrypted entries, encrypted with the password, into a temporary archive.
+fn encrypt_entries(
+    plan: &[(&OpenedArchive, &InfoAboutArchiveEntry, String)],
+    options: &MergeOptions,
+) -> Result<OpenedArchive, (String, String)> {
+    let mut zip_writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for (archive, entry, name) in plan.iter().filter(|(_, entry, _)| needs_encryption(entry, options)) {
+        let mut zip = archive.zip();
+        let mut file = entry.open(&mut zip, "")?;
+        let file_options = zip_tools::entry_options(&file, &options.password, options.type_encryption);
+        zip_writer.start_file(name.as_str(), file_options).map_err(|err| (
+            format!("Failed to add file meta information to archive: {}.", name),
+            format!("{}", err)
+        ))?;
+        std::io::copy(&mut file, &mut zip_writer).map_err(|err| entry.describe_read_error(err))?;
+    }
+    let data = zip_writer.finish()
+        .map_err(|err| ("Failed to create archive.".to_string(), format!("{}", err)))?
+        .into_inner();
+    OpenedArchive::from_bytes(yew::AttrValue::default(), data)
+}
+
+/// Whether the entry is decompressed to encrypt it with the new password.
+fn needs_encryption(entry: &InfoAboutArchiveEntry, options: &MergeOptions) -> bool {
+    !options.password.is_empty() && !entry.is_dir && !entry.encryption.is_encrypted()
+}
+
+/// Checks the archives against the limits before they are merged and returns a warning
+/// for every violated limit. Only archives that are decompressed, to encrypt their
+/// entries with the password, are checked: raw copies are not decompressed.
+pub(crate) fn check_limits(sources: &[&OpenedArchive], options: &MergeOptions, limits: &ArchiveLimits) -> Vec<String> {
+    if options.password.is_empty() {
+        return vec![];
+    }
+    sources.iter()
+        .flat_map(|archive| archive_limits::check_limits(archive, limits).into_iter()
+            .map(move |warning| format!("{}: {}", archive.file_name, warning)))
+        .collect()
+}
+
+/// Writes the entries of all archives into a new one.
+///
+/// Entries are copied raw, without decompression. Only unencrypted entries are
+/// decompressed when a password is set, to encrypt them with it. Encrypted entries
+/// keep their own password, because they cannot be decrypted without it.
+pub(crate) fn merge_archives(
+    sources: &[OpenedArchive],
+    options: &MergeOptions,
+) -> Result<(Vec<u8>, MergeReport), (String, String)> {
+    let mut report = MergeReport::default();
+    let plan = plan_merge(sources, options, &mut report);
+    let encrypted = encrypt_entries(&plan, options)?;
+    let mut encrypted_entries = encrypted.entries.iter();
+    let mut writer = RawArchiveWriter::with_capacity(sources.iter().map(|archive| archive.data().len()).sum());
+    for (archive, entry, name) in &plan {
+        if needs_encryption(entry, options) {
+            let encrypted_entry = encrypted_entries.next().ok_or((
+                format!("Failed to encrypt entry: {}.", entry.entry_name),
+                "The entry is missing in the temporary archive.".to_string(),
+            ))?;
+            writer.copy_entry(&encrypted, encrypted_entry, name)?;
+            report.encrypted += 1;
+        } else {
+            writer.copy_entry(archive, entry, name)?;
+            report.copied_raw += 1;
+        }
+    }
+    Ok((writer.finish("")?, report))
+}
@@ -0,0 +1,364 @@
+use yew::TargetCast;
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::append_files::ConflictResolution;
+use crate::features::archive::archive_limits::{ArchiveLimits, LimitWarningsComponent};
+use crate::features::archive::merge_archives::model::{self, MergeOptions, MergeReport};
+use crate::share::zip_tools::TypeEncryption;
+
+/// Name of the merged archive offered for download.
+const MERGED_FILE_NAME: &str = "merged.zip";
+
+/// State of the merging process.
+#[derive(PartialEq)]
+enum MergingState {
+    /// Waiting for the user to select archives.
+    WaitFiles,
+    /// Waiting for the user to choose the options.
+    WaitConfirm,
+    /// Archives are being read and merged.
+    InProcess,
+    /// The archives exceed the limits, waiting for the user to merge them anyway.
+    WaitAccept,
+    Done,
+    Fail,
+}
+
+pub enum MergeArchivesMsg {
+    /// Archives selected by the user.
+    Select(gloo_file::FileList),
+    ToggleByFolder,
+    ChangeResolution(String),
+    EditPassword(String),
+    ChangeTypeEncryption(String),
+    StartMerge,
+    /// The archive has been read from disk.
+    LoadedFile(usize, yew::AttrValue, Vec<u8>),
+    FailedMerge((String, String)),
+    /// The user decided to merge the archives despite the violated limits.
+    AcceptWarnings,
+    Reset,
+    Pass,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct MergeArchivesProps {
+    /// Limits checked before the entries are decompressed to encrypt them.
+    pub limits: ArchiveLimits,
+}
+
+/// Component - merges several archives selected by the user into one.
+pub struct MergeArchivesComponent {
+    state: MergingState,
+    files: Vec<gloo_file::File>,
+    options: MergeOptions,
+    file_reading_tasks: std::collections::HashMap<usize, gloo_file::callbacks::FileReader>,
+    loaded_archives: std::collections::HashMap<usize, OpenedArchive>,
+    report: MergeReport,
+    /// Violated limits of the loaded archives.
+    warnings: Vec<String>,
+    err_msg: (String, String),
+    blob_result: Option<gloo_file::Blob>,
+}
+
+impl MergeArchivesComponent {
+    fn get_obj_url_with_result(&self) -> Option<String> {
+        let blob = self.blob_result.as_ref()?;
+        let download_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok()?;
+        Some(download_url)
+    }
+
+    /// Merges the loaded archives. Unless the limits are accepted, the archives
+    /// are checked against them first and are kept until the user accepts them.
+    fn finish(&mut self, ctx: &yew::Context<Self>, accepted: bool) {
+        if !accepted {
+            let sources: Vec<&OpenedArchive> = (0..self.files.len())
+                .filter_map(|ind| self.loaded_archives.get(&ind))
+                .collect();
+            self.warnings = model::check_limits(&sources, &self.options, &ctx.props().limits);
+            if !self.warnings.is_empty() {
+                self.state = MergingState::WaitAccept;
+                return;
+            }
+        }
+        let sources: Vec<OpenedArchive> = (0..self.files.len())
+            .filter_map(|ind| self.loaded_archives.remove(&ind))
+            .collect();
+        match model::merge_archives(&sources, &self.options) {
+            Ok((data, report)) => {
+                self.state = MergingState::Done;
+                self.report = report;
+                self.blob_result = Some(gloo_file::Blob::new(data.as_slice()));
+            }
+            Err(err) => {
+                ctx.link().send_message(MergeArchivesMsg::FailedMerge(err));
+            }
+        }
+    }
+
+    fn view_options(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let by_folder_onchange = &ctx.link().callback(|_| MergeArchivesMsg::ToggleByFolder);
+        let onchange_resolution = &ctx.link().callback(move |e: web_sys::Event| {
+            let select: web_sys::HtmlInputElement = e.target_unchecked_into();
+            MergeArchivesMsg::ChangeResolution(select.value())
+        });
+        let onchange_type = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            MergeArchivesMsg::ChangeTypeEncryption(input.value())
+        });
+        let onchange_password = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            MergeArchivesMsg::EditPassword(input.value())
+        });
+        let reset_onclick = &ctx.link().callback(|_| MergeArchivesMsg::Reset);
+        let start_onclick = &ctx.link().callback(|_| MergeArchivesMsg::StartMerge);
+        let resolution = self.options.resolution;
+        let type_encryption = self.options.type_encryption;
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "w-100", "gap-1")}>
+                <small class={yew::classes!("text-truncate")}>
+                    {self.files.iter().map(|file| file.name()).collect::<Vec<_>>().join(", ")}
+                </small>
+                <div class={yew::classes!("form-check")}>
+                    <input class={yew::classes!("form-check-input")} type="checkbox" id="merge-by-folder"
+                        checked={self.options.by_folder} onchange={by_folder_onchange} />
+                    <label class={yew::classes!("form-check-label")} for="merge-by-folder">
+                        {"Put each archive into its own folder"}
+                    </label>
+                </div>
+                <div class={yew::classes!("input-group", "input-group-sm")}>
+                    <span class={yew::classes!("input-group-text")}>{"Same names"}</span>
+                    <select class={yew::classes!("form-select")} style="max-width: 135px;" onchange={onchange_resolution}>
+                        <option selected={resolution == ConflictResolution::KeepBoth} value={"KeepBoth"}>{"Keep both"}</option>
+                        <option selected={resolution == ConflictResolution::Replace} value={"Replace"}>{"Keep last"}</option>
+                        <option selected={resolution == ConflictResolution::Skip} value={"Skip"}>{"Keep first"}</option>
+                    </select>
+                </div>
+                <div class={yew::classes!("input-group", "input-group-sm")}>
+                    <select class={yew::classes!("form-select", "form-select-sm")}
+                        style="max-width: 135px;"
+                        onchange={onchange_type}
+                    >
+                        <option selected={type_encryption == TypeEncryption::ZipCrypto} value={"ZipCrypto"}>{"ZipCrypto"}</option>
+                        <option selected={type_encryption == TypeEncryption::Aes256} value={"Aes256"}>{"Aes256"}</option>
+                    </select>
+                    <input
+                        class={yew::classes!("form-control")}
+                        type="password"
+                        placeholder="Password for unencrypted entries (optional)" aria-label="Password for unencrypted entries"
+                        title="Unencrypted entries are decompressed and encrypted with this password. Encrypted entries keep their own password."
+                        value={self.options.password.clone()}
+                        onchange={onchange_password}
+                    />
+                </div>
+                <div class={yew::classes!("d-flex", "justify-content-end")}>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")} onclick={reset_onclick}>
+                        {"Cancel"}
+                    </button>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={start_onclick}>
+                        {format!("Merge {} archives", self.files.len())}
+                    </button>
+                </div>
+            </div>
+        }
+    }
+}
+
+impl yew::Component for MergeArchivesComponent {
+    type Message = MergeArchivesMsg;
+    type Properties = MergeArchivesProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            state: MergingState::WaitFiles,
+            files: vec![],
+            options: MergeOptions {
+                by_folder: false,
+                resolution: ConflictResolution::KeepBoth,
+                password: String::new(),
+                type_encryption: TypeEncryption::Aes256,
+            },
+            file_reading_tasks: std::collections::HashMap::new(),
+            loaded_archives: std::collections::HashMap::new(),
+            report: MergeReport::default(),
+            warnings: vec![],
+            err_msg: ("".to_string(), "".to_string()),
+            blob_result: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            MergeArchivesMsg::Select(file_list) => {
+                self.files = file_list.iter().cloned().collect();
+                self.state = if self.files.is_empty() {
+                    MergingState::WaitFiles
+                } else {
+                    MergingState::WaitConfirm
+                };
+                true
+            }
+            MergeArchivesMsg::ToggleByFolder => {
+                self.options.by_folder = !self.options.by_folder;
+                true
+            }
+            MergeArchivesMsg::ChangeResolution(value) => {
+                self.options.resolution = ConflictResolution::from(value);
+                false
+            }
+            MergeArchivesMsg::EditPassword(password) => {
+                self.options.password = password.trim().to_string();
+                true
+            }
+            MergeArchivesMsg::ChangeTypeEncryption(value) => {
+                self.options.type_encryption = TypeEncryption::from(value);
+                false
+            }
+            MergeArchivesMsg::StartMerge => {
+                self.state = MergingState::InProcess;
+                self.loaded_archives = std::collections::HashMap::new();
+                let callback_loaded_file = ctx.link()
+                    .callback(|(ind, file_name, data)| MergeArchivesMsg::LoadedFile(ind, file_name, data));
+                let callback_failed = ctx.link().callback(MergeArchivesMsg::FailedMerge);
+                for (ind, file) in self.files.iter().enumerate() {
+                    let task = crate::share::fs_tools::read_file(
+                        ind,
+                        yew::AttrValue::from(file.name()),
+                        file,
+                        callback_loaded_file.clone(),
+                        callback_failed.clone(),
+                    );
+                    self.file_reading_tasks.insert(ind, task);
+                }
+                true
+            }
+            MergeArchivesMsg::LoadedFile(ind, file_name, data) => {
+                if self.state != MergingState::InProcess { return false; }
+                self.file_reading_tasks.remove(&ind);
+                match OpenedArchive::from_bytes(file_name, data) {
+                    Ok(archive) => { self.loaded_archives.insert(ind, archive); }
+                    Err(err) => {
+                        ctx.link().send_message(MergeArchivesMsg::FailedMerge(err));
+                        return false;
+                    }
+                }
+                if self.loaded_archives.len() == self.files.len() {
+                    self.finish(ctx, false);
+                }
+                true
+            }
+            MergeArchivesMsg::FailedMerge(err) => {
+                self.state = MergingState::Fail;
+                self.err_msg = err;
+                self.file_reading_tasks = std::collections::HashMap::new();
+                self.loaded_archives = std::collections::HashMap::new();
+                true
+            }
+            MergeArchivesMsg::AcceptWarnings => {
+                if self.state != MergingState::WaitAccept { return false; }
+                self.state = MergingState::InProcess;
+                self.warnings = vec![];
+                self.finish(ctx, true);
+                true
+            }
+            MergeArchivesMsg::Reset => {
+                self.state = MergingState::WaitFiles;
+                self.files = vec![];
+                self.loaded_archives = std::collections::HashMap::new();
+                self.warnings = vec![];
+                self.blob_result = None;
+                true
+            }
+            MergeArchivesMsg::Pass => { false }
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let reset_onclick = &ctx.link().callback(|_| MergeArchivesMsg::Reset);
+        match self.state {
+            MergingState::WaitFiles => {
+                let onchange = &ctx.link().callback(move |e: web_sys::Event| {
+                    let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+                    if let Some(fl) = input.files() {
+                        MergeArchivesMsg::Select(fl.into())
+                    } else { MergeArchivesMsg::Pass }
+                });
+                yew::html! {
+                    <span class="btn btn-sm btn-outline-dark"
+                          style="position: relative; overflow: hidden;"
+                    >
+                        {"Merge archives"}
+                        <input
+                            style="position: absolute; top: 0; right: 0; min-width: 100%; min-height: 100%; font-size: 100px; text-align: right; filter: alpha(opacity=0); opacity: 0; outline: none; cursor: inherit; display: block;"
+                            type="file"
+                            accept=".zip"
+                            multiple={true}
+                            {onchange}
+                        />
+                    </span>
+                }
+            }
+            MergingState::WaitConfirm => self.view_options(ctx),
+            MergingState::InProcess => {
+                yew::html! {
+                    <p class={yew::classes!("mb-0")}>{format!("Reading: {}/{}", self.loaded_archives.len(), self.files.len())}</p>
+                }
+            }
+            MergingState::WaitAccept => {
+                let on_continue = &ctx.link().callback(|_| MergeArchivesMsg::AcceptWarnings);
+                yew::html! {
+                    <div class={yew::classes!("d-flex", "flex-column", "w-100", "gap-1")}>
+                        <LimitWarningsComponent warnings={self.warnings.clone()} {on_continue} />
+                        <div>
+                            <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                                {"Cancel"}
+                            </button>
+                        </div>
+                    </div>
+                }
+            }
+            MergingState::Done => {
+                let report = &self.report;
+                yew::html! {
+                    <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                        <small class={yew::classes!("mb-1")}>
+                            {format!(
+                                "Copied without recompression: {}, encrypted: {}, renamed: {}, dropped as duplicates: {}.",
+                                report.copied_raw,
+                                report.encrypted,
+                                report.renamed,
+                                report.skipped
+                            )}
+                        </small>
+                        <div>
+                            if let Some(href) = self.get_obj_url_with_result() {
+                                <a
+                                    class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")}
+                                    href={href}
+                                    download={MERGED_FILE_NAME}
+                                >
+                                    {"Download merged archive"}
+                                </a>
+                            }
+                            <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                                {"Back"}
+                            </button>
+                        </div>
+                    </div>
+                }
+            }
+            MergingState::Fail => {
+                let msg = self.err_msg.0.as_str();
+                let detail = self.err_msg.1.as_str();
+                yew::html! {
+                    <div
+                        class={yew::classes!("alert", "alert-danger", "mb-0")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.to_string()}
+                        onclick={reset_onclick}
+                    >{format!("Error: {msg}")}
+                    </div>
+                }
+            }
+        }
+    }
+}
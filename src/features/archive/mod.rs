@@ -0,0 +1,20 @@
+pub mod open_archive;
+pub mod decrypt_entry;
+pub mod append_files;
+pub mod test_archive;
+pub mod reencrypt_archive;
+pub mod edit_entries;
+pub mod preview_entry;
+pub mod compare_archives;
+pub mod recover_archive;
+pub mod nested_archive;
+pub mod extract_archive;
+pub mod inspect_structure;
+pub mod archive_limits;
+pub mod name_charset;
+pub mod search_archive;
+pub mod merge_archives;
+pub mod convert_archive;
+pub mod recompress_archive;
+pub mod update_archive;
+pub mod dual_pane;
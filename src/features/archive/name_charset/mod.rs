@@ -0,0 +1,5 @@
+//! Выбор кодировки имён и комментариев, записанных без флага UTF-8.
+
+mod ui;
+
+pub use ui::NameCharsetComponent;
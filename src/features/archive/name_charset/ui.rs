@@ -0,0 +1,78 @@
+use yew::TargetCast;
+use crate::entities::archive::model::OpenedArchive;
+use crate::share::text_tools::LegacyCharset;
+
+pub enum NameCharsetMsg {
+    ChangeCharset(String),
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct NameCharsetProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    /// Callback with the archive whose names and comments were decoded again.
+    pub on_charset_change: yew::Callback<OpenedArchive>,
+}
+
+/// Component - overrides the detected charset of entry names and comments.
+pub struct NameCharsetComponent {
+    error: Option<(String, String)>,
+}
+
+impl yew::Component for NameCharsetComponent {
+    type Message = NameCharsetMsg;
+    type Properties = NameCharsetProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self { error: None }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            NameCharsetMsg::ChangeCharset(value) => {
+                match ctx.props().archive.with_charset(LegacyCharset::from(value)) {
+                    Ok(archive) => {
+                        self.error = None;
+                        ctx.props().on_charset_change.emit(archive);
+                    }
+                    Err(err) => { self.error = Some(err); }
+                }
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let archive = &ctx.props().archive;
+        // Names without non-ASCII characters look the same in every charset.
+        let Some(detected_charset) = archive.detected_charset else {
+            return yew::html! {};
+        };
+        let onchange = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            NameCharsetMsg::ChangeCharset(input.value())
+        });
+        yew::html! {
+            <div class={yew::classes!("input-group", "input-group-sm")}>
+                <span class={yew::classes!("input-group-text")}>{"Names"}</span>
+                <select
+                    class={yew::classes!("form-select", "form-select-sm")}
+                    title="Charset of the names and comments stored without the UTF-8 flag."
+                    {onchange}
+                >
+                    { LegacyCharset::ALL.iter().map(|charset| yew::html! {
+                        <option selected={*charset == archive.charset} value={format!("{charset:?}")}>
+                            if *charset == detected_charset {
+                                {format!("{charset} (detected)")}
+                            } else {
+                                {format!("{charset}")}
+                            }
+                        </option>
+                    }).collect::<yew::Html>() }
+                </select>
+                if let Some((msg, detail)) = &self.error {
+                    <span class={yew::classes!("input-group-text", "text-danger")} title={detail.clone()}>{msg}</span>
+                }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Просмотр архивов, вложенных в элементы другого архива.
+
+mod ui;
+mod model;
+
+pub use ui::NestedArchiveComponent;
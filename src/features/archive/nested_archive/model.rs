@@ -0,0 +1,50 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::archive_limits::{check_tar_limits, ArchiveLimits};
+use crate::share::tar_tools::TarCompression;
+
+/// Maximum number of archive levels that can be expanded inside the opened archive.
+pub(crate) const MAX_NESTING_DEPTH: usize = 4;
+
+/// Format of an archive stored as an entry of another archive.
+#[derive(PartialEq, Copy, Clone)]
+pub(crate) enum NestedKind {
+    Zip,
+    Tar(TarCompression),
+}
+
+/// Recognizes a nested archive by the file extension.
+pub(crate) fn nested_kind(file_name: &str) -> Option<NestedKind> {
+    if let Some(compression) = TarCompression::from_file_name(file_name) {
+        return Some(NestedKind::Tar(compression));
+    }
+    let file_name = file_name.to_ascii_lowercase();
+    if [".zip", ".jar", ".war", ".ear"].iter().any(|extension| file_name.ends_with(extension)) {
+        Some(NestedKind::Zip)
+    } else {
+        None
+    }
+}
+
+/// Checks a nested tar archive against the limits by the headers of its entries, before it is repacked.
+/// ZIP archives are checked by their central directory after they are opened.
+pub(crate) fn check_headers(kind: NestedKind, data: &[u8], limits: &ArchiveLimits) -> Result<Vec<String>, (String, String)> {
+    match kind {
+        NestedKind::Zip => Ok(vec![]),
+        NestedKind::Tar(compression) => check_tar_limits(compression, data, limits),
+    }
+}
+
+/// Opens the decompressed data of a nested archive.
+/// Tar archives are repacked into ZIP so that their entries can be browsed the same way.
+pub(crate) fn open_nested(
+    file_name: &str,
+    kind: NestedKind,
+    data: Vec<u8>,
+) -> Result<OpenedArchive, (String, String)> {
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let data = match kind {
+        NestedKind::Zip => data,
+        NestedKind::Tar(compression) => crate::share::zip_tools::tar_to_zip(compression.decompress(&data), options)?,
+    };
+    OpenedArchive::from_bytes(yew::AttrValue::from(file_name.to_string()), data)
+}
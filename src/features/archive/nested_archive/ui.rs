@@ -0,0 +1,189 @@
+use crate::entities::archive::model::{InfoAboutArchiveEntry, OpenedArchive};
+use crate::features::archive::archive_limits::{check_limits, ArchiveLimits, LimitWarningsComponent};
+use crate::features::archive::nested_archive::model;
+
+pub enum NestedArchiveMsg {
+    Expand,
+    Collapse,
+    /// The user decided to browse the nested archive despite the violated limits.
+    AcceptWarnings,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct NestedArchiveProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    pub entry: InfoAboutArchiveEntry,
+    /// Password entered for the archive, also used for the nested archives.
+    pub password: yew::AttrValue,
+    /// Limits checked before the entries of the nested archive are shown.
+    pub limits: ArchiveLimits,
+    /// Nesting level of the entry: 1 for entries of the opened archive.
+    pub depth: usize,
+}
+
+/// Component - expands an entry that is itself an archive and lists its entries.
+pub struct NestedArchiveComponent {
+    nested: Option<Result<std::rc::Rc<OpenedArchive>, (String, String)>>,
+    /// Violated limits of the nested archive, the entries are hidden until the user accepts them.
+    warnings: Vec<String>,
+    /// Data of a tar archive that is not repacked until the user accepts the violated limits.
+    pending: Option<(model::NestedKind, Vec<u8>)>,
+}
+
+impl NestedArchiveComponent {
+    fn view_entry_row(
+        ctx: &yew::Context<Self>,
+        archive: &std::rc::Rc<OpenedArchive>,
+        entry: &InfoAboutArchiveEntry,
+    ) -> yew::Html {
+        let password = &ctx.props().password;
+        yew::html! {
+            <div class={yew::classes!("list-group-item", "p-2")} key={entry.ind}>
+                <crate::entities::archive::ui::ArchiveEntryRowComponent info_about_archive_entry={entry.clone()} />
+                <crate::features::archive::decrypt_entry::DecryptEntryComponent
+                    archive={archive.clone()}
+                    entry={entry.clone()}
+                    password={password.clone()}
+                />
+                <crate::features::archive::preview_entry::PreviewEntryComponent
+                    archive={archive.clone()}
+                    entry={entry.clone()}
+                    password={password.clone()}
+                />
+                <NestedArchiveComponent
+                    archive={archive.clone()}
+                    entry={entry.clone()}
+                    password={password.clone()}
+                    limits={ctx.props().limits}
+                    depth={ctx.props().depth + 1}
+                />
+            </div>
+        }
+    }
+}
+
+impl yew::Component for NestedArchiveComponent {
+    type Message = NestedArchiveMsg;
+    type Properties = NestedArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self { nested: None, warnings: vec![], pending: None }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            NestedArchiveMsg::Expand => {
+                let props = ctx.props();
+                let Some(kind) = model::nested_kind(&props.entry.entry_name) else { return false; };
+                let checked = props.archive.read_entry(&props.entry, &props.password)
+                    .and_then(|data| model::check_headers(kind, &data, &props.limits).map(|warnings| (data, warnings)));
+                match checked {
+                    Ok((data, warnings)) if !warnings.is_empty() => {
+                        self.warnings = warnings;
+                        self.pending = Some((kind, data));
+                    }
+                    Ok((data, _)) => {
+                        let nested = model::open_nested(props.entry.base_name(), kind, data).map(std::rc::Rc::new);
+                        self.warnings = match &nested {
+                            Ok(archive) => check_limits(archive, &props.limits),
+                            Err(_) => vec![],
+                        };
+                        self.nested = Some(nested);
+                    }
+                    Err(err) => { self.nested = Some(Err(err)); }
+                }
+                true
+            }
+            NestedArchiveMsg::Collapse => {
+                self.nested = None;
+                self.warnings = vec![];
+                self.pending = None;
+                true
+            }
+            NestedArchiveMsg::AcceptWarnings => {
+                self.warnings = vec![];
+                if let Some((kind, data)) = self.pending.take() {
+                    let nested = model::open_nested(ctx.props().entry.base_name(), kind, data).map(std::rc::Rc::new);
+                    self.nested = Some(nested);
+                }
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, ctx: &yew::Context<Self>, old_props: &Self::Properties) -> bool {
+        if ctx.props().archive != old_props.archive || ctx.props().entry != old_props.entry {
+            self.nested = None;
+            self.warnings = vec![];
+            self.pending = None;
+        }
+        // A password change may make an encrypted nested archive readable.
+        if ctx.props().password != old_props.password && matches!(self.nested, Some(Err(_))) {
+            self.nested = None;
+        }
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let props = ctx.props();
+        if props.entry.is_dir || model::nested_kind(&props.entry.entry_name).is_none() {
+            return yew::html! {};
+        }
+        if self.nested.is_none() && self.pending.is_none() {
+            if props.depth > model::MAX_NESTING_DEPTH {
+                return yew::html! {
+                    <small class={yew::classes!("d-block", "text-muted", "mt-1")}>
+                        {format!("Archives nested deeper than {} levels are not expanded.", model::MAX_NESTING_DEPTH)}
+                    </small>
+                };
+            }
+            let onclick = &ctx.link().callback(|_| NestedArchiveMsg::Expand);
+            return yew::html! {
+                <button
+                    type="button"
+                    class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "mt-1")}
+                    title="Browse the entries of the nested archive."
+                    {onclick}
+                >
+                    {"Expand"}
+                </button>
+            };
+        }
+
+        let onclick = &ctx.link().callback(|_| NestedArchiveMsg::Collapse);
+        let on_continue = &ctx.link().callback(|_| NestedArchiveMsg::AcceptWarnings);
+        yew::html! {
+            <div class={yew::classes!("mt-1", "ms-3")}>
+                <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center", "mb-1")}>
+                    <small class={yew::classes!("text-muted")}>
+                        if let Some(Ok(archive)) = &self.nested {
+                            {format!("{} entries", archive.entries.len())}
+                        }
+                    </small>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} {onclick}>
+                        {"Collapse"}
+                    </button>
+                </div>
+                { match &self.nested {
+                    _ if !self.warnings.is_empty() => yew::html! {
+                        <LimitWarningsComponent warnings={self.warnings.clone()} {on_continue} />
+                    },
+                    None => yew::html! {},
+                    Some(Ok(archive)) => yew::html! {
+                        <div class={yew::classes!("list-group")}>
+                            { archive.entries.iter().map(|entry| Self::view_entry_row(ctx, archive, entry)).collect::<yew::Html>() }
+                        </div>
+                    },
+                    Some(Err((msg, detail))) => yew::html! {
+                        <div
+                            class={yew::classes!("alert", "alert-danger", "mb-0")}
+                            data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                            title={detail.clone()}
+                        >{format!("Error: {msg}")}
+                        </div>
+                    },
+                } }
+            </div>
+        }
+    }
+}
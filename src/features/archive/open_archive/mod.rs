@@ -0,0 +1,6 @@
+//! Открытие архива, выбранного пользователем.
+
+mod ui;
+mod model;
+
+pub use ui::OpenArchiveComponent;
@@ -0,0 +1,74 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::archive_limits::{check_sevenz_limits, check_tar_limits, ArchiveLimits};
+use crate::share::tar_tools::TarCompression;
+
+const SEVENZ_EXTENSION: &str = ".7z";
+
+/// Extensions of the archives that can be opened, for the `accept` attribute.
+pub(crate) fn accepted_extensions() -> String {
+    [".zip", SEVENZ_EXTENSION].into_iter()
+        .chain(TarCompression::ALL.iter().flat_map(|compression| compression.extensions().iter().copied()))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Whether the file is a 7z archive, which may need a password to be opened.
+pub(crate) fn is_sevenz(file_name: &str) -> bool {
+    file_name.to_ascii_lowercase().ends_with(SEVENZ_EXTENSION)
+}
+
+/// Whether the file is a ZIP archive that can be recovered when it fails to open.
+pub(crate) fn is_zip(file_name: &str) -> bool {
+    TarCompression::from_file_name(file_name).is_none() && !is_sevenz(file_name)
+}
+
+/// The file name with the extension replaced with `.zip`.
+fn zip_file_name(file_name: &str, extensions: &[&str]) -> String {
+    let lowercase = file_name.to_ascii_lowercase();
+    let stem = extensions.iter()
+        .find(|extension| lowercase.ends_with(*extension))
+        .map(|extension| &file_name[..file_name.len() - extension.len()])
+        .unwrap_or(file_name);
+    format!("{stem}.zip")
+}
+
+/// Checks a tar or 7z archive against the limits by the headers of its entries, before it is repacked.
+/// ZIP archives are checked by their central directory after they are opened.
+pub(crate) fn check_headers(
+    file_name: &str,
+    data: &[u8],
+    password: &str,
+    limits: &ArchiveLimits,
+) -> Result<Vec<String>, (String, String)> {
+    if let Some(compression) = TarCompression::from_file_name(file_name) {
+        check_tar_limits(compression, data, limits)
+    } else if is_sevenz(file_name) {
+        check_sevenz_limits(data, password, limits)
+    } else {
+        Ok(vec![])
+    }
+}
+
+/// Opens an archive read from disk.
+///
+/// Tar and 7z archives are decompressed and repacked into an uncompressed ZIP,
+/// so that their entries are previewed and extracted the same way.
+/// They are checked with `check_headers` first, as repacking unpacks every entry.
+/// Tar archives are decompressed while reading, 7z entries are decrypted with the password.
+/// The repacked archive is named with the `.zip` extension.
+pub(crate) fn open_archive(
+    file_name: yew::AttrValue,
+    data: std::rc::Rc<[u8]>,
+    password: &str,
+) -> Result<OpenedArchive, (String, String)> {
+    let options = zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    if let Some(compression) = TarCompression::from_file_name(&file_name) {
+        let zip = crate::share::zip_tools::tar_to_zip(compression.decompress(&data), options)?;
+        OpenedArchive::from_bytes(zip_file_name(&file_name, compression.extensions()).into(), zip)
+    } else if is_sevenz(&file_name) {
+        let zip = crate::share::zip_tools::sevenz_to_zip(&data, password, options)?;
+        OpenedArchive::from_bytes(zip_file_name(&file_name, &[SEVENZ_EXTENSION]).into(), zip)
+    } else {
+        OpenedArchive::from_shared_bytes(file_name, data)
+    }
+}
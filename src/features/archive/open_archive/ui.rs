@@ -0,0 +1,249 @@
+use yew::TargetCast;
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::archive_limits::{check_limits, ArchiveLimits, LimitWarningsComponent};
+use crate::features::archive::open_archive::model;
+
+pub enum OpenArchiveMsg {
+    /// The user has selected an archive.
+    Select(gloo_file::FileList),
+    /// The archive has been read from disk.
+    LoadedFile(yew::AttrValue, Vec<u8>),
+    FailedOpen((String, String)),
+    EditPassword(String),
+    /// Open the encrypted 7z archive again with the entered password.
+    OpenLocked,
+    /// An archive has been rebuilt from the entries of a damaged one.
+    Recovered(OpenedArchive),
+    /// The user decided to open the archive despite the violated limits.
+    AcceptWarnings,
+    Pass,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct OpenArchiveProps {
+    /// Callback when the archive has been read and its central directory parsed.
+    /// Tar and 7z archives are passed repacked into ZIP.
+    pub on_archive_opened: yew::Callback<OpenedArchive>,
+    /// Limits checked by the central directory of ZIP archives and by the headers
+    /// of tar and 7z archives before they are repacked.
+    #[prop_or_default]
+    pub limits: ArchiveLimits,
+    /// Callback when the archive was opened despite the violated limits.
+    #[prop_or_default]
+    pub on_limits_accepted: yew::Callback<()>,
+}
+
+/// An archive that is kept aside until the user accepts the violated limits.
+enum LimitedArchive {
+    /// Name and contents of a tar or 7z archive that is not repacked yet.
+    Packed(yew::AttrValue, std::rc::Rc<[u8]>),
+    /// ZIP archive whose central directory has been read.
+    Opened(OpenedArchive),
+}
+
+/// Component - selection of an archive to open.
+pub struct OpenArchiveComponent {
+    file_reading_task: Option<gloo_file::callbacks::FileReader>,
+    err_msg: Option<(String, String)>,
+    /// Name and contents of an archive that could not be opened.
+    damaged_archive: Option<(yew::AttrValue, std::rc::Rc<[u8]>)>,
+    /// Name and contents of a 7z archive that could not be opened without a password.
+    locked_archive: Option<(yew::AttrValue, std::rc::Rc<[u8]>)>,
+    /// Archive and violated limits, the archive is not passed on until the user accepts them.
+    limited_archive: Option<(LimitedArchive, Vec<String>)>,
+    password: String,
+}
+
+impl OpenArchiveComponent {
+    /// Opens the archive. Unless the limits are accepted, the archive is checked
+    /// against them first and is kept aside if they are violated.
+    fn open(&mut self, ctx: &yew::Context<Self>, file_name: yew::AttrValue, data: std::rc::Rc<[u8]>, accepted: bool) {
+        if !accepted {
+            match model::check_headers(&file_name, &data, &self.password, &ctx.props().limits) {
+                Ok(warnings) if !warnings.is_empty() => {
+                    self.limited_archive = Some((LimitedArchive::Packed(file_name, data), warnings));
+                    return;
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    self.fail(file_name, data, err);
+                    return;
+                }
+            }
+        }
+        match model::open_archive(file_name.clone(), data.clone(), &self.password) {
+            Ok(archive) => {
+                self.locked_archive = None;
+                self.pass_on(ctx, archive, accepted);
+            }
+            Err(err) => self.fail(file_name, data, err),
+        }
+    }
+
+    /// Passes the opened archive on. Unless the limits are accepted, the archive is checked
+    /// by its central directory first and is kept aside if they are violated.
+    fn pass_on(&mut self, ctx: &yew::Context<Self>, archive: OpenedArchive, accepted: bool) {
+        if !accepted {
+            let warnings = check_limits(&archive, &ctx.props().limits);
+            if !warnings.is_empty() {
+                self.limited_archive = Some((LimitedArchive::Opened(archive), warnings));
+                return;
+            }
+        }
+        ctx.props().on_archive_opened.emit(archive);
+        if accepted {
+            ctx.props().on_limits_accepted.emit(());
+        }
+    }
+
+    fn fail(&mut self, file_name: yew::AttrValue, data: std::rc::Rc<[u8]>, err: (String, String)) {
+        self.err_msg = Some(err);
+        // Only ZIP archives can be recovered by their local headers.
+        if model::is_zip(&file_name) {
+            self.damaged_archive = Some((file_name, data));
+        } else if model::is_sevenz(&file_name) {
+            self.locked_archive = Some((file_name, data));
+        }
+    }
+}
+
+impl yew::Component for OpenArchiveComponent {
+    type Message = OpenArchiveMsg;
+    type Properties = OpenArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            file_reading_task: None,
+            err_msg: None,
+            damaged_archive: None,
+            locked_archive: None,
+            limited_archive: None,
+            password: String::new(),
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            OpenArchiveMsg::Select(file_list) => {
+                if let Some(file) = file_list.first() {
+                    self.err_msg = None;
+                    self.damaged_archive = None;
+                    self.locked_archive = None;
+                    self.limited_archive = None;
+                    self.password = String::new();
+                    self.file_reading_task = Some(crate::share::fs_tools::read_file(
+                        0,
+                        yew::AttrValue::from(file.name()),
+                        file,
+                        ctx.link().callback(|(_, file_name, data)| OpenArchiveMsg::LoadedFile(file_name, data)),
+                        ctx.link().callback(OpenArchiveMsg::FailedOpen),
+                    ));
+                }
+                true
+            }
+            OpenArchiveMsg::LoadedFile(file_name, data) => {
+                self.file_reading_task = None;
+                self.open(ctx, file_name, std::rc::Rc::from(data), false);
+                true
+            }
+            OpenArchiveMsg::FailedOpen(err) => {
+                self.file_reading_task = None;
+                self.err_msg = Some(err);
+                true
+            }
+            OpenArchiveMsg::EditPassword(password) => {
+                self.password = password.trim().to_string();
+                false
+            }
+            OpenArchiveMsg::OpenLocked => {
+                let Some((file_name, data)) = self.locked_archive.take() else { return false; };
+                self.err_msg = None;
+                self.open(ctx, file_name, data, false);
+                true
+            }
+            OpenArchiveMsg::Recovered(archive) => {
+                self.pass_on(ctx, archive, false);
+                true
+            }
+            OpenArchiveMsg::AcceptWarnings => {
+                match self.limited_archive.take() {
+                    Some((LimitedArchive::Packed(file_name, data), _)) => self.open(ctx, file_name, data, true),
+                    Some((LimitedArchive::Opened(archive), _)) => self.pass_on(ctx, archive, true),
+                    None => return false,
+                }
+                true
+            }
+            OpenArchiveMsg::Pass => { false }
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let onchange = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            if let Some(fl) = input.files() {
+                OpenArchiveMsg::Select(fl.into())
+            } else { OpenArchiveMsg::Pass }
+        });
+        let onchange_password = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            OpenArchiveMsg::EditPassword(input.value())
+        });
+        let open_onclick = &ctx.link().callback(|_| OpenArchiveMsg::OpenLocked);
+        let on_continue = &ctx.link().callback(|_| OpenArchiveMsg::AcceptWarnings);
+        let on_recovered = &ctx.link().callback(OpenArchiveMsg::Recovered);
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "align-items-center", "py-2")}>
+                <span class="btn btn-outline-dark"
+                      style="position: relative; overflow: hidden;"
+                >
+                    if self.file_reading_task.is_some() {
+                        {"Reading archive..."}
+                    } else {
+                        {"Open archive"}
+                    }
+                    <input
+                        style="position: absolute; top: 0; right: 0; min-width: 100%; min-height: 100%; font-size: 100px; text-align: right; filter: alpha(opacity=0); opacity: 0; outline: none; cursor: inherit; display: block;"
+                        type="file"
+                        accept={model::accepted_extensions()}
+                        {onchange}
+                    />
+                </span>
+                if let Some((msg, detail)) = &self.err_msg {
+                    <div
+                        class={yew::classes!("alert", "alert-danger", "mt-2", "mb-0")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.clone()}
+                    >{format!("Error: {msg}")}
+                    </div>
+                }
+                if self.locked_archive.is_some() {
+                    <div class={yew::classes!("input-group", "input-group-sm", "mt-2")} style="max-width: 400px;">
+                        <input
+                            class={yew::classes!("form-control")}
+                            type="password"
+                            placeholder="Password of the 7z archive" aria-label="Password of the 7z archive"
+                            value={self.password.clone()}
+                            onchange={onchange_password}
+                        />
+                        <button type="button" class={yew::classes!("btn", "btn-outline-dark")} onclick={open_onclick}>
+                            {"Open"}
+                        </button>
+                    </div>
+                }
+                if let Some((_, warnings)) = &self.limited_archive {
+                    <div class={yew::classes!("mt-2")}>
+                        <LimitWarningsComponent warnings={warnings.clone()} {on_continue} />
+                    </div>
+                }
+                if let Some((file_name, data)) = &self.damaged_archive {
+                    <crate::features::archive::recover_archive::RecoverArchiveComponent
+                        file_name={file_name.clone()}
+                        data={data.clone()}
+                        limits={ctx.props().limits}
+                        on_archive_opened={on_recovered}
+                    />
+                }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Просмотр содержимого элементов архива без извлечения.
+
+mod ui;
+mod model;
+
+pub use ui::PreviewEntryComponent;
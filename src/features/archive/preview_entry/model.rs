@@ -0,0 +1,98 @@
+/// Maximum number of characters of text shown in the preview.
+const MAX_TEXT_CHARS: usize = 256 * 1024;
+/// Maximum number of bytes shown in the hex view.
+const MAX_HEX_BYTES: usize = 16 * 1024;
+
+/// Kind of media shown through an object URL.
+#[derive(PartialEq, Copy, Clone)]
+pub(crate) enum MediaKind {
+    Image,
+    Pdf,
+    Audio,
+    Video,
+}
+
+/// Decompressed entry prepared for display.
+pub(crate) enum PreviewContent {
+    Text {
+        text: String,
+        encoding: &'static str,
+        truncated: bool,
+    },
+    Media {
+        kind: MediaKind,
+        mime: &'static str,
+        data: Vec<u8>,
+    },
+    Hex {
+        dump: String,
+        truncated: bool,
+    },
+}
+
+/// Media type by the file extension.
+fn media_type(file_name: &str) -> Option<(MediaKind, &'static str)> {
+    let extension = file_name.rsplit_once('.')?.1.to_ascii_lowercase();
+    let media = match extension.as_str() {
+        "png" => (MediaKind::Image, "image/png"),
+        "jpg" | "jpeg" => (MediaKind::Image, "image/jpeg"),
+        "gif" => (MediaKind::Image, "image/gif"),
+        "webp" => (MediaKind::Image, "image/webp"),
+        "bmp" => (MediaKind::Image, "image/bmp"),
+        "ico" => (MediaKind::Image, "image/x-icon"),
+        "svg" => (MediaKind::Image, "image/svg+xml"),
+        "pdf" => (MediaKind::Pdf, "application/pdf"),
+        "mp3" => (MediaKind::Audio, "audio/mpeg"),
+        "wav" => (MediaKind::Audio, "audio/wav"),
+        "ogg" | "oga" => (MediaKind::Audio, "audio/ogg"),
+        "flac" => (MediaKind::Audio, "audio/flac"),
+        "m4a" => (MediaKind::Audio, "audio/mp4"),
+        "mp4" | "m4v" => (MediaKind::Video, "video/mp4"),
+        "webm" => (MediaKind::Video, "video/webm"),
+        "ogv" => (MediaKind::Video, "video/ogg"),
+        "mov" => (MediaKind::Video, "video/quicktime"),
+        _ => return None,
+    };
+    Some(media)
+}
+
+/// Chooses how to show the entry: as media, as text or as a hex dump.
+pub(crate) fn make_preview(file_name: &str, data: Vec<u8>) -> PreviewContent {
+    if let Some((kind, mime)) = media_type(file_name) {
+        return PreviewContent::Media { kind, mime, data };
+    }
+    match crate::share::text_tools::decode_text(&data) {
+        Some((text, encoding)) => {
+            let truncated = text.chars().count() > MAX_TEXT_CHARS;
+            let text = if truncated { text.chars().take(MAX_TEXT_CHARS).collect() } else { text };
+            PreviewContent::Text { text, encoding, truncated }
+        }
+        None => PreviewContent::Hex {
+            dump: hex_dump(&data[..data.len().min(MAX_HEX_BYTES)]),
+            truncated: data.len() > MAX_HEX_BYTES,
+        },
+    }
+}
+
+/// Formats the data like `hexdump -C`: offset, 16 bytes in hex and their ASCII form.
+pub(crate) fn hex_dump(data: &[u8]) -> String {
+    let mut dump = String::with_capacity(data.len() * 4 + data.len() / 16 * 12);
+    for (line, chunk) in data.chunks(16).enumerate() {
+        dump.push_str(&format!("{:08x}  ", line * 16));
+        for ind in 0..16 {
+            match chunk.get(ind) {
+                Some(byte) => dump.push_str(&format!("{byte:02x} ")),
+                None => dump.push_str("   "),
+            }
+            if ind == 7 {
+                dump.push(' ');
+            }
+        }
+        dump.push_str(" |");
+        dump.extend(chunk.iter().map(|byte| {
+            if byte.is_ascii_graphic() || *byte == b' ' { *byte as char } else { '.' }
+        }));
+        dump.push_str("|\n");
+    }
+    dump
+}
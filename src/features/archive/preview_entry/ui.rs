@@ -0,0 +1,156 @@
+use crate::entities::archive::model::{InfoAboutArchiveEntry, OpenedArchive};
+use crate::features::archive::preview_entry::model::{self, MediaKind, PreviewContent};
+
+pub enum PreviewEntryMsg {
+    ShowPreview,
+    HidePreview,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct PreviewEntryProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    pub entry: InfoAboutArchiveEntry,
+    /// Password entered for the archive.
+    pub password: yew::AttrValue,
+}
+
+/// Component - shows the contents of an archive entry without extracting it.
+pub struct PreviewEntryComponent {
+    preview: Option<Result<PreviewContent, (String, String)>>,
+    /// Object URL with the data of a media entry.
+    object_url: Option<String>,
+}
+
+impl PreviewEntryComponent {
+    fn hide(&mut self) {
+        self.preview = None;
+        if let Some(url) = self.object_url.take() {
+            let _ = web_sys::Url::revoke_object_url(&url);
+        }
+    }
+
+    fn view_media(&self, kind: MediaKind, entry: &InfoAboutArchiveEntry) -> yew::Html {
+        let Some(src) = self.object_url.clone() else {
+            return yew::html! { <p>{"Oops, the preview is not available"}</p> };
+        };
+        match kind {
+            MediaKind::Image => yew::html! {
+                <img class={yew::classes!("img-fluid")} style="max-height: 50vh;" {src} alt={entry.entry_name.clone()} />
+            },
+            MediaKind::Pdf => yew::html! {
+                <iframe class={yew::classes!("w-100")} style="height: 50vh;" {src} title={entry.entry_name.clone()}></iframe>
+            },
+            MediaKind::Audio => yew::html! {
+                <audio class={yew::classes!("w-100")} controls={true} {src}></audio>
+            },
+            MediaKind::Video => yew::html! {
+                <video class={yew::classes!("w-100")} style="max-height: 50vh;" controls={true} {src}></video>
+            },
+        }
+    }
+}
+
+impl yew::Component for PreviewEntryComponent {
+    type Message = PreviewEntryMsg;
+    type Properties = PreviewEntryProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            preview: None,
+            object_url: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            PreviewEntryMsg::ShowPreview => {
+                self.hide();
+                let props = ctx.props();
+                let preview = props.archive.read_entry(&props.entry, &props.password)
+                    .map(|data| model::make_preview(props.entry.base_name(), data));
+                self.preview = Some(preview.map(|mut content| {
+                    if let PreviewContent::Media { mime, data, .. } = &mut content {
+                        let blob = gloo_file::Blob::new_with_options(data.as_slice(), Some(mime));
+                        self.object_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok();
+                        // The data now lives in the blob.
+                        *data = vec![];
+                    }
+                    content
+                }));
+                true
+            }
+            PreviewEntryMsg::HidePreview => {
+                self.hide();
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &yew::Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.hide();
+        true
+    }
+
+    fn destroy(&mut self, _ctx: &yew::Context<Self>) {
+        self.hide();
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let entry = &ctx.props().entry;
+        if entry.is_dir {
+            return yew::html! {};
+        }
+        let Some(preview) = &self.preview else {
+            let onclick = &ctx.link().callback(|_| PreviewEntryMsg::ShowPreview);
+            return yew::html! {
+                <button
+                    type="button"
+                    class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "mt-1")}
+                    title="Show the contents of the entry."
+                    {onclick}
+                >
+                    {"Preview"}
+                </button>
+            };
+        };
+
+        let onclick = &ctx.link().callback(|_| PreviewEntryMsg::HidePreview);
+        let (note, content) = match preview {
+            Err((msg, detail)) => (
+                String::new(),
+                yew::html! {
+                    <div
+                        class={yew::classes!("alert", "alert-danger", "mb-0")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.clone()}
+                    >{format!("Error: {msg}")}
+                    </div>
+                },
+            ),
+            Ok(PreviewContent::Text { text, encoding, truncated }) => (
+                format!("Text, {encoding}{}", if *truncated { ", truncated" } else { "" }),
+                yew::html! {
+                    <pre class={yew::classes!("border", "bg-white", "p-2", "mb-0", "overflow-auto")} style="max-height: 50vh;">{text}</pre>
+                },
+            ),
+            Ok(PreviewContent::Media { kind, mime, .. }) => (mime.to_string(), self.view_media(*kind, entry)),
+            Ok(PreviewContent::Hex { dump, truncated }) => (
+                format!("Binary{}", if *truncated { ", first 16 KB" } else { "" }),
+                yew::html! {
+                    <pre class={yew::classes!("border", "bg-white", "p-2", "mb-0", "overflow-auto", "font-monospace")} style="max-height: 50vh;">{dump}</pre>
+                },
+            ),
+        };
+        yew::html! {
+            <div class={yew::classes!("mt-1")}>
+                <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center", "mb-1")}>
+                    <small class={yew::classes!("text-muted")}>{note}</small>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} {onclick}>
+                        {"Close preview"}
+                    </button>
+                </div>
+                {content}
+            </div>
+        }
+    }
+}
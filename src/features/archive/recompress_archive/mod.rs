@@ -0,0 +1,6 @@
+//! Повторное сжатие архива другим методом или уровнем для уменьшения размера.
+
+mod ui;
+mod model;
+
+pub use ui::RecompressArchiveComponent;
@@ -0,0 +1,168 @@
+use crate::entities::archive::model::{EntryEncryption, InfoAboutArchiveEntry, OpenedArchive};
+use crate::features::archive::merge_archives::RawArchiveWriter;
+use crate::share::zip_tools::{self, TypeEncryption};
+
+/// Compression level of the zip crate above which Zopfli is used, with `level - 9` iterations.
+const ZOPFLI_LEVEL: i64 = 9 + 15;
+
+/// Method and level for the new entry data.
+///
+/// Only Deflate is offered. The `zstd` feature of the zip crate builds the C library
+/// through zstd-sys, and the encoder of ruzstd, which reads Zstd entries, only has its
+/// fastest level: about Zstd level 1, which rarely beats Deflate at level 9.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub(crate) enum Recompression {
+    /// Deflate with the level from 1 to 9.
+    Deflate(i64),
+    /// Deflate streams found by Zopfli: smallest, but many times slower.
+    Zopfli,
+}
+
+impl Recompression {
+    pub const ALL: [Self; 4] = [Self::Deflate(1), Self::Deflate(6), Self::Deflate(9), Self::Zopfli];
+
+    fn level(&self) -> i64 {
+        match self {
+            Self::Deflate(level) => *level,
+            Self::Zopfli => ZOPFLI_LEVEL,
+        }
+    }
+}
+
+impl std::fmt::Display for Recompression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Deflate(level) => write!(f, "Deflate, level {level}"),
+            Self::Zopfli => write!(f, "Deflate, Zopfli"),
+        }
+    }
+}
+
+impl From<String> for Recompression {
+    fn from(value: String) -> Self {
+        Self::ALL.into_iter()
+            .find(|recompression| recompression.to_string() == value)
+            .unwrap_or(Self::Deflate(9))
+    }
+}
+
+/// What happened to an entry.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub(crate) enum EntryOutcome {
+    /// The recompressed data is smaller and replaced the old one.
+    Recompressed,
+    /// The recompressed data is not smaller, the entry is copied raw.
+    NotSmaller,
+    /// The entry is encrypted and no password was given, the entry is copied raw.
+    Locked,
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct EntrySaving {
+    pub entry_name: yew::AttrValue,
+    /// Compressed size in the source archive.
+    pub old_size: u64,
+    /// Compressed size in the new archive.
+    pub new_size: u64,
+    pub outcome: EntryOutcome,
+}
+
+impl EntrySaving {
+    pub fn saved(&self) -> u64 {
+        self.old_size - self.new_size
+    }
+}
+
+/// Encryption of the recompressed entry: the same method, AES always with a 256-bit key.
+fn type_encryption(encryption: EntryEncryption) -> TypeEncryption {
+    match encryption {
+        EntryEncryption::ZipCrypto => TypeEncryption::ZipCrypto,
+        _ => TypeEncryption::Aes256,
+    }
+}
+
+/// Whether the entry is decompressed and compressed again.
+fn can_recompress(entry: &InfoAboutArchiveEntry, password: &str) -> bool {
+    !entry.is_dir && (!entry.encryption.is_encrypted() || !password.is_empty())
+}
+
+/// Compresses the entries again into a temporary archive.
+/// Encrypted entries are encrypted again with the same password.
+fn recompress_entries(
+    archive: &OpenedArchive,
+    password: &str,
+    recompression: Recompression,
+) -> Result<OpenedArchive, (String, String)> {
+    let mut zip = archive.zip();
+    let mut zip_writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for entry in archive.entries.iter().filter(|entry| can_recompress(entry, password)) {
+        let mut file = entry.open(&mut zip, password)?;
+        let new_password = if entry.encryption.is_encrypted() { password } else { "" };
+        let options = zip_tools::entry_options(&file, new_password, type_encryption(entry.encryption))
+            .compression_method(zip::CompressionMethod::Deflated)
+            .compression_level(Some(recompression.level()));
+        zip_writer.start_file(entry.entry_name.as_str(), options).map_err(|err| (
+            format!("Failed to add file meta information to archive: {}.", entry.entry_name),
+            format!("{}", err)
+        ))?;
+        std::io::copy(&mut file, &mut zip_writer).map_err(|err| entry.describe_read_error(err))?;
+    }
+    let data = zip_writer.finish()
+        .map_err(|err| ("Failed to create archive.".to_string(), format!("{}", err)))?
+        .into_inner();
+    OpenedArchive::from_bytes(yew::AttrValue::default(), data)
+}
+
+/// Writes a copy of the archive with the entries compressed again.
+///
+/// An entry keeps its old data when the new one is not smaller, and encrypted
+/// entries keep it when no password is given. Kept entries are copied raw.
+/// Returns the new archive and the result for every entry except directories.
+pub(crate) fn recompress_archive(
+    archive: &OpenedArchive,
+    password: &str,
+    recompression: Recompression,
+) -> Result<(Vec<u8>, Vec<EntrySaving>), (String, String)> {
+    let recompressed = recompress_entries(archive, password, recompression)?;
+    let mut recompressed_entries = recompressed.entries.iter();
+    let mut writer = RawArchiveWriter::with_capacity(archive.data().len());
+    let mut savings = vec![];
+    for entry in &archive.entries {
+        if entry.is_dir {
+            writer.copy_entry(archive, entry, &entry.entry_name)?;
+            continue;
+        }
+        let new_entry = if can_recompress(entry, password) {
+            Some(recompressed_entries.next().ok_or((
+                format!("Failed to recompress entry: {}.", entry.entry_name),
+                "The entry is missing in the temporary archive.".to_string(),
+            ))?)
+        } else {
+            None
+        };
+        let saving = match new_entry {
+            Some(new_entry) if new_entry.compressed_size < entry.compressed_size => {
+                // The zip crate does not write entry comments, so the old one is copied.
+                let new_entry = InfoAboutArchiveEntry { comment: entry.comment.clone(), ..new_entry.clone() };
+                writer.copy_entry(&recompressed, &new_entry, &entry.entry_name)?;
+                EntrySaving {
+                    entry_name: entry.entry_name.clone(),
+                    old_size: entry.compressed_size,
+                    new_size: new_entry.compressed_size,
+                    outcome: EntryOutcome::Recompressed,
+                }
+            }
+            new_entry => {
+                writer.copy_entry(archive, entry, &entry.entry_name)?;
+                EntrySaving {
+                    entry_name: entry.entry_name.clone(),
+                    old_size: entry.compressed_size,
+                    new_size: entry.compressed_size,
+                    outcome: if new_entry.is_some() { EntryOutcome::NotSmaller } else { EntryOutcome::Locked },
+                }
+            }
+        };
+        savings.push(saving);
+    }
+    Ok((writer.finish(&archive.comment)?, savings))
+}
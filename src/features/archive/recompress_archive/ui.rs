@@ -0,0 +1,189 @@
+use yew::TargetCast;
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::recompress_archive::model::{self, EntryOutcome, EntrySaving, Recompression};
+use crate::share::size_to_string;
+
+pub enum RecompressArchiveMsg {
+    ChangeRecompression(String),
+    StartRecompression,
+    Reset,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct RecompressArchiveProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    /// Password of the encrypted entries.
+    pub password: yew::AttrValue,
+}
+
+/// Component - writes a copy of the archive with the entries compressed again to make it smaller.
+pub struct RecompressArchiveComponent {
+    recompression: Recompression,
+    savings: Vec<EntrySaving>,
+    err_msg: Option<(String, String)>,
+    blob_result: Option<gloo_file::Blob>,
+}
+
+impl RecompressArchiveComponent {
+    fn get_obj_url_with_result(&self) -> Option<String> {
+        let blob = self.blob_result.as_ref()?;
+        let download_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok()?;
+        Some(download_url)
+    }
+
+    fn view_saving(saving: &EntrySaving) -> yew::Html {
+        let result = match saving.outcome {
+            EntryOutcome::Recompressed => format!(
+                "-{} ({:.1}%)",
+                size_to_string(saving.saved() as f64),
+                saving.saved() as f64 * 100.0 / saving.old_size as f64,
+            ),
+            EntryOutcome::NotSmaller => "not smaller, kept".to_string(),
+            EntryOutcome::Locked => "encrypted, kept".to_string(),
+        };
+        yew::html! {
+            <tr>
+                <td class={yew::classes!("text-break")}>{saving.entry_name.clone()}</td>
+                <td class={yew::classes!("text-nowrap")}>{size_to_string(saving.old_size as f64)}</td>
+                <td class={yew::classes!("text-nowrap")}>{size_to_string(saving.new_size as f64)}</td>
+                <td class={yew::classes!("text-nowrap")}>{result}</td>
+            </tr>
+        }
+    }
+}
+
+impl yew::Component for RecompressArchiveComponent {
+    type Message = RecompressArchiveMsg;
+    type Properties = RecompressArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            recompression: Recompression::Deflate(9),
+            savings: vec![],
+            err_msg: None,
+            blob_result: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            RecompressArchiveMsg::ChangeRecompression(value) => {
+                self.recompression = Recompression::from(value);
+                false
+            }
+            RecompressArchiveMsg::StartRecompression => {
+                let props = ctx.props();
+                match model::recompress_archive(&props.archive, &props.password, self.recompression) {
+                    Ok((data, savings)) => {
+                        self.err_msg = None;
+                        self.savings = savings;
+                        self.blob_result = Some(gloo_file::Blob::new(data.as_slice()));
+                    }
+                    Err(err) => { self.err_msg = Some(err); }
+                }
+                true
+            }
+            RecompressArchiveMsg::Reset => {
+                self.err_msg = None;
+                self.savings = vec![];
+                self.blob_result = None;
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &yew::Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.err_msg = None;
+        self.savings = vec![];
+        self.blob_result = None;
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let reset_onclick = &ctx.link().callback(|_| RecompressArchiveMsg::Reset);
+        if let (Some(href), Some(blob)) = (self.get_obj_url_with_result(), self.blob_result.as_ref()) {
+            let old_size = ctx.props().archive.data().len() as f64;
+            let new_size = blob.size() as f64;
+            let recompressed = self.savings.iter()
+                .filter(|saving| saving.outcome == EntryOutcome::Recompressed)
+                .count();
+            return yew::html! {
+                <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                    <small class={yew::classes!("mb-1")}>
+                        {format!(
+                            "Recompressed entries: {} of {}. Archive size: {} → {}.",
+                            recompressed,
+                            self.savings.len(),
+                            size_to_string(old_size),
+                            size_to_string(new_size),
+                        )}
+                    </small>
+                    <div style="max-height: 300px; overflow-y: auto;">
+                        <table class={yew::classes!("table", "table-sm", "small", "mb-1")}>
+                            <thead>
+                                <tr>
+                                    <th>{"Entry"}</th>
+                                    <th>{"Before"}</th>
+                                    <th>{"After"}</th>
+                                    <th>{"Saved"}</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {for self.savings.iter().map(Self::view_saving)}
+                            </tbody>
+                        </table>
+                    </div>
+                    <div class={yew::classes!("d-flex", "justify-content-end")}>
+                        <a
+                            class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")}
+                            href={href}
+                            download={ctx.props().archive.file_name.clone()}
+                        >
+                            {"Download recompressed archive"}
+                        </a>
+                        <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                            {"Back"}
+                        </button>
+                    </div>
+                </div>
+            };
+        }
+
+        let onchange_recompression = &ctx.link().callback(move |e: web_sys::Event| {
+            let select: web_sys::HtmlInputElement = e.target_unchecked_into();
+            RecompressArchiveMsg::ChangeRecompression(select.value())
+        });
+        let start_onclick = &ctx.link().callback(|_| RecompressArchiveMsg::StartRecompression);
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                <div class={yew::classes!("input-group", "input-group-sm")}>
+                    <span class={yew::classes!("input-group-text")}>{"Compression"}</span>
+                    <select class={yew::classes!("form-select")} onchange={onchange_recompression}>
+                        {for Recompression::ALL.into_iter().map(|recompression| yew::html! {
+                            <option selected={recompression == self.recompression} value={recompression.to_string()}>
+                                {recompression.to_string()}
+                            </option>
+                        })}
+                    </select>
+                    <button
+                        type="button"
+                        class={yew::classes!("btn", "btn-outline-dark")}
+                        title="Write a copy of the archive with the entries compressed again. Entries that do not get smaller are kept as they are."
+                        onclick={start_onclick}
+                    >
+                        {"Optimize"}
+                    </button>
+                </div>
+                if let Some((msg, detail)) = &self.err_msg {
+                    <div
+                        class={yew::classes!("alert", "alert-danger", "mt-2", "mb-0")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.clone()}
+                        onclick={reset_onclick}
+                    >{format!("Error: {msg}")}
+                    </div>
+                }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Восстановление элементов повреждённого архива по локальным заголовкам.
+
+mod ui;
+mod model;
+
+pub use ui::RecoverArchiveComponent;
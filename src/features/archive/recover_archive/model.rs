@@ -0,0 +1,252 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::archive_limits::ArchiveLimits;
+use crate::features::archive::merge_archives::RawArchiveWriter;
+use crate::share;
+use crate::share::zip_format::{
+    CENTRAL_HEADER_SIGNATURE, DATA_DESCRIPTOR_SIGNATURE, FLAG_DATA_DESCRIPTOR, LOCAL_HEADER_SIGNATURE, LOCAL_HEADER_SIZE,
+    ZIP64_EXTRA_FIELD_ID, ZIP64_SIZE,
+};
+
+/// An entry found by its local file header.
+struct LocalEntry {
+    name: String,
+    /// Fixed part of the local header from the version up to the uncompressed size.
+    header: [u8; 22],
+    crc32: u32,
+    compressed_size: u32,
+    size: u32,
+    name_and_extra: std::ops::Range<usize>,
+    extra_len: u16,
+    /// Bytes of the entry in the damaged archive, including the data descriptor.
+    range: std::ops::Range<usize>,
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) enum RecoveryStatus {
+    /// The entry decompressed with a valid CRC32.
+    Recovered,
+    /// The entry is kept without decompression: it is encrypted and can only be checked
+    /// with the password, or it exceeds the limits. Holds the reason.
+    NotVerified(String),
+    Lost(String, String),
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct RecoveredEntry {
+    pub name: String,
+    pub status: RecoveryStatus,
+}
+
+pub(crate) struct RecoveryReport {
+    pub entries: Vec<RecoveredEntry>,
+    /// Archive made of the recovered and unverified entries.
+    pub data: Option<Vec<u8>>,
+}
+
+/// Reads a little-endian size of 4 or 8 bytes.
+fn read_size(data: &[u8], offset: usize, zip64: bool) -> Option<u64> {
+    if zip64 {
+        share::read_u64_le(data, offset)
+    } else {
+        share::read_u32_le(data, offset).map(u64::from)
+    }
+}
+
+/// Uncompressed and compressed sizes from the Zip64 extra field of a local header.
+/// Returns `None` if the field is absent.
+fn zip64_sizes(extra_field: &[u8]) -> Option<(u64, u64)> {
+    let mut offset = 0;
+    while let (Some(id), Some(size)) = (
+        share::read_u16_le(extra_field, offset),
+        share::read_u16_le(extra_field, offset + 2),
+    ) {
+        let block = offset + 4;
+        if id == ZIP64_EXTRA_FIELD_ID {
+            return Some((read_size(extra_field, block, true)?, read_size(extra_field, block + 8, true)?));
+        }
+        offset = block + size as usize;
+    }
+    None
+}
+
+/// Finds the end of entry data followed by a data descriptor.
+/// Returns the CRC32, the sizes and the end of the descriptor.
+fn find_data_descriptor(data: &[u8], data_start: usize, zip64: bool) -> Option<(u32, u64, u64, usize)> {
+    let size_len = if zip64 { 8 } else { 4 };
+    let descriptor_len = 4 + 2 * size_len;
+    let mut pos = data_start;
+    while pos + 4 <= data.len() {
+        let signature = &data[pos..pos + 4];
+        // A descriptor without the optional signature ends right before the next header.
+        let descriptor = if signature == DATA_DESCRIPTOR_SIGNATURE {
+            Some((pos, pos + 4 + descriptor_len))
+        } else if (signature == LOCAL_HEADER_SIGNATURE || signature == CENTRAL_HEADER_SIGNATURE)
+            && pos >= data_start + descriptor_len {
+            Some((pos - descriptor_len, pos))
+        } else {
+            None
+        };
+        if let Some((data_end, end)) = descriptor {
+            let fields = end - descriptor_len;
+            let compressed_size = read_size(data, fields + 4, zip64)?;
+            if compressed_size == (data_end - data_start) as u64 {
+                return Some((
+                    share::read_u32_le(data, fields)?,
+                    compressed_size,
+                    read_size(data, fields + 4 + size_len, zip64)?,
+                    end,
+                ));
+            }
+        }
+        pos += 1;
+    }
+    None
+}
+
+/// Parses the local file header at the offset.
+/// On failure returns the name of the entry, if it was read, and the reason.
+fn read_local_entry(data: &[u8], header_start: usize) -> Result<LocalEntry, (String, String)> {
+    let truncated = || "The archive ends inside the entry.".to_string();
+    let header: [u8; 22] = data.get(header_start + 4..header_start + 26)
+        .and_then(|header| header.try_into().ok())
+        .ok_or((String::new(), truncated()))?;
+    let flags = share::read_u16_le(&header, 2).unwrap_or(0);
+    let name_len = share::read_u16_le(data, header_start + 26).ok_or((String::new(), truncated()))? as usize;
+    let extra_len = share::read_u16_le(data, header_start + 28).ok_or((String::new(), truncated()))?;
+    let name_start = header_start + LOCAL_HEADER_SIZE;
+    let data_start = name_start + name_len + extra_len as usize;
+    let name = data.get(name_start..name_start + name_len)
+        .map(|name| String::from_utf8_lossy(name).to_string())
+        .ok_or((String::new(), truncated()))?;
+    let Some(extra_field) = data.get(name_start + name_len..data_start) else {
+        return Err((name, truncated()));
+    };
+    let zip64 = zip64_sizes(extra_field);
+
+    let (crc32, compressed_size, size, end) = if flags & FLAG_DATA_DESCRIPTOR != 0 {
+        find_data_descriptor(data, data_start, zip64.is_some())
+            .ok_or((name.clone(), "The data descriptor with the size of the entry was not found.".to_string()))?
+    } else {
+        let (zip64_size, zip64_compressed_size) = zip64.unwrap_or_default();
+        let size = share::read_u32_le(&header, 18).unwrap_or(0);
+        let size = if size == ZIP64_SIZE { zip64_size } else { size as u64 };
+        let compressed_size = share::read_u32_le(&header, 14).unwrap_or(0);
+        let compressed_size = if compressed_size == ZIP64_SIZE { zip64_compressed_size } else { compressed_size as u64 };
+        (
+            share::read_u32_le(&header, 10).unwrap_or(0),
+            compressed_size,
+            size,
+            data_start.saturating_add(compressed_size as usize),
+        )
+    };
+    let (Ok(compressed_size), Ok(size)) = (u32::try_from(compressed_size), u32::try_from(size)) else {
+        return Err((name, "Entries larger than 4 GB are not supported by the recovery.".to_string()));
+    };
+    if compressed_size == ZIP64_SIZE || size == ZIP64_SIZE {
+        return Err((name, "Entries larger than 4 GB are not supported by the recovery.".to_string()));
+    }
+    if end > data.len() {
+        return Err((name, truncated()));
+    }
+
+    Ok(LocalEntry {
+        name,
+        header,
+        crc32,
+        compressed_size,
+        size,
+        name_and_extra: name_start..data_start,
+        extra_len,
+        range: header_start..end,
+    })
+}
+
+/// Copies the entries into a new archive with a rebuilt central directory.
+fn build_archive(data: &[u8], entries: &[&LocalEntry]) -> Result<Vec<u8>, (String, String)> {
+    let mut writer = RawArchiveWriter::with_capacity(entries.iter().map(|entry| entry.range.len()).sum());
+    for entry in entries {
+        let name_len = entry.name_and_extra.len() - entry.extra_len as usize;
+        let mut central_record = vec![];
+        central_record.extend_from_slice(&CENTRAL_HEADER_SIGNATURE);
+        // Version made by: the same as the version needed to extract.
+        central_record.extend_from_slice(&entry.header[0..2]);
+        // Version, flags, method, time and date.
+        central_record.extend_from_slice(&entry.header[0..10]);
+        central_record.extend_from_slice(&entry.crc32.to_le_bytes());
+        central_record.extend_from_slice(&entry.compressed_size.to_le_bytes());
+        central_record.extend_from_slice(&entry.size.to_le_bytes());
+        central_record.extend_from_slice(&(name_len as u16).to_le_bytes());
+        central_record.extend_from_slice(&entry.extra_len.to_le_bytes());
+        // Comment length, disk number, internal and external attributes, and the offset set by the writer.
+        central_record.extend_from_slice(&[0; 14]);
+        central_record.extend_from_slice(&data[entry.name_and_extra.clone()]);
+        writer.append_entry(&data[entry.range.clone()], central_record)?;
+    }
+    writer.finish("")
+}
+
+/// Scans a damaged archive for local file headers and keeps the entries
+/// that decompress with a valid CRC32.
+/// Entries that exceed the limits are kept without decompression, like encrypted ones.
+pub(crate) fn recover_archive(
+    file_name: &yew::AttrValue,
+    data: &[u8],
+    limits: &ArchiveLimits,
+) -> Result<RecoveryReport, (String, String)> {
+    let mut found = vec![];
+    let mut report = vec![];
+    let mut pos = 0;
+    while let Some(offset) = data.get(pos..).and_then(|rest| rest.windows(4).position(|window| window == LOCAL_HEADER_SIGNATURE)) {
+        let header_start = pos + offset;
+        match read_local_entry(data, header_start) {
+            Ok(entry) => {
+                pos = entry.range.end;
+                found.push(entry);
+            }
+            Err((name, detail)) => {
+                pos = header_start + 4;
+                let name = if name.is_empty() { format!("Entry at offset {header_start}") } else { name };
+                report.push((header_start, RecoveredEntry {
+                    status: RecoveryStatus::Lost(format!("Lost: {name}."), detail),
+                    name,
+                }));
+            }
+        }
+    }
+
+    let mut kept = vec![];
+    // Total size of the decompressed entries.
+    let mut unpacked = 0u64;
+    for local_entry in &found {
+        // Each entry is checked in an archive of its own, so a damaged header does not hide the others.
+        let status = match build_archive(data, &[local_entry]).and_then(|archive| OpenedArchive::from_bytes(file_name.clone(), archive)) {
+            Ok(archive) => match archive.entries.first() {
+                Some(entry) if entry.encryption.is_encrypted() => RecoveryStatus::NotVerified(
+                    "Encrypted, test the archive with the password after opening it.".to_string(),
+                ),
+                Some(entry) => match limits.check_entry(unpacked, entry.raw_size, entry.compressed_size) {
+                    Some(warning) => RecoveryStatus::NotVerified(format!("Not decompressed. {}", warning)),
+                    None => {
+                        unpacked += entry.raw_size;
+                        match archive.read_entry(entry, "") {
+                            Ok(_) => RecoveryStatus::Recovered,
+                            Err((msg, detail)) => RecoveryStatus::Lost(msg, detail),
+                        }
+                    }
+                },
+                None => RecoveryStatus::Lost(format!("Lost: {}.", local_entry.name), String::new()),
+            },
+            Err((_, detail)) => RecoveryStatus::Lost(format!("Lost: {}.", local_entry.name), detail),
+        };
+        if !matches!(status, RecoveryStatus::Lost(..)) {
+            kept.push(local_entry);
+        }
+        report.push((local_entry.range.start, RecoveredEntry { name: local_entry.name.clone(), status }));
+    }
+    report.sort_by_key(|(offset, _)| *offset);
+
+    Ok(RecoveryReport {
+        entries: report.into_iter().map(|(_, entry)| entry).collect(),
+        data: if kept.is_empty() { None } else { Some(build_archive(data, &kept)?) },
+    })
+}
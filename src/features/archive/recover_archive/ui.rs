@@ -0,0 +1,179 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::archive_limits::ArchiveLimits;
+use crate::features::archive::recover_archive::model::{self, RecoveredEntry, RecoveryReport, RecoveryStatus};
+
+pub enum RecoverArchiveMsg {
+    Recover,
+    OpenRecovered,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct RecoverArchiveProps {
+    /// Name of the damaged archive on disk.
+    pub file_name: yew::AttrValue,
+    /// Contents of the damaged archive.
+    pub data: std::rc::Rc<[u8]>,
+    /// Limits of the entries decompressed to verify them.
+    pub limits: ArchiveLimits,
+    /// Callback with the archive made of the recovered entries.
+    pub on_archive_opened: yew::Callback<OpenedArchive>,
+}
+
+/// Component - rebuilds a damaged archive from the entries that are still readable.
+pub struct RecoverArchiveComponent {
+    report: Option<RecoveryReport>,
+    blob_result: Option<gloo_file::Blob>,
+    err_msg: Option<(String, String)>,
+}
+
+impl RecoverArchiveComponent {
+    fn get_obj_url_with_result(&self) -> Option<String> {
+        let blob = self.blob_result.as_ref()?;
+        let download_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok()?;
+        Some(download_url)
+    }
+
+    /// Name of the repaired archive, e.g. `backup (repaired).zip`.
+    fn repaired_file_name(file_name: &str) -> String {
+        let stem = file_name.strip_suffix(".zip").unwrap_or(file_name);
+        format!("{stem} (repaired).zip")
+    }
+
+    fn view_error(&self) -> yew::Html {
+        let Some((msg, detail)) = &self.err_msg else { return yew::html! {}; };
+        yew::html! {
+            <div
+                class={yew::classes!("alert", "alert-danger", "mt-2", "mb-0")}
+                data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                title={detail.clone()}
+            >{format!("Error: {msg}")}
+            </div>
+        }
+    }
+
+    fn view_entry_row(entry: &RecoveredEntry) -> yew::Html {
+        let (badge_class, badge, message, detail) = match &entry.status {
+            RecoveryStatus::Recovered => ("bg-success", "RECOVERED", String::new(), String::new()),
+            RecoveryStatus::NotVerified(reason) => ("bg-secondary", "NOT VERIFIED", reason.clone(), String::new()),
+            RecoveryStatus::Lost(msg, detail) => ("bg-danger", "LOST", msg.clone(), detail.clone()),
+        };
+        yew::html! {
+            <div class={yew::classes!("list-group-item", "p-1")}>
+                <div class={yew::classes!("d-flex", "w-100", "justify-content-between", "align-items-center")}>
+                    <small class={yew::classes!("text-truncate")}>{&entry.name}</small>
+                    <span class={yew::classes!("badge", badge_class)}>{badge}</span>
+                </div>
+                if !message.is_empty() {
+                    <small
+                        class={yew::classes!("d-block", "text-muted")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail}
+                    >{message}</small>
+                }
+            </div>
+        }
+    }
+}
+
+impl yew::Component for RecoverArchiveComponent {
+    type Message = RecoverArchiveMsg;
+    type Properties = RecoverArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            report: None,
+            blob_result: None,
+            err_msg: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            RecoverArchiveMsg::Recover => {
+                let props = ctx.props();
+                match model::recover_archive(&props.file_name, &props.data, &props.limits) {
+                    Ok(report) => {
+                        self.blob_result = report.data.as_ref().map(|data| gloo_file::Blob::new(data.as_slice()));
+                        self.report = Some(report);
+                        self.err_msg = None;
+                    }
+                    Err(err) => {
+                        self.report = None;
+                        self.err_msg = Some(err);
+                    }
+                }
+                true
+            }
+            RecoverArchiveMsg::OpenRecovered => {
+                let Some(data) = self.report.as_ref().and_then(|report| report.data.clone()) else { return false; };
+                let file_name = yew::AttrValue::from(Self::repaired_file_name(&ctx.props().file_name));
+                match OpenedArchive::from_bytes(file_name, data) {
+                    Ok(archive) => { ctx.props().on_archive_opened.emit(archive); }
+                    Err(err) => { self.err_msg = Some(err); }
+                }
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &yew::Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.report = None;
+        self.blob_result = None;
+        self.err_msg = None;
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let Some(report) = &self.report else {
+            let onclick = &ctx.link().callback(|_| RecoverArchiveMsg::Recover);
+            return yew::html! {
+                <>
+                    <button
+                        type="button"
+                        class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "mt-2")}
+                        title="Search the file for entry headers and keep the entries with valid data."
+                        {onclick}
+                    >
+                        {"Try to recover"}
+                    </button>
+                    {self.view_error()}
+                </>
+            };
+        };
+
+        let lost = report.entries.iter()
+            .filter(|entry| matches!(entry.status, RecoveryStatus::Lost(..)))
+            .count();
+        let summary = format!(
+            "Recovered {} of {} entries found.",
+            report.entries.len() - lost,
+            report.entries.len()
+        );
+        let open_onclick = &ctx.link().callback(|_| RecoverArchiveMsg::OpenRecovered);
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "w-100", "mt-2")}>
+                <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center", "mb-1")}>
+                    <span class={yew::classes!(if lost == 0 { "text-success" } else { "text-danger" })}>{summary}</span>
+                    if let Some(href) = self.get_obj_url_with_result() {
+                        <div>
+                            <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")} onclick={open_onclick}>
+                                {"Open recovered"}
+                            </button>
+                            <a
+                                class={yew::classes!("btn", "btn-sm", "btn-outline-dark")}
+                                href={href}
+                                download={Self::repaired_file_name(&ctx.props().file_name)}
+                            >
+                                {"Download repaired archive"}
+                            </a>
+                        </div>
+                    }
+                </div>
+                <div class={yew::classes!("list-group", "overflow-auto")} style="max-height: 25vh;">
+                    { report.entries.iter().map(Self::view_entry_row).collect::<yew::Html>() }
+                </div>
+                {self.view_error()}
+            </div>
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Смена пароля и метода шифрования существующего архива.
+
+mod ui;
+mod model;
+
+pub use ui::ReencryptArchiveComponent;
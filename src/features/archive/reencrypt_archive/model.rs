@@ -0,0 +1,63 @@
+use crate::entities::archive::model::{InfoAboutArchiveEntry, OpenedArchive};
+use crate::features::archive::merge_archives::RawArchiveWriter;
+use crate::share::zip_tools::{self, TypeEncryption};
+
+/// Whether the entry is decrypted and encrypted again, the other entries are copied raw.
+fn needs_reencryption(entry: &InfoAboutArchiveEntry, new_password: &str) -> bool {
+    !entry.is_dir && (entry.encryption.is_encrypted() || !new_password.is_empty())
+}
+
+/// Encrypts the entries again into a temporary archive.
+fn reencrypt_entries(
+    archive: &OpenedArchive,
+    old_password: &str,
+    new_password: &str,
+    type_encryption: TypeEncryption,
+) -> Result<OpenedArchive, (String, String)> {
+    let mut zip = archive.zip();
+    let mut zip_writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::with_capacity(archive.data().len())));
+    for entry in archive.entries.iter().filter(|entry| needs_reencryption(entry, new_password)) {
+        let mut file = entry.open(&mut zip, old_password)?;
+        let options = zip_tools::entry_options(&file, new_password, type_encryption);
+        zip_writer.start_file(entry.entry_name.as_str(), options).map_err(|err| (
+            format!("Failed to add file meta information to archive: {}.", entry.entry_name),
+            format!("{}", err)
+        ))?;
+        std::io::copy(&mut file, &mut zip_writer).map_err(|err| entry.describe_read_error(err))?;
+    }
+    let data = zip_writer.finish()
+        .map_err(|err| ("Failed to create archive.".to_string(), format!("{}", err)))?
+        .into_inner();
+    OpenedArchive::from_bytes(yew::AttrValue::default(), data)
+}
+
+/// Writes a copy of the archive with a new password and encryption method.
+///
+/// Entries are decrypted and encrypted again one at a time into a temporary archive.
+/// Unencrypted entries and directories that stay unencrypted are copied raw.
+/// The archive comment and the entry comments are kept.
+/// An empty `new_password` removes the encryption.
+pub(crate) fn reencrypt_archive(
+    archive: &OpenedArchive,
+    old_password: &str,
+    new_password: &str,
+    type_encryption: TypeEncryption,
+) -> Result<Vec<u8>, (String, String)> {
+    let reencrypted = reencrypt_entries(archive, old_password, new_password, type_encryption)?;
+    let mut reencrypted_entries = reencrypted.entries.iter();
+    let mut writer = RawArchiveWriter::with_capacity(archive.data().len());
+    for entry in &archive.entries {
+        if !needs_reencryption(entry, new_password) {
+            writer.copy_entry(archive, entry, &entry.entry_name)?;
+            continue;
+        }
+        let new_entry = reencrypted_entries.next().ok_or((
+            format!("Failed to encrypt entry: {}.", entry.entry_name),
+            "The entry is missing in the temporary archive.".to_string(),
+        ))?;
+        // The zip crate does not write entry comments, so the old one is copied.
+        let new_entry = InfoAboutArchiveEntry { comment: entry.comment.clone(), ..new_entry.clone() };
+        writer.copy_entry(&reencrypted, &new_entry, &entry.entry_name)?;
+    }
+    writer.finish(&archive.comment)
+}
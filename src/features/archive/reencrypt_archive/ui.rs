@@ -0,0 +1,151 @@
+use yew::TargetCast;
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::reencrypt_archive::model;
+use crate::share::zip_tools::TypeEncryption;
+
+pub enum ReencryptArchiveMsg {
+    /// New password from the input field.
+    EditPassword(String),
+    ChangeTypeEncryption(String),
+    StartReencryption,
+    Reset,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct ReencryptArchiveProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    /// Current password of the archive.
+    pub password: yew::AttrValue,
+}
+
+/// Component - writes a copy of the archive with a new password or encryption method.
+pub struct ReencryptArchiveComponent {
+    new_password: String,
+    type_encryption: TypeEncryption,
+    err_msg: Option<(String, String)>,
+    blob_result: Option<gloo_file::Blob>,
+}
+
+impl ReencryptArchiveComponent {
+    fn get_obj_url_with_result(&self) -> Option<String> {
+        let blob = self.blob_result.as_ref()?;
+        let download_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok()?;
+        Some(download_url)
+    }
+}
+
+impl yew::Component for ReencryptArchiveComponent {
+    type Message = ReencryptArchiveMsg;
+    type Properties = ReencryptArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            new_password: String::new(),
+            type_encryption: TypeEncryption::Aes256,
+            err_msg: None,
+            blob_result: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ReencryptArchiveMsg::EditPassword(value) => {
+                self.new_password = value.trim().to_string();
+                true
+            }
+            ReencryptArchiveMsg::ChangeTypeEncryption(value) => {
+                self.type_encryption = TypeEncryption::from(value);
+                true
+            }
+            ReencryptArchiveMsg::StartReencryption => {
+                let props = ctx.props();
+                match model::reencrypt_archive(&props.archive, &props.password, &self.new_password, self.type_encryption) {
+                    Ok(data) => {
+                        self.err_msg = None;
+                        self.blob_result = Some(gloo_file::Blob::new(data.as_slice()));
+                    }
+                    Err(err) => { self.err_msg = Some(err); }
+                }
+                true
+            }
+            ReencryptArchiveMsg::Reset => {
+                self.err_msg = None;
+                self.blob_result = None;
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &yew::Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.err_msg = None;
+        self.blob_result = None;
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let reset_onclick = &ctx.link().callback(|_| ReencryptArchiveMsg::Reset);
+        if let Some(href) = self.get_obj_url_with_result() {
+            return yew::html! {
+                <div class={yew::classes!("d-flex", "justify-content-end")}>
+                    <a
+                        class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")}
+                        href={href}
+                        download={ctx.props().archive.file_name.clone()}
+                    >
+                        {"Download re-encrypted archive"}
+                    </a>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                        {"Back"}
+                    </button>
+                </div>
+            };
+        }
+
+        let onchange_password = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            ReencryptArchiveMsg::EditPassword(input.value())
+        });
+        let onchange_type = &ctx.link().callback(move |e: web_sys::Event| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            ReencryptArchiveMsg::ChangeTypeEncryption(input.value())
+        });
+        let start_onclick = &ctx.link().callback(|_| ReencryptArchiveMsg::StartReencryption);
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                <div class={yew::classes!("input-group", "input-group-sm")}>
+                    <select class={yew::classes!("form-select", "form-select-sm")}
+                        style="max-width: 135px;"
+                        onchange={onchange_type}
+                    >
+                        <option selected={self.type_encryption == TypeEncryption::ZipCrypto} value={"ZipCrypto"}>{"ZipCrypto"}</option>
+                        <option selected={self.type_encryption == TypeEncryption::Aes256} value={"Aes256"}>{"Aes256"}</option>
+                    </select>
+                    <input
+                        class={yew::classes!("form-control")}
+                        type="password"
+                        placeholder="New password (empty to remove)" aria-label="New password"
+                        value={self.new_password.clone()}
+                        onchange={onchange_password}
+                    />
+                    <button
+                        type="button"
+                        class={yew::classes!("btn", "btn-outline-dark")}
+                        title="Write a copy of the archive with the new password."
+                        onclick={start_onclick}
+                    >
+                        if self.new_password.is_empty() { {"Remove password"} } else { {"Re-encrypt"} }
+                    </button>
+                </div>
+                if let Some((msg, detail)) = &self.err_msg {
+                    <div
+                        class={yew::classes!("alert", "alert-danger", "mt-2", "mb-0")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.clone()}
+                        onclick={reset_onclick}
+                    >{format!("Error: {msg}")}
+                    </div>
+                }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Поиск по именам и содержимому текстовых элементов архива.
+
+mod ui;
+mod model;
+
+pub use ui::SearchArchiveComponent;
@@ -0,0 +1,230 @@
+use std::io::Read;
+use crate::entities::archive::model::{InfoAboutArchiveEntry, OpenedArchive};
+
+/// Size of the chunks in which entries are decompressed while searching.
+const CHUNK_SIZE: usize = 64 * 1024;
+/// Matching lines shown for one entry, the others are only counted.
+const MAX_LINES_PER_ENTRY: usize = 50;
+/// Characters of a matching line shown on each side of the match.
+const CONTEXT_CHARS: usize = 80;
+/// Bytes of an unfinished line kept in memory. Longer lines are searched in parts of this size.
+const MAX_LINE_LENGTH: usize = 4 * CHUNK_SIZE;
+/// Entries searched between two pauses that let the page repaint.
+const ENTRIES_PER_YIELD: usize = 64;
+
+#[derive(PartialEq, Clone, Default)]
+pub(crate) struct SearchQuery {
+    pub pattern: String,
+    /// The pattern is a regular expression instead of a plain string.
+    pub is_regex: bool,
+    pub match_case: bool,
+    /// Search the decompressed contents of text entries, not only the names.
+    pub in_contents: bool,
+}
+
+/// A line of an entry with the first match in it.
+#[derive(PartialEq, Clone)]
+pub(crate) struct LineMatch {
+    /// Number of the line, starting from 1.
+    pub number: usize,
+    pub before: String,
+    pub matched: String,
+    pub after: String,
+    /// The line is longer than `MAX_LINE_LENGTH` and only a part of it is shown.
+    pub is_truncated: bool,
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct EntrySearchResult {
+    pub entry: InfoAboutArchiveEntry,
+    pub name_matches: bool,
+    pub lines: Vec<LineMatch>,
+    /// Number of matching lines, including the ones not kept in `lines`.
+    pub total_lines: usize,
+    /// Error while reading the entry, the lines found before it are kept.
+    pub error: Option<(String, String)>,
+}
+
+#[derive(PartialEq, Clone, Default)]
+pub(crate) struct SearchProgress {
+    pub searched_entries: usize,
+    pub total_entries: usize,
+    /// Entries with matches found so far.
+    pub found_entries: usize,
+}
+
+/// The line being searched when the text is split into chunks.
+#[derive(Default)]
+struct LineState {
+    /// Number of the finished lines.
+    finished: usize,
+    /// The start of the line has been searched and dropped.
+    is_cut: bool,
+    /// A match has already been found in the line.
+    is_matched: bool,
+}
+
+fn build_regex(query: &SearchQuery) -> Result<regex::Regex, (String, String)> {
+    let pattern = if query.is_regex { query.pattern.clone() } else { regex::escape(&query.pattern) };
+    regex::RegexBuilder::new(&pattern)
+        .case_insensitive(!query.match_case)
+        .build()
+        .map_err(|err| ("Invalid regular expression.".to_string(), format!("{}", err)))
+}
+
+/// Cuts a long line around the match, keeping whole characters.
+fn line_match(number: usize, line: &str, range: std::ops::Range<usize>) -> LineMatch {
+    let before = &line[..range.start];
+    let after = &line[range.end..];
+    let skip = before.chars().count().saturating_sub(CONTEXT_CHARS);
+    let mut before = before.chars().skip(skip).collect::<String>();
+    if skip > 0 {
+        before.insert(0, '…');
+    }
+    let mut after_cut = after.chars().take(CONTEXT_CHARS).collect::<String>();
+    if after_cut.len() < after.len() {
+        after_cut.push('…');
+    }
+    LineMatch {
+        number,
+        before,
+        matched: line[range].to_string(),
+        after: after_cut,
+        is_truncated: false,
+    }
+}
+
+/// Collects the matching lines of the text, keeping an unfinished last line in `pending`.
+///
+/// An unfinished line longer than `MAX_LINE_LENGTH` is searched and dropped,
+/// so a file without line breaks does not have to fit in memory.
+fn search_lines(
+    regex: &regex::Regex,
+    pending: &mut String,
+    is_last: bool,
+    line: &mut LineState,
+    result: &mut EntrySearchResult,
+) {
+    let mut consumed = 0;
+    for text in pending.split_inclusive('\n') {
+        let is_finished = is_last || text.ends_with('\n');
+        if !is_finished && text.len() <= MAX_LINE_LENGTH {
+            break;
+        }
+        consumed += text.len();
+        let text = text.trim_end_matches(['\n', '\r']);
+        if !line.is_matched {
+            if let Some(found) = regex.find(text) {
+                line.is_matched = true;
+                result.total_lines += 1;
+                if result.lines.len() < MAX_LINES_PER_ENTRY {
+                    result.lines.push(LineMatch {
+                        is_truncated: line.is_cut || !is_finished,
+                        ..line_match(line.finished + 1, text, found.range())
+                    });
+                }
+            }
+        }
+        if is_finished {
+            *line = LineState { finished: line.finished + 1, ..LineState::default() };
+        } else {
+            line.is_cut = true;
+        }
+    }
+    pending.drain(..consumed);
+}
+
+/// Searches the decompressed data of the entry chunk by chunk, so only one chunk
+/// and one line are held in memory. Binary entries are skipped.
+///
+/// Lets the page repaint after each chunk and stops early when `cancelled` is set.
+async fn search_contents(
+    archive: &OpenedArchive,
+    entry: &InfoAboutArchiveEntry,
+    password: &str,
+    regex: &regex::Regex,
+    cancelled: &std::cell::Cell<bool>,
+    result: &mut EntrySearchResult,
+) -> Result<(), (String, String)> {
+    let mut zip = archive.zip();
+    let mut file = entry.open(&mut zip, password)?;
+    let mut chunk = vec![0; CHUNK_SIZE];
+    let mut decoder = None;
+    let mut pending = String::new();
+    let mut line = LineState::default();
+    loop {
+        // A short read does not mean the end of the entry, only zero does.
+        let mut filled = 0;
+        while filled < chunk.len() {
+            let read = file.read(&mut chunk[filled..]).map_err(|err| entry.describe_read_error(err))?;
+            if read == 0 { break; }
+            filled += read;
+        }
+        let is_last = filled < chunk.len();
+        let decoder = match &mut decoder {
+            Some(decoder) => decoder,
+            None => {
+                let Some(encoding) = crate::share::text_tools::detect_encoding(&chunk[..filled], is_last) else {
+                    return Ok(());
+                };
+                decoder.insert(encoding.new_decoder_with_bom_removal())
+            }
+        };
+        let mut input = &chunk[..filled];
+        loop {
+            pending.reserve(decoder.max_utf8_buffer_length(input.len()).unwrap_or(input.len() * 3));
+            let (res, read, _) = decoder.decode_to_string(input, &mut pending, is_last);
+            input = &input[read..];
+            if res == encoding_rs::CoderResult::InputEmpty {
+                break;
+            }
+        }
+        search_lines(regex, &mut pending, is_last, &mut line, result);
+        if is_last || cancelled.get() {
+            return Ok(());
+        }
+        crate::share::fs_tools::yield_to_browser().await;
+    }
+}
+
+/// Searches entry names and, if requested, the contents of text entries.
+///
+/// Reports progress and lets the page repaint while searching. When `cancelled`
+/// is set the search stops and returns the entries found so far.
+/// Returns only the entries with matches or errors.
+pub(crate) async fn search_archive(
+    archive: std::rc::Rc<OpenedArchive>,
+    password: String,
+    query: SearchQuery,
+    cancelled: std::rc::Rc<std::cell::Cell<bool>>,
+    on_progress: yew::Callback<SearchProgress>,
+) -> Result<Vec<EntrySearchResult>, (String, String)> {
+    let regex = build_regex(&query)?;
+    let mut results = vec![];
+    let mut progress = SearchProgress { total_entries: archive.entries.len(), ..SearchProgress::default() };
+    for entry in &archive.entries {
+        if cancelled.get() {
+            break;
+        }
+        let mut result = EntrySearchResult {
+            entry: entry.clone(),
+            name_matches: regex.is_match(&entry.entry_name),
+            lines: vec![],
+            total_lines: 0,
+            error: None,
+        };
+        if query.in_contents && !entry.is_dir {
+            result.error = search_contents(&archive, entry, &password, &regex, &cancelled, &mut result).await.err();
+        }
+        if result.name_matches || result.total_lines > 0 || result.error.is_some() {
+            results.push(result);
+        }
+        progress.searched_entries += 1;
+        progress.found_entries = results.len();
+        if progress.searched_entries % ENTRIES_PER_YIELD == 0 {
+            on_progress.emit(progress.clone());
+            crate::share::fs_tools::yield_to_browser().await;
+        }
+    }
+    Ok(results)
+}
@@ -0,0 +1,276 @@
+use yew::TargetCast;
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::search_archive::model::{self, EntrySearchResult, SearchProgress, SearchQuery};
+
+pub enum SearchArchiveMsg {
+    EditPattern(String),
+    ToggleRegex,
+    ToggleMatchCase,
+    ToggleContents,
+    Search,
+    Progress(SearchProgress),
+    /// The search has finished, with the cancel flag of the search it belongs to.
+    Finished(std::rc::Rc<std::cell::Cell<bool>>, Result<Vec<EntrySearchResult>, (String, String)>),
+    /// Stop the search and show the entries found so far.
+    Cancel,
+    HideResults,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct SearchArchiveProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    /// Password entered for the archive.
+    pub password: yew::AttrValue,
+}
+
+/// Component - searches entry names and the contents of text entries.
+pub struct SearchArchiveComponent {
+    query: SearchQuery,
+    /// Cancel flag and progress of the running search.
+    search: Option<(std::rc::Rc<std::cell::Cell<bool>>, SearchProgress)>,
+    results: Option<Result<Vec<EntrySearchResult>, (String, String)>>,
+    /// The results are the entries found before the search was cancelled.
+    is_cancelled: bool,
+}
+
+impl SearchArchiveComponent {
+    /// Stops the running search, its results will be ignored.
+    fn stop_search(&mut self) {
+        if let Some((cancelled, _)) = self.search.take() {
+            cancelled.set(true);
+        }
+    }
+
+    fn view_progress(ctx: &yew::Context<Self>, progress: &SearchProgress) -> yew::Html {
+        let percent = (100 * progress.searched_entries).checked_div(progress.total_entries).unwrap_or(0);
+        let cancel_onclick = &ctx.link().callback(|_| SearchArchiveMsg::Cancel);
+        yew::html! {
+            <div class={yew::classes!("d-flex", "align-items-center", "gap-2", "my-1")}>
+                <small class={yew::classes!("text-nowrap")}>
+                    {format!("Searched {} of {} entries, found in {}.", progress.searched_entries, progress.total_entries, progress.found_entries)}
+                </small>
+                <div class={yew::classes!("progress", "flex-grow-1")} role="progressbar" aria-label="Search progress">
+                    <div class={yew::classes!("progress-bar")} style={format!("width: {percent}%")}>
+                        {format!("{percent}%")}
+                    </div>
+                </div>
+                <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={cancel_onclick}>
+                    {"Cancel"}
+                </button>
+            </div>
+        }
+    }
+
+    fn view_result(result: &EntrySearchResult) -> yew::Html {
+        yew::html! {
+            <div class={yew::classes!("list-group-item", "p-1")} key={result.entry.ind}>
+                <div class={yew::classes!("d-flex", "w-100", "justify-content-between", "align-items-center")}>
+                    <small class={yew::classes!("text-truncate", if result.name_matches { "fw-bold" } else { "" })}>
+                        {&result.entry.entry_name}
+                    </small>
+                    if result.total_lines > 0 {
+                        <span class={yew::classes!("badge", "bg-secondary")}>{format!("{} lines", result.total_lines)}</span>
+                    }
+                </div>
+                { result.lines.iter().map(|line| yew::html! {
+                    <small class={yew::classes!("d-block", "font-monospace", "text-break")}>
+                        <span class={yew::classes!("text-muted")}>{format!("{}: ", line.number)}</span>
+                        {&line.before}<mark>{&line.matched}</mark>{&line.after}
+                        if line.is_truncated {
+                            <span class={yew::classes!("badge", "bg-light", "text-dark", "ms-1")}
+                                title="The line is too long to keep in memory: it was searched in parts and only the part with the match is shown."
+                            >{"truncated line"}</span>
+                        }
+                    </small>
+                }).collect::<yew::Html>() }
+                if result.total_lines > result.lines.len() {
+                    <small class={yew::classes!("d-block", "text-muted")}>
+                        {format!("{} more lines.", result.total_lines - result.lines.len())}
+                    </small>
+                }
+                if let Some((msg, detail)) = &result.error {
+                    <small
+                        class={yew::classes!("d-block", "text-danger")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.clone()}
+                    >{msg}</small>
+                }
+            </div>
+        }
+    }
+}
+
+impl yew::Component for SearchArchiveComponent {
+    type Message = SearchArchiveMsg;
+    type Properties = SearchArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            query: SearchQuery { in_contents: true, ..SearchQuery::default() },
+            search: None,
+            results: None,
+            is_cancelled: false,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            SearchArchiveMsg::EditPattern(pattern) => {
+                self.query.pattern = pattern;
+                false
+            }
+            SearchArchiveMsg::ToggleRegex => {
+                self.query.is_regex = !self.query.is_regex;
+                true
+            }
+            SearchArchiveMsg::ToggleMatchCase => {
+                self.query.match_case = !self.query.match_case;
+                true
+            }
+            SearchArchiveMsg::ToggleContents => {
+                self.query.in_contents = !self.query.in_contents;
+                true
+            }
+            SearchArchiveMsg::Search => {
+                if self.query.pattern.is_empty() || self.search.is_some() {
+                    return false;
+                }
+                let archive = ctx.props().archive.clone();
+                let password = ctx.props().password.to_string();
+                let query = self.query.clone();
+                let cancelled = std::rc::Rc::new(std::cell::Cell::new(false));
+                let on_progress = ctx.link().callback(SearchArchiveMsg::Progress);
+                let on_finished = ctx.link().callback({
+                    let cancelled = cancelled.clone();
+                    move |res| SearchArchiveMsg::Finished(cancelled.clone(), res)
+                });
+                let progress = SearchProgress { total_entries: archive.entries.len(), ..SearchProgress::default() };
+                self.search = Some((cancelled.clone(), progress));
+                self.results = None;
+                wasm_bindgen_futures::spawn_local(async move {
+                    on_finished.emit(model::search_archive(archive, password, query, cancelled, on_progress).await);
+                });
+                true
+            }
+            SearchArchiveMsg::Progress(progress) => {
+                let Some((_, current)) = &mut self.search else { return false; };
+                *current = progress;
+                true
+            }
+            SearchArchiveMsg::Finished(cancelled, res) => {
+                // Results of a search stopped by a change of the archive are stale.
+                if !self.search.as_ref().is_some_and(|(current, _)| std::rc::Rc::ptr_eq(current, &cancelled)) {
+                    return false;
+                }
+                self.search = None;
+                self.is_cancelled = cancelled.get();
+                self.results = Some(res);
+                true
+            }
+            SearchArchiveMsg::Cancel => {
+                let Some((cancelled, _)) = &self.search else { return false; };
+                cancelled.set(true);
+                false
+            }
+            SearchArchiveMsg::HideResults => {
+                self.results = None;
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &yew::Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.stop_search();
+        self.results = None;
+        true
+    }
+
+    fn destroy(&mut self, _ctx: &yew::Context<Self>) {
+        self.stop_search();
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let oninput = &ctx.link().callback(|e: web_sys::InputEvent| {
+            let input: web_sys::HtmlInputElement = e.target_unchecked_into();
+            SearchArchiveMsg::EditPattern(input.value())
+        });
+        let onkeypress = &ctx.link().batch_callback(|e: web_sys::KeyboardEvent| {
+            (e.key() == "Enter").then_some(SearchArchiveMsg::Search)
+        });
+        let search_onclick = &ctx.link().callback(|_| SearchArchiveMsg::Search);
+        let regex_onchange = &ctx.link().callback(|_| SearchArchiveMsg::ToggleRegex);
+        let case_onchange = &ctx.link().callback(|_| SearchArchiveMsg::ToggleMatchCase);
+        let contents_onchange = &ctx.link().callback(|_| SearchArchiveMsg::ToggleContents);
+        let hide_onclick = &ctx.link().callback(|_| SearchArchiveMsg::HideResults);
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                <div class={yew::classes!("input-group", "input-group-sm")}>
+                    <input
+                        class={yew::classes!("form-control")}
+                        type="search"
+                        placeholder="Search names and contents" aria-label="Search names and contents"
+                        value={self.query.pattern.clone()}
+                        {oninput}
+                        {onkeypress}
+                    />
+                    <div class={yew::classes!("input-group-text", "gap-2")}>
+                        <label class={yew::classes!("form-check-label")}>
+                            <input class={yew::classes!("form-check-input", "me-1")} type="checkbox"
+                                checked={self.query.is_regex} onchange={regex_onchange} />
+                            {"Regex"}
+                        </label>
+                        <label class={yew::classes!("form-check-label")}>
+                            <input class={yew::classes!("form-check-input", "me-1")} type="checkbox"
+                                checked={self.query.match_case} onchange={case_onchange} />
+                            {"Match case"}
+                        </label>
+                        <label class={yew::classes!("form-check-label")} title="Decompress text entries and search their lines.">
+                            <input class={yew::classes!("form-check-input", "me-1")} type="checkbox"
+                                checked={self.query.in_contents} onchange={contents_onchange} />
+                            {"Contents"}
+                        </label>
+                    </div>
+                    <button type="button" class={yew::classes!("btn", "btn-outline-dark")}
+                        disabled={self.search.is_some()}
+                        onclick={search_onclick}
+                    >
+                        {"Search"}
+                    </button>
+                </div>
+                if let Some((_, progress)) = &self.search {
+                    { Self::view_progress(ctx, progress) }
+                }
+                { match &self.results {
+                    None => yew::html! {},
+                    Some(Ok(results)) => yew::html! {
+                        <>
+                            <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center", "my-1")}>
+                                <span>
+                                    {format!("Found in {} entries.", results.len())}
+                                    if self.is_cancelled {
+                                        <small class={yew::classes!("text-muted", "ms-1")}>{"The search was cancelled."}</small>
+                                    }
+                                </span>
+                                <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={hide_onclick}>
+                                    {"Hide"}
+                                </button>
+                            </div>
+                            <div class={yew::classes!("list-group", "overflow-auto")} style="max-height: 40vh;">
+                                { results.iter().map(Self::view_result).collect::<yew::Html>() }
+                            </div>
+                        </>
+                    },
+                    Some(Err((msg, detail))) => yew::html! {
+                        <div
+                            class={yew::classes!("alert", "alert-danger", "mb-0", "mt-1")}
+                            data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                            title={detail.clone()}
+                            onclick={hide_onclick}
+                        >{format!("Error: {msg}")}
+                        </div>
+                    },
+                } }
+            </div>
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Проверка целостности всех элементов архива.
+
+mod ui;
+mod model;
+
+pub use ui::TestArchiveComponent;
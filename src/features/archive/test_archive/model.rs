@@ -0,0 +1,103 @@
+use crate::entities::archive::model::{ArchiveReader, InfoAboutArchiveEntry, OpenedArchive};
+
+/// Result of testing one entry.
+#[derive(PartialEq, Clone)]
+pub(crate) enum EntryTestResult {
+    /// The data was read and all checks passed.
+    Passed,
+    /// The entry was not tested.
+    Skipped(String),
+    /// The entry is damaged.
+    Failed {
+        title: String,
+        detail: String,
+        /// Region of the archive that contains the damage.
+        region: std::ops::Range<u64>,
+    },
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct EntryTestReport {
+    pub entry: InfoAboutArchiveEntry,
+    pub result: EntryTestResult,
+}
+
+/// Reader that shares its current position in the archive.
+///
+/// When decompression fails, the position shows how far the archive was read.
+struct TrackingReader {
+    inner: ArchiveReader,
+    position: std::rc::Rc<std::cell::Cell<u64>>,
+}
+
+impl std::io::Read for TrackingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.position.set(self.inner.position());
+        Ok(read)
+    }
+}
+
+impl std::io::Seek for TrackingReader {
+    fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+        let position = self.inner.seek(pos)?;
+        self.position.set(position);
+        Ok(position)
+    }
+}
+
+/// Reads every entry and checks its CRC32 and AES authentication code, like `unzip -t`.
+pub(crate) fn test_archive(
+    archive: &OpenedArchive,
+    password: &str,
+) -> Result<Vec<EntryTestReport>, (String, String)> {
+    let position = std::rc::Rc::new(std::cell::Cell::new(0));
+    let reader = TrackingReader {
+        inner: archive.zip().into_inner(),
+        position: position.clone(),
+    };
+    let mut zip = zip::ZipArchive::new(reader).map_err(|err| (
+        format!("Failed to open archive {}.", archive.file_name),
+        format!("{}", err)
+    ))?;
+
+    Ok(archive.entries.iter().map(|entry| {
+        let result = if entry.is_dir {
+            EntryTestResult::Passed
+        } else if entry.encryption.is_encrypted() && password.is_empty() {
+            EntryTestResult::Skipped(format!("Password required ({}).", entry.encryption))
+        } else {
+            test_entry(&mut zip, entry, password, &position)
+        };
+        EntryTestReport { entry: entry.clone(), result }
+    }).collect())
+}
+
+fn test_entry(
+    zip: &mut zip::ZipArchive<TrackingReader>,
+    entry: &InfoAboutArchiveEntry,
+    password: &str,
+    position: &std::cell::Cell<u64>,
+) -> EntryTestResult {
+    let mut file = match entry.open(zip, password) {
+        Ok(file) => file,
+        Err((title, detail)) => {
+            return EntryTestResult::Failed { title, detail, region: entry.header_start..entry.data_start };
+        }
+    };
+    match std::io::copy(&mut file, &mut std::io::sink()) {
+        Ok(_) => EntryTestResult::Passed,
+        Err(err) => {
+            // Checksums are verified after the last byte, so the damage can be anywhere in the data.
+            // Decompression errors are found before the current position of the buffered reader.
+            let data_end = entry.data_start + entry.compressed_size;
+            let end = if entry.is_integrity_error(&err) {
+                data_end
+            } else {
+                position.get().clamp(entry.data_start, data_end)
+            };
+            let (title, detail) = entry.describe_read_error(err);
+            EntryTestResult::Failed { title, detail, region: entry.data_start..end }
+        }
+    }
+}
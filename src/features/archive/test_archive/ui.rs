@@ -0,0 +1,128 @@
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::test_archive::model::{self, EntryTestReport, EntryTestResult};
+
+pub enum TestArchiveMsg {
+    StartTest,
+    HideReport,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct TestArchiveProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    /// Password entered for the archive.
+    pub password: yew::AttrValue,
+}
+
+/// Component - tests the integrity of every entry of an archive.
+pub struct TestArchiveComponent {
+    report: Option<Result<Vec<EntryTestReport>, (String, String)>>,
+}
+
+impl TestArchiveComponent {
+    fn view_report_row(report: &EntryTestReport) -> yew::Html {
+        let (badge_class, badge, message, detail) = match &report.result {
+            EntryTestResult::Passed => ("bg-success", "OK", String::new(), String::new()),
+            EntryTestResult::Skipped(reason) => ("bg-secondary", "SKIPPED", reason.clone(), String::new()),
+            EntryTestResult::Failed { title, detail, region } => (
+                "bg-danger",
+                "FAILED",
+                format!("{title} Damaged region: 0x{:08X}-0x{:08X}.", region.start, region.end),
+                detail.clone(),
+            ),
+        };
+        yew::html! {
+            <div class={yew::classes!("list-group-item", "p-1")} key={report.entry.ind}>
+                <div class={yew::classes!("d-flex", "w-100", "justify-content-between", "align-items-center")}>
+                    <small class={yew::classes!("text-truncate")}>{&report.entry.entry_name}</small>
+                    <span class={yew::classes!("badge", badge_class)}>{badge}</span>
+                </div>
+                if !message.is_empty() {
+                    <small
+                        class={yew::classes!("d-block", "text-muted")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail}
+                    >{message}</small>
+                }
+            </div>
+        }
+    }
+}
+
+impl yew::Component for TestArchiveComponent {
+    type Message = TestArchiveMsg;
+    type Properties = TestArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self { report: None }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            TestArchiveMsg::StartTest => {
+                self.report = Some(model::test_archive(&ctx.props().archive, &ctx.props().password));
+                true
+            }
+            TestArchiveMsg::HideReport => {
+                self.report = None;
+                true
+            }
+        }
+    }
+
+    fn changed(&mut self, _ctx: &yew::Context<Self>, _old_props: &Self::Properties) -> bool {
+        self.report = None;
+        true
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let file_name = &ctx.props().archive.file_name;
+        let Some(report) = &self.report else {
+            let onclick = &ctx.link().callback(|_| TestArchiveMsg::StartTest);
+            return yew::html! {
+                <button
+                    type="button"
+                    class={yew::classes!("btn", "btn-sm", "btn-outline-dark")}
+                    title="Read every entry and check CRC32 and AES authentication codes."
+                    {onclick}
+                >
+                    {"Test archive"}
+                </button>
+            };
+        };
+        let onclick = &ctx.link().callback(|_| TestArchiveMsg::HideReport);
+        match report {
+            Err((msg, detail)) => yew::html! {
+                <div
+                    class={yew::classes!("alert", "alert-danger", "mb-0")}
+                    data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                    title={detail.clone()}
+                    {onclick}
+                >{format!("Error: {msg}")}
+                </div>
+            },
+            Ok(reports) => {
+                let failed = reports.iter()
+                    .filter(|report| matches!(report.result, EntryTestResult::Failed { .. }))
+                    .count();
+                let summary = if failed == 0 {
+                    format!("No errors detected in compressed data of {file_name}.")
+                } else {
+                    format!("{failed} error(s) detected in {file_name}.")
+                };
+                yew::html! {
+                    <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                        <div class={yew::classes!("d-flex", "justify-content-between", "align-items-center", "mb-1")}>
+                            <span class={yew::classes!(if failed == 0 { "text-success" } else { "text-danger" })}>{summary}</span>
+                            <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} {onclick}>
+                                {"Hide"}
+                            </button>
+                        </div>
+                        <div class={yew::classes!("list-group", "overflow-auto")} style="max-height: 25vh;">
+                            { reports.iter().map(Self::view_report_row).collect::<yew::Html>() }
+                        </div>
+                    </div>
+                }
+            }
+        }
+    }
+}
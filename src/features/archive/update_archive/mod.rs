@@ -0,0 +1,6 @@
+//! Обновление архива по новому набору файлов: добавление новых и замена изменённых.
+
+mod ui;
+mod model;
+
+pub use ui::UpdateArchiveComponent;
@@ -0,0 +1,207 @@
+use crate::entities::archive::model::{InfoAboutArchiveEntry, OpenedArchive};
+use crate::features::archive::append_files::type_encryption_of;
+use crate::features::archive::merge_archives::RawArchiveWriter;
+use crate::share::zip_tools;
+
+/// ZIP stores the modification time with 2-second precision.
+const TIME_PRECISION: u64 = 2;
+
+/// How a selected file is recognized as changed.
+#[derive(PartialEq, Copy, Clone)]
+pub(crate) enum ChangeDetection {
+    /// By size and modification time, without reading the file.
+    Metadata,
+    /// By size and CRC32 of the contents.
+    Content,
+}
+
+impl From<String> for ChangeDetection {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Content" => { Self::Content }
+            _ => { Self::Metadata }
+        }
+    }
+}
+
+#[derive(PartialEq, Clone)]
+pub(crate) struct UpdateOptions {
+    pub detection: ChangeDetection,
+    /// Remove the entries that are missing in the selection.
+    pub remove_missing: bool,
+    /// Password of the encrypted entries, also used for the new ones.
+    pub password: String,
+}
+
+/// A selected file to put into the archive.
+pub(crate) struct UpdatedFile {
+    pub name: String,
+    pub size: u64,
+    /// Modification time in seconds since the Unix epoch.
+    pub modified: u64,
+    /// Contents of the file, `None` if it was not read because it is unchanged.
+    pub data: Option<Vec<u8>>,
+}
+
+#[derive(PartialEq, Copy, Clone)]
+pub(crate) enum UpdateStatus {
+    Added,
+    Replaced,
+    Removed,
+}
+
+#[derive(PartialEq, Clone, Default)]
+pub(crate) struct UpdateReport {
+    /// Changed entries with what happened to them, in the order of the new archive.
+    pub changes: Vec<(String, UpdateStatus)>,
+    pub unchanged: usize,
+}
+
+/// Whether the entry is inside a folder. The selection has no folders,
+/// so such entries never match a selected file.
+pub(crate) fn is_in_folder(entry: &InfoAboutArchiveEntry) -> bool {
+    entry.entry_name.trim_end_matches('/').contains('/')
+}
+
+/// Entries of the selected files, in the order of the files.
+///
+/// Files are matched with the files at the root of the archive by name, then by
+/// the `{ind}-{name}` name. Each entry is matched with one file at most.
+fn match_entries<'a>(archive: &'a OpenedArchive, files: &[UpdatedFile]) -> Vec<Option<&'a InfoAboutArchiveEntry>> {
+    let candidates: Vec<&InfoAboutArchiveEntry> = archive.entries.iter()
+        .filter(|entry| !entry.is_dir && !is_in_folder(entry))
+        .collect();
+    let entry_names: Vec<&str> = candidates.iter().map(|entry| entry.entry_name.as_str()).collect();
+    let file_names: Vec<&str> = files.iter().map(|file| file.name.as_str()).collect();
+    crate::share::match_names(&entry_names, &file_names).into_iter()
+        .map(|ind| ind.map(|ind| candidates[ind]))
+        .collect()
+}
+
+/// Indexes of the files whose contents must be read to update the archive.
+///
+/// With `ChangeDetection::Metadata` files with the size and modification time
+/// of their entry are unchanged and are not read.
+pub(crate) fn files_to_read(archive: &OpenedArchive, files: &[UpdatedFile], detection: ChangeDetection) -> Vec<usize> {
+    let mut zip = archive.zip();
+    files.iter().zip(match_entries(archive, files)).enumerate()
+        .filter(|(_, (file, entry))| {
+            let Some(entry) = entry else { return true; };
+            if detection == ChangeDetection::Content {
+                return true;
+            }
+            let modified = zip.by_index_raw(entry.ind).ok()
+                .and_then(|file| zip_tools::unix_time(file.last_modified()));
+            let same_metadata = entry.raw_size == file.size
+                && modified.is_some_and(|modified| modified.abs_diff(file.modified) < TIME_PRECISION);
+            !same_metadata
+        })
+        .map(|(ind, _)| ind)
+        .collect()
+}
+
+/// Whether the contents of the file are the same as of the entry.
+fn same_contents(
+    archive: &OpenedArchive,
+    entry: &InfoAboutArchiveEntry,
+    data: &[u8],
+    password: &str,
+) -> Result<bool, (String, String)> {
+    if entry.raw_size != data.len() as u64 {
+        return Ok(false);
+    }
+    if entry.crc32 == 0 && entry.raw_size > 0 && entry.encryption.is_encrypted() {
+        // AES entries of version 2 do not store the CRC32, so the entry is decrypted to compute it.
+        let entry_data = archive.read_entry(entry, password)?;
+        return Ok(entry_data == data);
+    }
+    Ok(entry.crc32 == crc32fast::hash(data))
+}
+
+/// Writes the new and changed files into a temporary archive.
+fn write_files(
+    archive: &OpenedArchive,
+    files: &[&UpdatedFile],
+    password: &str,
+) -> Result<OpenedArchive, (String, String)> {
+    let mut zip_writer = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let options = zip_tools::file_options(password, type_encryption_of(archive));
+    for file in files {
+        let mut file_options = options.large_file(file.size >= u32::MAX as u64);
+        if let Some(modified) = zip_tools::zip_date_time(file.modified) {
+            file_options = file_options.last_modified_time(modified);
+        }
+        zip_writer.start_file(file.name.as_str(), file_options).map_err(|err| (
+            format!("Failed to add file meta information to archive: {}.", file.name),
+            format!("{}", err)
+        ))?;
+        std::io::Write::write_all(&mut zip_writer, file.data.as_deref().unwrap_or_default()).map_err(|err| (
+            format!("Error writing file to archive: {}.", file.name),
+            format!("{}", err)
+        ))?;
+    }
+    let data = zip_writer.finish()
+        .map_err(|err| ("Failed to create archive.".to_string(), format!("{}", err)))?
+        .into_inner();
+    OpenedArchive::from_bytes(yew::AttrValue::default(), data)
+}
+
+/// Writes a copy of the archive with the new and changed files.
+///
+/// Unchanged entries are copied raw, changed ones are replaced in place under their
+/// name and new files are added at the end. Directories and entries inside them are
+/// always kept.
+pub(crate) fn update_archive(
+    archive: &OpenedArchive,
+    files: &[UpdatedFile],
+    options: &UpdateOptions,
+) -> Result<(Vec<u8>, UpdateReport), (String, String)> {
+    let mut changed = std::collections::HashMap::new();
+    let mut matched = std::collections::HashSet::new();
+    let mut added = vec![];
+    for (file, entry) in files.iter().zip(match_entries(archive, files)) {
+        if let Some(entry) = entry {
+            matched.insert(entry.ind);
+        }
+        let Some(data) = &file.data else { continue; };
+        match entry {
+            Some(entry) if options.detection == ChangeDetection::Content
+                && same_contents(archive, entry, data, &options.password)? => {}
+            Some(entry) => { changed.insert(entry.ind, file); }
+            None => added.push(file),
+        }
+    }
+
+    let to_write: Vec<&UpdatedFile> = archive.entries.iter()
+        .filter_map(|entry| changed.get(&entry.ind).copied())
+        .chain(added.iter().copied())
+        .collect();
+    let written = write_files(archive, &to_write, &options.password)?;
+    let mut written_entries = written.entries.iter();
+    let mut next_written = |name: &str| written_entries.next().ok_or((
+        format!("Failed to write file: {}.", name),
+        "The entry is missing in the temporary archive.".to_string(),
+    ));
+
+    let mut writer = RawArchiveWriter::with_capacity(archive.data().len());
+    let mut report = UpdateReport::default();
+    for entry in &archive.entries {
+        let name = entry.entry_name.as_str();
+        if entry.is_dir {
+            writer.copy_entry(archive, entry, name)?;
+        } else if changed.contains_key(&entry.ind) {
+            writer.copy_entry(&written, next_written(name)?, name)?;
+            report.changes.push((name.to_string(), UpdateStatus::Replaced));
+        } else if options.remove_missing && !is_in_folder(entry) && !matched.contains(&entry.ind) {
+            report.changes.push((name.to_string(), UpdateStatus::Removed));
+        } else {
+            writer.copy_entry(archive, entry, name)?;
+            report.unchanged += 1;
+        }
+    }
+    for file in added {
+        writer.copy_entry(&written, next_written(&file.name)?, &file.name)?;
+        report.changes.push((file.name.clone(), UpdateStatus::Added));
+    }
+    Ok((writer.finish(&archive.comment)?, report))
+}
@@ -0,0 +1,300 @@
+use yew::TargetCast;
+use crate::entities::archive::model::OpenedArchive;
+use crate::entities::file::model::InfoAboutSelectedFile;
+use crate::features::archive::update_archive::model::{self, ChangeDetection, UpdateOptions, UpdateReport, UpdateStatus, UpdatedFile};
+
+/// State of the updating process.
+#[derive(PartialEq)]
+enum UpdatingState {
+    /// Waiting for the user to start the update.
+    WaitConfirm,
+    /// Changed files are being read and written to the archive.
+    InProcess,
+    Done,
+    Fail,
+}
+
+pub enum UpdateArchiveMsg {
+    ChangeDetection(String),
+    ToggleRemoveMissing,
+    StartUpdate,
+    /// The file has been read from disk.
+    LoadedFile(usize, Vec<u8>),
+    FailedUpdate((String, String)),
+    Reset,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct UpdateArchiveProps {
+    pub archive: std::rc::Rc<OpenedArchive>,
+    /// Password of the encrypted entries, also used for the new ones.
+    pub password: yew::AttrValue,
+    /// Files currently selected for a new archive.
+    pub selected_files: Vec<std::rc::Rc<InfoAboutSelectedFile>>,
+    /// Callback with the updated archive.
+    pub on_archive_updated: yew::Callback<OpenedArchive>,
+}
+
+/// Component - updates the archive from the selected files, replacing only new and changed ones.
+pub struct UpdateArchiveComponent {
+    state: UpdatingState,
+    detection: ChangeDetection,
+    remove_missing: bool,
+    files: Vec<UpdatedFile>,
+    /// Indexes of the files that are read from disk.
+    to_read: Vec<usize>,
+    file_reading_tasks: std::collections::HashMap<usize, gloo_file::callbacks::FileReader>,
+    report: UpdateReport,
+    err_msg: (String, String),
+    blob_result: Option<gloo_file::Blob>,
+}
+
+impl UpdateArchiveComponent {
+    fn get_obj_url_with_result(&self) -> Option<String> {
+        let blob = self.blob_result.as_ref()?;
+        let download_url = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok()?;
+        Some(download_url)
+    }
+
+    fn loaded_count(&self) -> usize {
+        self.to_read.len() - self.file_reading_tasks.len()
+    }
+
+    fn finish(&mut self, ctx: &yew::Context<Self>) {
+        let props = ctx.props();
+        let options = UpdateOptions {
+            detection: self.detection,
+            remove_missing: self.remove_missing,
+            password: props.password.to_string(),
+        };
+        let res = model::update_archive(&props.archive, &self.files, &options)
+            .and_then(|(data, report)| {
+                let blob = gloo_file::Blob::new(data.as_slice());
+                OpenedArchive::from_bytes(props.archive.file_name.clone(), data).map(|archive| (blob, archive, report))
+            });
+        self.files = vec![];
+        match res {
+            Ok((blob, archive, report)) => {
+                self.state = UpdatingState::Done;
+                self.report = report;
+                self.blob_result = Some(blob);
+                props.on_archive_updated.emit(archive);
+            }
+            Err(err) => {
+                ctx.link().send_message(UpdateArchiveMsg::FailedUpdate(err));
+            }
+        }
+    }
+
+    fn view_report(&self) -> yew::Html {
+        let count = |status: UpdateStatus| self.report.changes.iter().filter(|(_, s)| *s == status).count();
+        yew::html! {
+            <>
+                <small class={yew::classes!("mb-1")}>
+                    {format!(
+                        "Added: {}, replaced: {}, removed: {}, unchanged: {}.",
+                        count(UpdateStatus::Added),
+                        count(UpdateStatus::Replaced),
+                        count(UpdateStatus::Removed),
+                        self.report.unchanged,
+                    )}
+                </small>
+                if !self.report.changes.is_empty() {
+                    <ul class={yew::classes!("list-unstyled", "small", "mb-1")} style="max-height: 200px; overflow-y: auto;">
+                        {for self.report.changes.iter().map(|(name, status)| {
+                            let (badge, label) = match status {
+                                UpdateStatus::Added => ("bg-success", "Added"),
+                                UpdateStatus::Replaced => ("bg-warning", "Replaced"),
+                                UpdateStatus::Removed => ("bg-danger", "Removed"),
+                            };
+                            yew::html! {
+                                <li class={yew::classes!("text-break")}>
+                                    <span class={yew::classes!("badge", badge, "me-2")}>{label}</span>
+                                    {name}
+                                </li>
+                            }
+                        })}
+                    </ul>
+                }
+            </>
+        }
+    }
+}
+
+impl yew::Component for UpdateArchiveComponent {
+    type Message = UpdateArchiveMsg;
+    type Properties = UpdateArchiveProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self {
+            state: UpdatingState::WaitConfirm,
+            detection: ChangeDetection::Metadata,
+            remove_missing: false,
+            files: vec![],
+            to_read: vec![],
+            file_reading_tasks: std::collections::HashMap::new(),
+            report: UpdateReport::default(),
+            err_msg: ("".to_string(), "".to_string()),
+            blob_result: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            UpdateArchiveMsg::ChangeDetection(value) => {
+                self.detection = ChangeDetection::from(value);
+                false
+            }
+            UpdateArchiveMsg::ToggleRemoveMissing => {
+                self.remove_missing = !self.remove_missing;
+                true
+            }
+            UpdateArchiveMsg::StartUpdate => {
+                let props = ctx.props();
+                self.state = UpdatingState::InProcess;
+                self.files = props.selected_files.iter().map(|file| UpdatedFile {
+                    name: file.file_name.to_string(),
+                    size: file.raw_size,
+                    modified: file.js_file_obj.last_modified_time()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .map(|duration| duration.as_secs())
+                        .unwrap_or_default(),
+                    data: None,
+                }).collect();
+                self.to_read = model::files_to_read(&props.archive, &self.files, self.detection);
+                if self.to_read.is_empty() {
+                    self.finish(ctx);
+                    return true;
+                }
+                let callback_loaded_file = ctx.link()
+                    .callback(|(ind, _, data)| UpdateArchiveMsg::LoadedFile(ind, data));
+                let callback_failed = ctx.link().callback(UpdateArchiveMsg::FailedUpdate);
+                for &ind in &self.to_read {
+                    let file = &props.selected_files[ind];
+                    let task = crate::share::fs_tools::read_file(
+                        ind,
+                        file.file_name.clone(),
+                        &file.js_file_obj,
+                        callback_loaded_file.clone(),
+                        callback_failed.clone(),
+                    );
+                    self.file_reading_tasks.insert(ind, task);
+                }
+                true
+            }
+            UpdateArchiveMsg::LoadedFile(ind, data) => {
+                if self.state != UpdatingState::InProcess { return false; }
+                self.file_reading_tasks.remove(&ind);
+                if let Some(file) = self.files.get_mut(ind) {
+                    file.data = Some(data);
+                }
+                if self.file_reading_tasks.is_empty() {
+                    self.finish(ctx);
+                }
+                true
+            }
+            UpdateArchiveMsg::FailedUpdate(err) => {
+                self.state = UpdatingState::Fail;
+                self.err_msg = err;
+                self.files = vec![];
+                self.file_reading_tasks = std::collections::HashMap::new();
+                true
+            }
+            UpdateArchiveMsg::Reset => {
+                self.state = UpdatingState::WaitConfirm;
+                self.report = UpdateReport::default();
+                self.blob_result = None;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let reset_onclick = &ctx.link().callback(|_| UpdateArchiveMsg::Reset);
+        match self.state {
+            UpdatingState::WaitConfirm => {
+                let selected_count = ctx.props().selected_files.len();
+                let in_folders = ctx.props().archive.entries.iter()
+                    .filter(|entry| !entry.is_dir && model::is_in_folder(entry))
+                    .count();
+                let onchange_detection = &ctx.link().callback(move |e: web_sys::Event| {
+                    let select: web_sys::HtmlInputElement = e.target_unchecked_into();
+                    UpdateArchiveMsg::ChangeDetection(select.value())
+                });
+                let remove_onchange = &ctx.link().callback(|_| UpdateArchiveMsg::ToggleRemoveMissing);
+                let start_onclick = &ctx.link().callback(|_| UpdateArchiveMsg::StartUpdate);
+                yew::html! {
+                    <div class={yew::classes!("d-flex", "flex-wrap", "align-items-center", "gap-2")}>
+                        <div class={yew::classes!("input-group", "input-group-sm", "w-auto")}>
+                            <span class={yew::classes!("input-group-text")}>{"Changed files by"}</span>
+                            <select class={yew::classes!("form-select")} onchange={onchange_detection}>
+                                <option selected={self.detection == ChangeDetection::Metadata} value={"Metadata"}>{"Size and time"}</option>
+                                <option selected={self.detection == ChangeDetection::Content} value={"Content"}>{"Contents (CRC32)"}</option>
+                            </select>
+                        </div>
+                        <div class={yew::classes!("form-check", "mb-0")}>
+                            <input class={yew::classes!("form-check-input")} type="checkbox" id="update-remove-missing"
+                                checked={self.remove_missing} onchange={remove_onchange} />
+                            <label class={yew::classes!("form-check-label")} for="update-remove-missing">
+                                {"Remove entries missing in the selection"}
+                            </label>
+                        </div>
+                        if self.remove_missing && in_folders > 0 {
+                            <small class={yew::classes!("text-warning")}>
+                                {format!("{} entries inside folders are kept: selected files have no folders.", in_folders)}
+                            </small>
+                        }
+                        <button
+                            type="button"
+                            class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "ms-auto")}
+                            title="Add new and replace changed files from the selection. Unchanged entries are copied without recompression."
+                            disabled={selected_count == 0}
+                            onclick={start_onclick}
+                        >
+                            {format!("Update from {} selected file(s)", selected_count)}
+                        </button>
+                    </div>
+                }
+            }
+            UpdatingState::InProcess => {
+                yew::html! {
+                    <p class={yew::classes!("mb-0")}>{format!("Reading changed files: {}/{}", self.loaded_count(), self.to_read.len())}</p>
+                }
+            }
+            UpdatingState::Done => {
+                yew::html! {
+                    <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                        {self.view_report()}
+                        <div>
+                            if let Some(href) = self.get_obj_url_with_result() {
+                                <a
+                                    class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")}
+                                    href={href}
+                                    download={ctx.props().archive.file_name.clone()}
+                                >
+                                    {"Download updated archive"}
+                                </a>
+                            }
+                            <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                                {"Back"}
+                            </button>
+                        </div>
+                    </div>
+                }
+            }
+            UpdatingState::Fail => {
+                let msg = self.err_msg.0.as_str();
+                let detail = self.err_msg.1.as_str();
+                yew::html! {
+                    <div
+                        class={yew::classes!("alert", "alert-danger", "mb-0")}
+                        data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                        title={detail.to_string()}
+                        onclick={reset_onclick}
+                    >{format!("Error: {msg}")}
+                    </div>
+                }
+            }
+        }
+    }
+}
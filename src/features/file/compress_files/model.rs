@@ -1,20 +1,5 @@
-use std::io::Write;
-use zip::unstable::write::FileOptionsExt;
-
-#[derive(PartialEq, Copy, Clone)]
-pub(crate) enum TypeEncryption {
-    ZipCrypto,
-    Aes256,
-}
-
-impl From<String> for TypeEncryption {
-    fn from(value: String) -> Self {
-        match value.as_str() {
-            "Aes256" => { Self::Aes256 }
-            _ => { Self::ZipCrypto }
-        }
-    }
-}
+use std::io::{Read, Write};
+use crate::share::zip_tools::TypeEncryption;
 
 /// State of the compression process.
 #[derive(PartialEq)]
@@ -29,21 +14,31 @@ pub(crate) enum CompressingState {
     Fail,
 }
 
+/// Size and checksum of a source file, recorded for verification.
+struct WrittenFile {
+    name: String,
+    size: usize,
+    crc32: u32,
+}
+
 pub(crate) struct CompressionFiles {
     password: String,
     type_encryption: TypeEncryption,
     zip_writer: Option<zip::ZipWriter<std::io::Cursor<Vec<u8>>>>,
+    /// Source files to check after the archive is finished, if verification is enabled.
+    written_files: Option<Vec<WrittenFile>>,
     pub state: CompressingState,
     pub need_to_wait: bool,
 }
 
 
 impl CompressionFiles {
-    pub fn new(password: String, type_encryption: TypeEncryption) -> Self {
+    pub fn new(password: String, type_encryption: TypeEncryption, verify: bool) -> Self {
         Self {
             password,
             type_encryption,
             zip_writer: Some(zip::ZipWriter::new(std::io::Cursor::new(vec![]))),
+            written_files: verify.then(Vec::new),
             state: CompressingState::WaitStart,
             need_to_wait: false,
         }
@@ -81,18 +76,7 @@ impl CompressionFiles {
             Err(("zip_writer is not defined.".to_string(), "".to_string()))
         } else {
             let mut zip_writer = std::mem::take(&mut self.zip_writer).unwrap();
-            let mut options = zip::write::SimpleFileOptions::default();
-
-            if !self.password.is_empty() {
-                options = if self.type_encryption == TypeEncryption::Aes256 {
-                    options.with_aes_encryption(
-                        zip::AesMode::Aes256,
-                        self.password.as_str(),
-                    )
-                } else {
-                    options.with_deprecated_encryption(self.password.as_bytes())
-                };
-            }
+            let options = crate::share::zip_tools::file_options(&self.password, self.type_encryption);
 
             let new_archive_filename = format!("{}-{}", ind, file_name.as_str());
 
@@ -114,6 +98,13 @@ impl CompressionFiles {
             let res = match res_write {
                 Ok(_) => {
                     // The file was successfully written.
+                    if let Some(written_files) = &mut self.written_files {
+                        written_files.push(WrittenFile {
+                            name: new_archive_filename,
+                            size: file_data.len(),
+                            crc32: crc32fast::hash(file_data),
+                        });
+                    }
                     Ok(file_data.len())
                 }
                 Err(err) => {
@@ -144,15 +135,66 @@ impl CompressionFiles {
 
         let res = match zip_writer.finish() {
             Ok(res) => {
-                self.state = CompressingState::Done;
-                Ok(res.get_ref().clone())
-            }
-            Err(err) => {
-                self.state = CompressingState::Fail;
-                Err(("Failed to create archive.".to_string(), format!("{}", err)))
+                let data = res.into_inner();
+                self.verify(&data).map(|_| data)
             }
+            Err(err) => Err(("Failed to create archive.".to_string(), format!("{}", err))),
         };
+        self.state = if res.is_ok() { CompressingState::Done } else { CompressingState::Fail };
         res
     }
+
+    /// Whether the finished archive was checked against the source files.
+    pub fn is_verified(&self) -> bool {
+        self.written_files.is_some() && self.state == CompressingState::Done
+    }
+
+    /// Re-opens the finished archive, decrypts every entry and compares it with the source files.
+    fn verify(&self, data: &[u8]) -> Result<(), (String, String)> {
+        let Some(written_files) = &self.written_files else {
+            return Ok(());
+        };
+        let mut zip = zip::ZipArchive::new(std::io::Cursor::new(data)).map_err(|err| (
+            "Verification failed: the archive cannot be opened.".to_string(),
+            format!("{}", err)
+        ))?;
+        if zip.len() != written_files.len() {
+            return Err((
+                "Verification failed: wrong number of entries.".to_string(),
+                format!("Expected {}, found {}.", written_files.len(), zip.len())
+            ));
+        }
+
+        for written_file in written_files {
+            let file = if self.password.is_empty() {
+                zip.by_name(&written_file.name)
+            } else {
+                zip.by_name_decrypt(&written_file.name, self.password.as_bytes())
+            };
+            let mut file = file.map_err(|err| (
+                format!("Verification failed: {}.", written_file.name),
+                format!("{}", err)
+            ))?;
+            let mut entry_data = Vec::with_capacity(written_file.size);
+            file.read_to_end(&mut entry_data).map_err(|err| (
+                format!("Verification failed: {}.", written_file.name),
+                format!("{}", err)
+            ))?;
+            if entry_data.len() != written_file.size {
+                return Err((
+                    format!("Verification failed: {}.", written_file.name),
+                    format!("Expected {} bytes, read {}.", written_file.size, entry_data.len())
+                ));
+            }
+            let crc32 = crc32fast::hash(&entry_data);
+            if crc32 != written_file.crc32 {
+                return Err((
+                    format!("Verification failed: {}.", written_file.name),
+                    format!("Expected CRC32 {:08x}, found {:08x}.", written_file.crc32, crc32)
+                ));
+            }
+        }
+        Ok(())
+    }
 }
 
@@ -1,7 +1,8 @@
 use yew::TargetCast;
 use crate::entities::file::model::InfoAboutSelectedFile;
 use crate::features::file::compress_files;
-use crate::features::file::compress_files::model::{CompressingState, TypeEncryption};
+use crate::features::file::compress_files::model::CompressingState;
+use crate::share::zip_tools::TypeEncryption;
 
 pub enum CompressionFilesMsg {
     StartCompression,
@@ -15,23 +16,31 @@ pub enum CompressionFilesMsg {
     /// Password from the input field.
     EditPassword(String),
     ChangeTypeEncryption(String),
+    /// Enable or disable the verification of the finished archive.
+    ToggleVerification,
 }
 
 #[derive(yew::Properties, PartialEq)]
 pub struct CompressionFilesProps {
     /// Callback when compression was successful.
     pub on_start_compress: yew::Callback<()>,
-    /// Callback with the index of the successfully compressed file.
+    /// Callback with the index in `files` of the successfully compressed file.
     pub on_add_success_compress_file: yew::Callback<usize>,
-    pub files: std::collections::HashMap<usize, std::rc::Rc<InfoAboutSelectedFile>>,
+    /// Selected files, in the order of the archive entries.
+    pub files: Vec<std::rc::Rc<InfoAboutSelectedFile>>,
 }
 
 pub struct CompressionFilesComponent {
     password: String,
     type_encryption: TypeEncryption,
+    verify: bool,
     is_blocked: bool,
     compressor: compress_files::model::CompressionFiles,
-    file_reading_tasks: std::collections::HashMap<yew::AttrValue, gloo_file::callbacks::FileReader>,
+    file_reading_tasks: std::collections::HashMap<usize, gloo_file::callbacks::FileReader>,
+    /// Files read from disk that wait for the files before them to be written.
+    loaded_files: std::collections::BTreeMap<usize, (yew::AttrValue, Vec<u8>)>,
+    /// Index of the next file to write into the archive.
+    next_file_ind: usize,
     number_of_successfully_processed_files: usize,
     total_size: usize,
     err_msg: (String, String),
@@ -53,12 +62,16 @@ impl yew::Component for CompressionFilesComponent {
     fn create(_ctx: &yew::Context<Self>) -> Self {
         let password = String::new();
         let type_encryption = TypeEncryption::Aes256;
+        let verify = true;
         Self {
             password: password.clone(),
             type_encryption,
+            verify,
             is_blocked: false,
-            compressor: compress_files::model::CompressionFiles::new(password, type_encryption),
+            compressor: compress_files::model::CompressionFiles::new(password, type_encryption, verify),
             file_reading_tasks: std::collections::HashMap::new(),
+            loaded_files: std::collections::BTreeMap::new(),
+            next_file_ind: 0,
             number_of_successfully_processed_files: 0,
             total_size: 0,
             err_msg: ("".to_string(), "".to_string()),
@@ -72,6 +85,10 @@ impl yew::Component for CompressionFilesComponent {
                 self.type_encryption = TypeEncryption::from(value);
                 true
             }
+            CompressionFilesMsg::ToggleVerification => {
+                self.verify = !self.verify;
+                true
+            }
             CompressionFilesMsg::EditPassword(edit_value) => {
                 self.password = edit_value.trim().to_string();
                 true
@@ -86,7 +103,10 @@ impl yew::Component for CompressionFilesComponent {
                 self.compressor = compress_files::model::CompressionFiles::new(
                     self.password.clone(),
                     self.type_encryption,
+                    self.verify,
                 );
+                self.loaded_files = std::collections::BTreeMap::new();
+                self.next_file_ind = 0;
 
                 let callback_loaded_file = ctx.link()
                     .callback(
@@ -95,18 +115,16 @@ impl yew::Component for CompressionFilesComponent {
                 let callback_filed = ctx.link().callback(CompressionFilesMsg::FailedCompression);
                 self.compressor.change_state_on_in_process();
 
-                for (current_ind_file, file) in ctx.props().files.iter() {
-                    let file = file.clone();
+                for (current_ind_file, file) in ctx.props().files.iter().enumerate() {
                     if self.is_blocked { break; }
-                    let file_name = file.file_name.clone();
                     let task = crate::share::fs_tools::read_file(
-                        *current_ind_file,
-                        file_name.clone(),
+                        current_ind_file,
+                        file.file_name.clone(),
                         &file.js_file_obj,
                         callback_loaded_file.clone(),
                         callback_filed.clone(),
                     );
-                    self.file_reading_tasks.insert(file_name, task);
+                    self.file_reading_tasks.insert(current_ind_file, task);
                 }
                 true
             }
@@ -120,26 +138,33 @@ impl yew::Component for CompressionFilesComponent {
                     );
                     return true;
                 }
-                match self.compressor.add_file_in_zip(
-                    current_ind_file,
-                    &file_name,
-                    &data,
-                ) {
-                    Ok(total_size) => {
-                        ctx.props().on_add_success_compress_file.emit(current_ind_file);
-                        ctx.link().send_message(
-                            CompressionFilesMsg::ProgressUpdateCompression {
-                                total_size
-                            }
-                        );
-                    }
-                    Err((title, detail)) => {
-                        ctx.link().send_message(
-                            CompressionFilesMsg::FailedCompression((title, detail))
-                        );
-                    }
-                };
-                self.file_reading_tasks.remove(&file_name);
+                self.file_reading_tasks.remove(&current_ind_file);
+                // Files are read in any order, but written in the order of the list.
+                self.loaded_files.insert(current_ind_file, (file_name, data));
+                while let Some((file_name, data)) = self.loaded_files.remove(&self.next_file_ind) {
+                    let ind_file = self.next_file_ind;
+                    self.next_file_ind += 1;
+                    match self.compressor.add_file_in_zip(
+                        ind_file,
+                        &file_name,
+                        &data,
+                    ) {
+                        Ok(total_size) => {
+                            ctx.props().on_add_success_compress_file.emit(ind_file);
+                            ctx.link().send_message(
+                                CompressionFilesMsg::ProgressUpdateCompression {
+                                    total_size
+                                }
+                            );
+                        }
+                        Err((title, detail)) => {
+                            ctx.link().send_message(
+                                CompressionFilesMsg::FailedCompression((title, detail))
+                            );
+                            break;
+                        }
+                    };
+                }
                 true
             }
             CompressionFilesMsg::ProgressUpdateCompression { total_size } => {
@@ -167,6 +192,7 @@ impl yew::Component for CompressionFilesComponent {
                 self.is_blocked = true;
                 self.err_msg = err;
                 self.file_reading_tasks = std::collections::HashMap::new();
+                self.loaded_files = std::collections::BTreeMap::new();
                 self.compressor.change_state_on_in_fail();
                 true
             }
@@ -198,6 +224,7 @@ impl yew::Component for CompressionFilesComponent {
         });
 
         let start_onclick = &ctx.link().callback(|_| CompressionFilesMsg::StartCompression);
+        let verify_onchange = &ctx.link().callback(|_| CompressionFilesMsg::ToggleVerification);
         match &self.compressor.state {
             CompressingState::WaitStart => {
                 if ctx.props().files.is_empty() {
@@ -224,6 +251,18 @@ impl yew::Component for CompressionFilesComponent {
                                 {onblur}
                                 {onkeypress}
                             />
+                            <div class={yew::classes!("input-group-text")}
+                                title="Re-open the archive and compare every entry with the source file."
+                            >
+                                <input
+                                    class={yew::classes!("form-check-input", "mt-0", "me-1")}
+                                    type="checkbox"
+                                    id="checkbox-verify"
+                                    checked={self.verify}
+                                    onchange={verify_onchange}
+                                />
+                                <label for="checkbox-verify">{"Verify"}</label>
+                            </div>
                             <button
                                 id="button-compress"
                                 type="button"
@@ -274,6 +313,9 @@ impl yew::Component for CompressionFilesComponent {
                         download={"compressed.zip"}
                     >
                         {format!("Download: {total_size}")}
+                        if self.compressor.is_verified() {
+                            <span class={yew::classes!("badge", "bg-success", "ms-2")}>{"Verified"}</span>
+                        }
                     </a>
                 }
                 } else {
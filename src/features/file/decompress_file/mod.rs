@@ -0,0 +1,6 @@
+//! Распаковка отдельных файлов, сжатых gzip, bzip2, xz или Zstandard.
+
+mod ui;
+mod model;
+
+pub use ui::DecompressFileComponent;
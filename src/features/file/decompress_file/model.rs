@@ -0,0 +1,128 @@
+use std::io::Read;
+use crate::features::archive::archive_limits::ArchiveLimits;
+use crate::share::tar_tools::TarCompression;
+use crate::share::xz_tools::XzReader;
+use crate::share::zstd_tools::ZstdReader;
+
+/// Size of the chunks of decompressed data produced between repaints of the page.
+const CHUNK_SIZE: usize = 1024 * 1024;
+
+/// Compression of a single file.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub(crate) enum StreamCompression {
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+impl StreamCompression {
+    pub const ALL: [Self; 4] = [Self::Gzip, Self::Bzip2, Self::Xz, Self::Zstd];
+
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Gzip => ".gz",
+            Self::Bzip2 => ".bz2",
+            Self::Xz => ".xz",
+            Self::Zstd => ".zst",
+        }
+    }
+
+    /// Recognizes a compressed file by the extension. Compressed tar archives are not single files.
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        if TarCompression::from_file_name(file_name).is_some() {
+            return None;
+        }
+        let file_name = file_name.to_ascii_lowercase();
+        Self::ALL.into_iter().find(|compression| file_name.ends_with(compression.extension()))
+    }
+
+    /// Name of the decompressed file: the name without the extension of the compression.
+    pub fn inner_file_name(&self, file_name: &str) -> String {
+        let stem = &file_name[..file_name.len().saturating_sub(self.extension().len())];
+        if stem.is_empty() { "decompressed".to_string() } else { stem.to_string() }
+    }
+}
+
+impl std::fmt::Display for StreamCompression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Gzip => write!(f, "gzip"),
+            Self::Bzip2 => write!(f, "bzip2"),
+            Self::Xz => write!(f, "xz"),
+            Self::Zstd => write!(f, "Zstandard"),
+        }
+    }
+}
+
+#[derive(PartialEq, Clone, Default)]
+pub(crate) struct DecompressProgress {
+    /// Compressed bytes read so far.
+    pub read_bytes: u64,
+    pub total_bytes: u64,
+    /// Size of the decompressed data so far.
+    pub written_bytes: u64,
+}
+
+/// Reader of the compressed data that counts the bytes taken by the decoder.
+struct CountingReader<'a> {
+    data: &'a [u8],
+    read_bytes: std::rc::Rc<std::cell::Cell<u64>>,
+}
+
+impl Read for CountingReader<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let len = self.data.read(buf)?;
+        self.read_bytes.set(self.read_bytes.get() + len as u64);
+        Ok(len)
+    }
+}
+
+/// Decompresses the file chunk by chunk, reporting progress and letting the page repaint after each chunk.
+///
+/// Decompression stops with an error as soon as the output exceeds the size or the ratio allowed by the limits.
+pub(crate) async fn decompress_file(
+    data: Vec<u8>,
+    compression: StreamCompression,
+    limits: ArchiveLimits,
+    on_progress: yew::Callback<DecompressProgress>,
+) -> Result<Vec<u8>, (String, String)> {
+    let read_err = |err: std::io::Error| (format!("Failed to decompress {} file.", compression), format!("{}", err));
+    let read_bytes = std::rc::Rc::new(std::cell::Cell::new(0));
+    let reader = CountingReader { data: &data, read_bytes: read_bytes.clone() };
+    let mut decoder: Box<dyn Read + '_> = match compression {
+        StreamCompression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+        StreamCompression::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+        StreamCompression::Xz => Box::new(XzReader::new(reader)),
+        StreamCompression::Zstd => Box::new(ZstdReader::new(reader)),
+    };
+
+    let mut progress = DecompressProgress { total_bytes: data.len() as u64, ..DecompressProgress::default() };
+    let mut output = Vec::with_capacity(data.len());
+    let mut chunk = vec![0; CHUNK_SIZE];
+    loop {
+        let mut len = 0;
+        while len < CHUNK_SIZE {
+            let read = decoder.read(&mut chunk[len..]).map_err(read_err)?;
+            if read == 0 {
+                break;
+            }
+            len += read;
+        }
+        output.extend_from_slice(&chunk[..len]);
+        progress.read_bytes = read_bytes.get();
+        progress.written_bytes = output.len() as u64;
+        if let Some(warning) = limits.check_stream(progress.written_bytes, progress.read_bytes) {
+            return Err((
+                format!("Decompression stopped: {}", warning),
+                "Raise the limits for opened archives to decompress the file.".to_string(),
+            ));
+        }
+        on_progress.emit(progress.clone());
+        if len < CHUNK_SIZE {
+            break;
+        }
+        crate::share::fs_tools::yield_to_browser().await;
+    }
+    Ok(output)
+}
@@ -0,0 +1,161 @@
+use crate::entities::file::model::InfoAboutSelectedFile;
+use crate::features::archive::archive_limits::ArchiveLimits;
+use crate::features::file::decompress_file::model::{self, DecompressProgress, StreamCompression};
+
+pub enum DecompressFileMsg {
+    Start,
+    /// The compressed file has been read from disk.
+    LoadedFile(Vec<u8>),
+    Progress(DecompressProgress),
+    Finished(Result<Vec<u8>, (String, String)>),
+    Reset,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct DecompressFileProps {
+    pub file: std::rc::Rc<InfoAboutSelectedFile>,
+    /// Limits of the decompressed size and of the compression ratio.
+    #[prop_or_default]
+    pub limits: ArchiveLimits,
+}
+
+enum DecompressState {
+    Idle,
+    Reading,
+    InProcess(DecompressProgress),
+    Done(gloo_file::Blob),
+    Fail((String, String)),
+}
+
+/// Component - decompresses a selected gzip, bzip2, xz or Zstandard file and offers the inner file for download.
+/// Shows nothing for other files.
+pub struct DecompressFileComponent {
+    state: DecompressState,
+    file_reading_task: Option<gloo_file::callbacks::FileReader>,
+}
+
+impl DecompressFileComponent {
+    fn compression(ctx: &yew::Context<Self>) -> Option<StreamCompression> {
+        StreamCompression::from_file_name(&ctx.props().file.file_name)
+    }
+}
+
+impl yew::Component for DecompressFileComponent {
+    type Message = DecompressFileMsg;
+    type Properties = DecompressFileProps;
+
+    fn create(_ctx: &yew::Context<Self>) -> Self {
+        Self { state: DecompressState::Idle, file_reading_task: None }
+    }
+
+    fn update(&mut self, ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            DecompressFileMsg::Start => {
+                let file = &ctx.props().file;
+                self.state = DecompressState::Reading;
+                self.file_reading_task = Some(crate::share::fs_tools::read_file(
+                    0,
+                    file.file_name.clone(),
+                    &file.js_file_obj,
+                    ctx.link().callback(|(_, _, data)| DecompressFileMsg::LoadedFile(data)),
+                    ctx.link().callback(|err| DecompressFileMsg::Finished(Err(err))),
+                ));
+                true
+            }
+            DecompressFileMsg::LoadedFile(data) => {
+                self.file_reading_task = None;
+                let Some(compression) = Self::compression(ctx) else { return false; };
+                let limits = ctx.props().limits;
+                let on_progress = ctx.link().callback(DecompressFileMsg::Progress);
+                let on_finished = ctx.link().callback(DecompressFileMsg::Finished);
+                wasm_bindgen_futures::spawn_local(async move {
+                    on_finished.emit(model::decompress_file(data, compression, limits, on_progress).await);
+                });
+                self.state = DecompressState::InProcess(DecompressProgress::default());
+                true
+            }
+            DecompressFileMsg::Progress(progress) => {
+                self.state = DecompressState::InProcess(progress);
+                true
+            }
+            DecompressFileMsg::Finished(res) => {
+                self.file_reading_task = None;
+                self.state = match res {
+                    Ok(data) => DecompressState::Done(gloo_file::Blob::new(data.as_slice())),
+                    Err(err) => DecompressState::Fail(err),
+                };
+                true
+            }
+            DecompressFileMsg::Reset => {
+                self.state = DecompressState::Idle;
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let Some(compression) = Self::compression(ctx) else { return yew::html! {}; };
+        let inner_file_name = compression.inner_file_name(&ctx.props().file.file_name);
+        let reset_onclick = &ctx.link().callback(|_| DecompressFileMsg::Reset);
+        let content = match &self.state {
+            DecompressState::Idle => {
+                let start_onclick = &ctx.link().callback(|_| DecompressFileMsg::Start);
+                yew::html! {
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")}
+                        title={format!("The file is compressed with {compression}.")}
+                        onclick={start_onclick}
+                    >
+                        {format!("Decompress to {inner_file_name}")}
+                    </button>
+                }
+            }
+            DecompressState::Reading => yew::html! {
+                <small>{"Reading file..."}</small>
+            },
+            DecompressState::InProcess(progress) => {
+                let percent = (100 * progress.read_bytes).checked_div(progress.total_bytes).unwrap_or(0);
+                yew::html! {
+                    <div class={yew::classes!("d-flex", "flex-column", "w-100")}>
+                        <small>{format!("Decompressed: {}", crate::share::size_to_string(progress.written_bytes as f64))}</small>
+                        <div class={yew::classes!("progress")} role="progressbar" aria-label="Decompression progress">
+                            <div class={yew::classes!("progress-bar")} style={format!("width: {percent}%")}>
+                                {format!("{percent}%")}
+                            </div>
+                        </div>
+                    </div>
+                }
+            }
+            DecompressState::Done(blob) => {
+                let href = web_sys::Url::create_object_url_with_blob(blob.as_ref()).ok();
+                yew::html! {
+                    <div>
+                        if let Some(href) = href {
+                            <a
+                                class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")}
+                                href={href}
+                                download={inner_file_name.clone()}
+                            >
+                                {format!("Download {} ({})", inner_file_name, crate::share::size_to_string(blob.size() as f64))}
+                            </a>
+                        }
+                        <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={reset_onclick}>
+                            {"Back"}
+                        </button>
+                    </div>
+                }
+            }
+            DecompressState::Fail((msg, detail)) => yew::html! {
+                <div
+                    class={yew::classes!("alert", "alert-danger", "py-1", "mb-0")}
+                    data-bs-toggle={"tooltip"} data-bs-placement={"top"}
+                    title={detail.clone()}
+                    onclick={reset_onclick}
+                >{format!("Error: {msg}")}
+                </div>
+            },
+        };
+        yew::html! {
+            <div class={yew::classes!("mt-1")}>{content}</div>
+        }
+    }
+}
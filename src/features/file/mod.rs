@@ -1,3 +1,4 @@
 pub mod delete_file;
 pub mod add_files;
-pub mod compress_files;
\ No newline at end of file
+pub mod compress_files;
+pub mod decompress_file;
\ No newline at end of file
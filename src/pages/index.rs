@@ -1,15 +1,39 @@
-pub struct IndexPage {}
+pub enum IndexPageMsg {
+    /// The list of files selected for compression has changed.
+    SelectionChanged(Vec<std::rc::Rc<crate::entities::file::model::InfoAboutSelectedFile>>),
+    /// The limits checked before archives and compressed files are unpacked have changed.
+    LimitsChanged(crate::features::archive::archive_limits::ArchiveLimits),
+}
+
+pub struct IndexPage {
+    selected_files: Vec<std::rc::Rc<crate::entities::file::model::InfoAboutSelectedFile>>,
+    limits: crate::features::archive::archive_limits::ArchiveLimits,
+}
 
 impl yew::Component for IndexPage {
-    type Message = ();
+    type Message = IndexPageMsg;
     type Properties = ();
 
     fn create(_ctx: &yew::Context<Self>) -> Self {
-        Self {}
+        Self { selected_files: vec![], limits: crate::features::archive::archive_limits::ArchiveLimits::default() }
     }
 
+    fn update(&mut self, _ctx: &yew::Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            IndexPageMsg::SelectionChanged(files) => {
+                self.selected_files = files;
+                true
+            }
+            IndexPageMsg::LimitsChanged(limits) => {
+                self.limits = limits;
+                true
+            }
+        }
+    }
 
-    fn view(&self, _ctx: &yew::Context<Self>) -> yew::Html {
+    fn view(&self, ctx: &yew::Context<Self>) -> yew::Html {
+        let on_files_change = ctx.link().callback(IndexPageMsg::SelectionChanged);
+        let on_limits_change = ctx.link().callback(IndexPageMsg::LimitsChanged);
         yew::html! {
             <>
                 <div class={yew::classes!("row", "text-center", "border")}>
@@ -17,7 +41,26 @@ impl yew::Component for IndexPage {
                     <p>{"A simple example program for creating ZIP archives running in the browser using WebAssembly."}</p>
                     <p>{"GitHub: "}<a href={"https://github.com/MAE664128/demo_web_zip_wasm"} target="_blank">{"mae664128/demo_web_zip_wasm"}</a></p>
                 </div>
-                <crate::widgets::file_selection_block::FileSelectionBlockComponent />
+                <crate::widgets::file_selection_block::FileSelectionBlockComponent {on_files_change} limits={self.limits} />
+                <div class={yew::classes!("row", "text-center", "border", "mt-3")}>
+                    <h3>{"Open Zip archive"}</h3>
+                </div>
+                <crate::widgets::archive_browser_block::ArchiveBrowserBlockComponent
+                    selected_files={self.selected_files.clone()}
+                    limits={self.limits}
+                    {on_limits_change}
+                />
+                <div class={yew::classes!("row", "text-center", "border", "mt-3")}>
+                    <h3>{"Archive tools"}</h3>
+                </div>
+                <crate::widgets::archive_tools_block::ArchiveToolsBlockComponent limits={self.limits} />
+                <div class={yew::classes!("row", "text-center", "border", "mt-3")}>
+                    <h3>{"Archive manager"}</h3>
+                </div>
+                <crate::widgets::archive_manager_block::ArchiveManagerBlockComponent
+                    selected_files={self.selected_files.clone()}
+                    limits={self.limits}
+                />
             </>
         }
     }
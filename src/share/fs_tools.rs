@@ -20,4 +20,130 @@ pub fn read_file(
             }
         }
     })
-}
\ No newline at end of file
+}
+
+#[wasm_bindgen::prelude::wasm_bindgen]
+extern "C" {
+    /// `window.showDirectoryPicker()` of the File System Access API.
+    #[wasm_bindgen(catch, js_namespace = window, js_name = showDirectoryPicker)]
+    fn show_directory_picker(options: &wasm_bindgen::JsValue) -> Result<js_sys::Promise, wasm_bindgen::JsValue>;
+}
+
+/// Text of a JS exception.
+fn js_error_text(err: &wasm_bindgen::JsValue) -> String {
+    use wasm_bindgen::JsCast;
+    match err.dyn_ref::<js_sys::Error>() {
+        Some(err) => format!("{}: {}", String::from(err.name()), String::from(err.message())),
+        None => format!("{:?}", err),
+    }
+}
+
+/// Whether the browser lets the page write into a folder chosen by the user.
+pub fn is_directory_picker_supported() -> bool {
+    web_sys::window()
+        .map(|window| js_sys::Reflect::has(&window, &"showDirectoryPicker".into()).unwrap_or(false))
+        .unwrap_or(false)
+}
+
+/// Asks the user for a folder with write access.
+/// Returns `None` if the user closed the picker.
+pub async fn pick_directory() -> Result<Option<web_sys::FileSystemDirectoryHandle>, (String, String)> {
+    use wasm_bindgen::JsCast;
+    let picker_err = |err: &wasm_bindgen::JsValue| ("Failed to open the folder.".to_string(), js_error_text(err));
+    let options = js_sys::Object::new();
+    js_sys::Reflect::set(&options, &"mode".into(), &"readwrite".into()).map_err(|err| picker_err(&err))?;
+    let promise = show_directory_picker(&options).map_err(|err| picker_err(&err))?;
+    match wasm_bindgen_futures::JsFuture::from(promise).await {
+        Ok(handle) => Ok(Some(handle.unchecked_into())),
+        Err(err) => {
+            let name = js_sys::Reflect::get(&err, &"name".into()).ok().and_then(|name| name.as_string());
+            if name.as_deref() == Some("AbortError") { Ok(None) } else { Err(picker_err(&err)) }
+        }
+    }
+}
+
+/// Opens a subfolder, creating it if it does not exist.
+pub async fn get_or_create_directory(
+    parent: &web_sys::FileSystemDirectoryHandle,
+    name: &str,
+) -> Result<web_sys::FileSystemDirectoryHandle, (String, String)> {
+    use wasm_bindgen::JsCast;
+    let options = web_sys::FileSystemGetDirectoryOptions::new();
+    options.set_create(true);
+    wasm_bindgen_futures::JsFuture::from(parent.get_directory_handle_with_options(name, &options))
+        .await
+        .map(|handle| handle.unchecked_into())
+        .map_err(|err| (format!("Failed to create folder {}.", name), js_error_text(&err)))
+}
+
+/// Whether the folder already contains a file or a subfolder with the name.
+pub async fn entry_exists(parent: &web_sys::FileSystemDirectoryHandle, name: &str) -> bool {
+    if wasm_bindgen_futures::JsFuture::from(parent.get_file_handle(name)).await.is_ok() {
+        return true;
+    }
+    wasm_bindgen_futures::JsFuture::from(parent.get_directory_handle(name)).await.is_ok()
+}
+
+/// Creates or truncates a file in the folder and opens it for writing.
+pub async fn create_file_writer(
+    parent: &web_sys::FileSystemDirectoryHandle,
+    name: &str,
+) -> Result<web_sys::FileSystemWritableFileStream, (String, String)> {
+    use wasm_bindgen::JsCast;
+    let create_err = |err: wasm_bindgen::JsValue| (format!("Failed to create file {}.", name), js_error_text(&err));
+    let options = web_sys::FileSystemGetFileOptions::new();
+    options.set_create(true);
+    let handle: web_sys::FileSystemFileHandle =
+        wasm_bindgen_futures::JsFuture::from(parent.get_file_handle_with_options(name, &options))
+            .await
+            .map_err(create_err)?
+            .unchecked_into();
+    wasm_bindgen_futures::JsFuture::from(handle.create_writable())
+        .await
+        .map(|writer| writer.unchecked_into())
+        .map_err(create_err)
+}
+
+/// Appends a chunk of data to a file opened with [`create_file_writer`].
+pub async fn write_chunk(
+    writer: &web_sys::FileSystemWritableFileStream,
+    chunk: &[u8],
+) -> Result<(), (String, String)> {
+    let write_err = |err: wasm_bindgen::JsValue| ("Failed to write file.".to_string(), js_error_text(&err));
+    let promise = writer.write_with_u8_array(chunk).map_err(write_err)?;
+    wasm_bindgen_futures::JsFuture::from(promise).await.map(|_| ()).map_err(write_err)
+}
+
+/// Finishes writing a file opened with [`create_file_writer`].
+pub async fn close_writer(writer: &web_sys::FileSystemWritableFileStream) -> Result<(), (String, String)> {
+    wasm_bindgen_futures::JsFuture::from(writer.close())
+        .await
+        .map(|_| ())
+        .map_err(|err| ("Failed to save file.".to_string(), js_error_text(&err)))
+}
+
+/// Discards the data written to a file opened with [`create_file_writer`].
+/// A file created for the writer is removed, an existing file keeps its contents.
+/// Errors are ignored: the file is cleaned up after another error, which is reported instead.
+pub async fn abort_writer(
+    parent: &web_sys::FileSystemDirectoryHandle,
+    name: &str,
+    writer: &web_sys::FileSystemWritableFileStream,
+    is_new_file: bool,
+) {
+    let _ = wasm_bindgen_futures::JsFuture::from(writer.abort()).await;
+    if is_new_file {
+        let _ = wasm_bindgen_futures::JsFuture::from(parent.remove_entry(name)).await;
+    }
+}
+
+/// Lets the browser handle events and repaint the page before a long computation continues.
+pub async fn yield_to_browser() {
+    let promise = js_sys::Promise::new(&mut |resolve, _| {
+        let scheduled = web_sys::window().and_then(|window| window.set_timeout_with_callback(&resolve).ok());
+        if scheduled.is_none() {
+            let _ = resolve.call0(&wasm_bindgen::JsValue::NULL);
+        }
+    });
+    let _ = wasm_bindgen_futures::JsFuture::from(promise).await;
+}
@@ -1,4 +1,10 @@
 pub mod fs_tools;
+pub mod zip_tools;
+pub mod zip_format;
+pub mod text_tools;
+pub mod tar_tools;
+pub mod xz_tools;
+pub mod zstd_tools;
 
 
 const SUFFIX: [&str; 9] = ["B", "KB", "MB", "GB", "TB", "PB", "EB", "ZB", "YB"];
@@ -17,4 +23,99 @@ pub fn size_to_string(size: f64) -> String {
         // Add suffix
         [result, SUFFIX[base.floor() as usize]].join(" ")
     }
-}
\ No newline at end of file
+}
+
+/// Reads a little-endian `u16` at the given offset, if the slice is long enough.
+pub fn read_u16_le(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Reads a little-endian `u32` at the given offset, if the slice is long enough.
+pub fn read_u32_le(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads a little-endian `u64` at the given offset, if the slice is long enough.
+pub fn read_u64_le(data: &[u8], offset: usize) -> Option<u64> {
+    let bytes = data.get(offset..offset.checked_add(8)?)?;
+    Some(u64::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Inserts a number before the extension: `dir/report.txt` becomes `dir/report (n).txt`.
+pub fn numbered_name(name: &str, n: usize) -> String {
+    let (dir, file) = match name.rsplit_once('/') {
+        Some((dir, file)) => (format!("{dir}/"), file),
+        None => (String::new(), name),
+    };
+    let (stem, ext) = match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem, format!(".{ext}")),
+        _ => (file, String::new()),
+    };
+    format!("{dir}{stem} ({n}){ext}")
+}
+
+/// Whether the entry name is the file name with the `{ind}-` prefix
+/// given to the files when they are compressed.
+pub fn is_numbered_name(entry_name: &str, file_name: &str) -> bool {
+    entry_name.strip_suffix(file_name)
+        .and_then(|prefix| prefix.strip_suffix('-'))
+        .is_some_and(|ind| !ind.is_empty() && ind.bytes().all(|byte| byte.is_ascii_digit()))
+}
+
+/// Indexes of the entry names matched with the file names, in the order of the files.
+///
+/// Files are matched by name, then by the `{ind}-{name}` name. Each entry name is
+/// matched with one file at most.
+pub fn match_names(entry_names: &[&str], file_names: &[&str]) -> Vec<Option<usize>> {
+    let mut matched = std::collections::HashSet::new();
+    let mut res = vec![None; file_names.len()];
+    let passes: [fn(&str, &str) -> bool; 2] = [|entry_name, file_name| entry_name == file_name, is_numbered_name];
+    for is_match in passes {
+        for (file_name, res) in file_names.iter().zip(res.iter_mut()).filter(|(_, res)| res.is_none()) {
+            *res = (0..entry_names.len())
+                .find(|ind| !matched.contains(ind) && is_match(entry_names[*ind], file_name));
+            if let Some(ind) = res {
+                matched.insert(*ind);
+            }
+        }
+    }
+    res
+}
+
+/// Compares names in natural order: case-insensitive, with runs of digits compared as numbers,
+/// so that `img2` goes before `img10`.
+pub fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    let mut a_chars = a.chars().peekable();
+    let mut b_chars = b.chars().peekable();
+    // Numbers that differ only in leading zeros are ordered by them only if everything else is equal.
+    let mut zeros_ordering = Ordering::Equal;
+    while let (Some(&a_char), Some(&b_char)) = (a_chars.peek(), b_chars.peek()) {
+        let ordering = if a_char.is_ascii_digit() && b_char.is_ascii_digit() {
+            let a_number = take_digits(&mut a_chars);
+            let b_number = take_digits(&mut b_chars);
+            let (a_value, b_value) = (a_number.trim_start_matches('0'), b_number.trim_start_matches('0'));
+            zeros_ordering = zeros_ordering.then(a_number.len().cmp(&b_number.len()));
+            a_value.len().cmp(&b_value.len()).then_with(|| a_value.cmp(b_value))
+        } else {
+            a_chars.next();
+            b_chars.next();
+            a_char.to_lowercase().cmp(b_char.to_lowercase())
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a_chars.count().cmp(&b_chars.count()).then(zeros_ordering).then_with(|| a.cmp(b))
+}
+
+/// Takes the run of ASCII digits at the start of the iterator.
+fn take_digits(chars: &mut std::iter::Peekable<std::str::Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+        digits.push(digit);
+    }
+    digits
+}
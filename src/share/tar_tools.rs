@@ -0,0 +1,113 @@
+//! Compression of tar archives.
+
+use std::io::Read;
+
+/// Compression applied to a whole tar archive.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub(crate) enum TarCompression {
+    None,
+    Gzip,
+    Bzip2,
+    Xz,
+    Zstd,
+}
+
+impl TarCompression {
+    pub const ALL: [Self; 5] = [Self::None, Self::Gzip, Self::Bzip2, Self::Xz, Self::Zstd];
+
+    /// Recognizes a tar archive by the file extension.
+    pub fn from_file_name(file_name: &str) -> Option<Self> {
+        let file_name = file_name.to_ascii_lowercase();
+        Self::ALL.into_iter().find(|compression| {
+            compression.extensions().iter().any(|extension| file_name.ends_with(extension))
+        })
+    }
+
+    /// Extensions of the archive, the first one is used for new files.
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            Self::None => &[".tar"],
+            Self::Gzip => &[".tar.gz", ".tgz"],
+            Self::Bzip2 => &[".tar.bz2", ".tbz2", ".tbz"],
+            Self::Xz => &[".tar.xz", ".txz"],
+            Self::Zstd => &[".tar.zst", ".tzst"],
+        }
+    }
+
+    /// Returns a reader of the uncompressed tar archive, which is decompressed while reading.
+    /// Concatenated gzip, bzip2 and xz streams and Zstandard frames are read one after another.
+    pub fn decompress<'a>(&self, data: &'a [u8]) -> Box<dyn Read + 'a> {
+        match self {
+            Self::None => Box::new(data),
+            Self::Gzip => Box::new(flate2::read::MultiGzDecoder::new(data)),
+            Self::Bzip2 => Box::new(bzip2::read::MultiBzDecoder::new(data)),
+            Self::Xz => Box::new(crate::share::xz_tools::XzReader::new(data)),
+            Self::Zstd => Box::new(crate::share::zstd_tools::ZstdReader::new(data)),
+        }
+    }
+
+    /// Compresses a tar archive.
+    pub fn compress(&self, tar: Vec<u8>) -> Result<Vec<u8>, (String, String)> {
+        let write_err = |err: std::io::Error| (format!("Failed to compress {} archive.", self), format!("{}", err));
+        match self {
+            Self::None => Ok(tar),
+            Self::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                std::io::Write::write_all(&mut encoder, &tar).map_err(write_err)?;
+                encoder.finish().map_err(write_err)
+            }
+            Self::Bzip2 => {
+                let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+                std::io::Write::write_all(&mut encoder, &tar).map_err(write_err)?;
+                encoder.finish().map_err(write_err)
+            }
+            Self::Xz => crate::share::xz_tools::xz_compress(&tar).map_err(write_err),
+            Self::Zstd => Ok(ruzstd::encoding::compress_to_vec(tar.as_slice(), ruzstd::encoding::CompressionLevel::Fastest)),
+        }
+    }
+}
+
+/// Number and total size of the entries of a tar archive, read from their headers.
+#[derive(Default)]
+pub(crate) struct TarListing {
+    pub entries: usize,
+    pub total_size: u64,
+    /// Reading stopped early because a limit was exceeded.
+    pub is_partial: bool,
+}
+
+/// Lists a tar archive by the headers of its entries. The data of the entries is skipped.
+///
+/// Reading stops as soon as there are more than `max_entries` entries or their sizes
+/// add up to more than `max_total_size`, so that a huge archive is not decompressed to the end.
+pub(crate) fn list_tar<R: Read>(
+    reader: R,
+    max_entries: usize,
+    max_total_size: u64,
+) -> Result<TarListing, (String, String)> {
+    let read_err = |err: std::io::Error| ("Failed to read tar archive.".to_string(), format!("{}", err));
+    let mut listing = TarListing::default();
+    let mut tar = tar::Archive::new(reader);
+    for entry in tar.entries().map_err(read_err)? {
+        let entry = entry.map_err(read_err)?;
+        listing.entries += 1;
+        listing.total_size = listing.total_size.saturating_add(entry.size());
+        if listing.entries > max_entries || listing.total_size > max_total_size {
+            listing.is_partial = true;
+            break;
+        }
+    }
+    Ok(listing)
+}
+
+impl std::fmt::Display for TarCompression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::None => write!(f, "tar"),
+            Self::Gzip => write!(f, "tar.gz"),
+            Self::Bzip2 => write!(f, "tar.bz2"),
+            Self::Xz => write!(f, "tar.xz"),
+            Self::Zstd => write!(f, "tar.zst"),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! Detection of the encoding of text data.
+
+/// How many bytes are checked to decide whether the data is text.
+const SAMPLE_SIZE: usize = 8192;
+
+/// Detects the encoding of text data, `None` if the data looks binary.
+///
+/// `is_complete` is false when the data is only the beginning of the text,
+/// so it may end in the middle of a character.
+pub fn detect_encoding(data: &[u8], is_complete: bool) -> Option<&'static encoding_rs::Encoding> {
+    if let Some((encoding, _)) = encoding_rs::Encoding::for_bom(data) {
+        return Some(encoding);
+    }
+
+    let sample = &data[..data.len().min(SAMPLE_SIZE)];
+    let control_chars = sample.iter()
+        .filter(|byte| byte.is_ascii_control() && !matches!(byte, b'\n' | b'\r' | b'\t' | 0x0C))
+        .count();
+    if sample.contains(&0) || control_chars * 100 > sample.len() {
+        return None;
+    }
+
+    match std::str::from_utf8(data) {
+        Ok(_) => return Some(encoding_rs::UTF_8),
+        Err(err) if !is_complete && err.error_len().is_none() => return Some(encoding_rs::UTF_8),
+        Err(_) => {}
+    }
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(data, is_complete);
+    Some(detector.guess(None, true))
+}
+
+/// Decodes the data as text, detecting its encoding.
+///
+/// Returns the text and the name of the encoding, or `None` if the data looks binary.
+pub fn decode_text(data: &[u8]) -> Option<(String, &'static str)> {
+    let encoding = detect_encoding(data, true)?;
+    let (text, encoding, _) = encoding.decode(data);
+    Some((text.into_owned(), encoding.name()))
+}
+
+/// Charset of entry names and comments stored without the UTF-8 flag.
+///
+/// Old archivers wrote them in the OEM code page of the system,
+/// CP437 on Western and CP866 on Russian versions of Windows and DOS.
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub enum LegacyCharset {
+    Utf8,
+    Cp437,
+    Cp866,
+}
+
+impl LegacyCharset {
+    pub const ALL: [Self; 3] = [Self::Utf8, Self::Cp437, Self::Cp866];
+
+    /// Detects the charset of names and comments without the UTF-8 flag.
+    ///
+    /// Returns `None` if all of them are ASCII and the charset does not matter.
+    pub fn detect<'a>(samples: impl Iterator<Item = &'a [u8]>) -> Option<Self> {
+        let mut is_legacy = false;
+        let mut is_utf8 = true;
+        let mut high_bytes = 0;
+        let mut cyrillic_bytes = 0;
+        for sample in samples.filter(|sample| !sample.is_ascii()) {
+            is_legacy = true;
+            is_utf8 &= std::str::from_utf8(sample).is_ok();
+            for byte in sample.iter().filter(|byte| !byte.is_ascii()) {
+                high_bytes += 1;
+                // Lowercase letters from "ж" to "я" in CP866. In CP437 these bytes are
+                // fractions, quotes and Greek letters, which are rare in file names.
+                if matches!(byte, 0xA6..=0xAF | 0xE0..=0xF1) {
+                    cyrillic_bytes += 1;
+                }
+            }
+        }
+        if !is_legacy {
+            None
+        } else if is_utf8 {
+            Some(Self::Utf8)
+        } else if cyrillic_bytes * 3 >= high_bytes {
+            Some(Self::Cp866)
+        } else {
+            Some(Self::Cp437)
+        }
+    }
+
+    pub fn decode(&self, data: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(data).into_owned(),
+            Self::Cp437 => oem_cp::decode_string_complete_table(data, &oem_cp::code_table::DECODING_TABLE_CP437),
+            Self::Cp866 => oem_cp::decode_string_complete_table(data, &oem_cp::code_table::DECODING_TABLE_CP866),
+        }
+    }
+}
+
+impl std::fmt::Display for LegacyCharset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Utf8 => write!(f, "UTF-8"),
+            Self::Cp437 => write!(f, "CP437 (DOS Latin)"),
+            Self::Cp866 => write!(f, "CP866 (DOS Cyrillic)"),
+        }
+    }
+}
+
+impl From<String> for LegacyCharset {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Cp437" => { Self::Cp437 }
+            "Cp866" => { Self::Cp866 }
+            _ => { Self::Utf8 }
+        }
+    }
+}
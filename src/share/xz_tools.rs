@@ -0,0 +1,495 @@
+//! Reading and writing of xz files with the LZMA2 coder of lzma-rust.
+
+use std::io::{Read, Write};
+
+const HEADER_MAGIC: [u8; 6] = [0xFD, b'7', b'z', b'X', b'Z', 0x00];
+const FOOTER_MAGIC: [u8; 2] = [b'Y', b'Z'];
+/// Size of the stream header and of the stream footer.
+const STREAM_HEADER_SIZE: usize = 12;
+/// ID of the LZMA2 filter, the only filter written by xz without extra options.
+const LZMA2_FILTER_ID: u64 = 0x21;
+/// ID of the CRC32 check in the stream flags, used for the written files.
+const CHECK_CRC32: u8 = 0x01;
+/// Preset of the LZMA2 encoder, the default of `xz`.
+const LZMA2_PRESET: u32 = 6;
+/// Polynomial of the CRC64 check, in the reversed form used by xz.
+const CRC64_POLY: u64 = 0xC96C_5795_D787_0F42;
+const CRC64_TABLE: [u64; 256] = crc64_table();
+
+const fn crc64_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut ind = 0;
+    while ind < 256 {
+        let mut crc = ind as u64;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ CRC64_POLY } else { crc >> 1 };
+            bit += 1;
+        }
+        table[ind] = crc;
+        ind += 1;
+    }
+    table
+}
+
+fn invalid_data(msg: &str) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("xz: {msg}"))
+}
+
+/// Reads a variable-length integer of xz: 7 bits per byte, the high bit marks a next byte.
+fn read_varint(mut next_byte: impl FnMut() -> std::io::Result<u8>) -> std::io::Result<u64> {
+    let mut value = 0u64;
+    for shift in (0..63).step_by(7) {
+        let byte = next_byte()?;
+        value |= ((byte & 0x7F) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(invalid_data("integer is too long"))
+}
+
+/// Writes a variable-length integer of xz.
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        out.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Appends zero bytes up to the next multiple of 4.
+fn pad(out: &mut Vec<u8>) {
+    while out.len() % 4 != 0 {
+        out.push(0);
+    }
+}
+
+/// Dictionary size encoded in the property byte of the LZMA2 filter.
+fn dict_size(props: u8) -> Option<u32> {
+    match props {
+        0..=39 => Some((2 | (props as u32 & 1)) << (props / 2 + 11)),
+        40 => Some(u32::MAX),
+        _ => None,
+    }
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> std::io::Result<u8> {
+    let mut byte = [0u8];
+    reader.read_exact(&mut byte)?;
+    Ok(byte[0])
+}
+
+/// Reads a byte of the index, keeping it to verify the CRC32 of the index.
+fn read_index_byte<R: Read>(reader: &mut R, index: &mut Vec<u8>) -> std::io::Result<u8> {
+    let byte = read_u8(reader)?;
+    index.push(byte);
+    Ok(byte)
+}
+
+/// Reader that counts the bytes read: blocks and the index are padded to 4 bytes.
+struct CountingReader<R> {
+    inner: R,
+    read_bytes: u64,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.read_bytes += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read> CountingReader<R> {
+    /// Skips the zero bytes up to the next multiple of 4.
+    fn skip_padding(&mut self) -> std::io::Result<()> {
+        while self.read_bytes % 4 != 0 {
+            if read_u8(self)? != 0 {
+                return Err(invalid_data("non-zero padding"));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Check of the uncompressed data of a block.
+enum Check {
+    None,
+    Crc32(crc32fast::Hasher),
+    Crc64(u64),
+    /// SHA-256 and reserved checks are skipped without verification.
+    Skipped(usize),
+}
+
+impl Check {
+    fn new(check_id: u8) -> Self {
+        match check_id {
+            0x00 => Self::None,
+            0x01 => Self::Crc32(crc32fast::Hasher::new()),
+            0x04 => Self::Crc64(!0),
+            // The size of the other checks is defined by groups of three IDs.
+            id => Self::Skipped(4 << ((id as usize - 1) / 3)),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Self::Crc32(hasher) => hasher.update(data),
+            Self::Crc64(crc) => {
+                for &byte in data {
+                    *crc = CRC64_TABLE[((*crc ^ byte as u64) & 0xFF) as usize] ^ (*crc >> 8);
+                }
+            }
+            Self::None | Self::Skipped(_) => {}
+        }
+    }
+
+    /// Reads the check stored after the block and compares it with the computed one.
+    fn verify<R: Read>(self, reader: &mut R) -> std::io::Result<()> {
+        let matches = match self {
+            Self::None => true,
+            Self::Crc32(hasher) => {
+                let mut stored = [0u8; 4];
+                reader.read_exact(&mut stored)?;
+                u32::from_le_bytes(stored) == hasher.finalize()
+            }
+            Self::Crc64(crc) => {
+                let mut stored = [0u8; 8];
+                reader.read_exact(&mut stored)?;
+                u64::from_le_bytes(stored) == !crc
+            }
+            Self::Skipped(size) => {
+                std::io::copy(&mut reader.take(size as u64), &mut std::io::sink())?;
+                true
+            }
+        };
+        if matches { Ok(()) } else { Err(invalid_data("checksum mismatch")) }
+    }
+}
+
+/// Decompresses an xz file while reading, one block at a time.
+///
+/// Concatenated streams are read one after another, as by `xz -d`.
+/// Only blocks with the single LZMA2 filter are supported.
+pub(crate) struct XzReader<R: Read> {
+    /// Source of the compressed data, `None` while it is owned by the block decoder.
+    inner: Option<CountingReader<R>>,
+    block: Option<lzma_rust::LZMA2Reader<CountingReader<R>>>,
+    /// Check ID from the header of the current stream, `None` between streams.
+    check_id: Option<u8>,
+    check: Check,
+    streams: usize,
+    finished: bool,
+}
+
+impl<R: Read> XzReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner: Some(CountingReader { inner, read_bytes: 0 }),
+            block: None,
+            check_id: None,
+            check: Check::None,
+            streams: 0,
+            finished: false,
+        }
+    }
+
+    fn inner(&mut self) -> &mut CountingReader<R> {
+        self.inner.as_mut().expect("the source is owned by the reader between blocks")
+    }
+
+    /// Reads the stream header, skipping the stream padding. Returns `false` at the end of the file.
+    fn start_stream(&mut self) -> std::io::Result<bool> {
+        let mut header = [0u8; STREAM_HEADER_SIZE];
+        loop {
+            let mut filled = 0;
+            while filled < 4 {
+                match self.inner().read(&mut header[filled..4])? {
+                    0 if filled == 0 && self.streams > 0 => return Ok(false),
+                    0 => return Err(std::io::ErrorKind::UnexpectedEof.into()),
+                    n => filled += n,
+                }
+            }
+            // Zero bytes between streams are the stream padding.
+            if self.streams == 0 || header[..4] != [0; 4] {
+                break;
+            }
+        }
+        self.inner().read_exact(&mut header[4..])?;
+        if header[..6] != HEADER_MAGIC {
+            return Err(invalid_data("not an xz file"));
+        }
+        if crc32fast::hash(&header[6..8]).to_le_bytes() != header[8..12] {
+            return Err(invalid_data("corrupted stream header"));
+        }
+        if header[6] != 0 || header[7] > 0x0F {
+            return Err(invalid_data("unsupported stream flags"));
+        }
+        self.check_id = Some(header[7]);
+        Ok(true)
+    }
+
+    /// Reads the header of the next block and starts decoding it, or reads the index
+    /// and the stream footer if there are no more blocks in the stream.
+    fn start_block(&mut self) -> std::io::Result<()> {
+        let Some(check_id) = self.check_id else {
+            if !self.start_stream()? {
+                self.finished = true;
+            }
+            return Ok(());
+        };
+        let size_byte = read_u8(self.inner())?;
+        if size_byte == 0 {
+            self.read_index()?;
+            self.check_id = None;
+            self.streams += 1;
+            return Ok(());
+        }
+
+        let mut header = vec![0u8; (size_byte as usize + 1) * 4];
+        header[0] = size_byte;
+        self.inner().read_exact(&mut header[1..])?;
+        let (fields, stored_crc) = header.split_at(header.len() - 4);
+        if crc32fast::hash(fields).to_le_bytes() != stored_crc {
+            return Err(invalid_data("corrupted block header"));
+        }
+        let flags = fields[1];
+        if flags & 0x3C != 0 {
+            return Err(invalid_data("unsupported block flags"));
+        }
+        if flags & 0x03 != 0 {
+            return Err(invalid_data("only the LZMA2 filter is supported"));
+        }
+        let mut fields = fields[2..].iter().copied();
+        let mut next_byte = || fields.next().ok_or_else(|| invalid_data("corrupted block header"));
+        if flags & 0x40 != 0 {
+            read_varint(&mut next_byte)?;
+        }
+        if flags & 0x80 != 0 {
+            read_varint(&mut next_byte)?;
+        }
+        if read_varint(&mut next_byte)? != LZMA2_FILTER_ID || read_varint(&mut next_byte)? != 1 {
+            return Err(invalid_data("only the LZMA2 filter is supported"));
+        }
+        let dict_size = dict_size(next_byte()?).ok_or_else(|| invalid_data("invalid LZMA2 dictionary size"))?;
+
+        let inner = self.inner.take().expect("the source is owned by the reader between blocks");
+        self.block = Some(lzma_rust::LZMA2Reader::new(inner, dict_size, None));
+        self.check = Check::new(check_id);
+        Ok(())
+    }
+
+    /// Skips the padding of the finished block and verifies its check.
+    fn finish_block(&mut self) -> std::io::Result<()> {
+        let Some(block) = self.block.take() else { return Ok(()); };
+        let mut inner = block.into_inner();
+        inner.skip_padding()?;
+        std::mem::replace(&mut self.check, Check::None).verify(&mut inner)?;
+        self.inner = Some(inner);
+        Ok(())
+    }
+
+    /// Reads the index, whose indicator byte has been read, and the stream footer.
+    fn read_index(&mut self) -> std::io::Result<()> {
+        let inner = self.inner();
+        let mut index = vec![0u8];
+        let records = read_varint(|| read_index_byte(inner, &mut index))?;
+        for _ in 0..records.saturating_mul(2) {
+            read_varint(|| read_index_byte(inner, &mut index))?;
+        }
+        while index.len() % 4 != 0 {
+            if read_index_byte(inner, &mut index)? != 0 {
+                return Err(invalid_data("non-zero padding"));
+            }
+        }
+        let mut stored_crc = [0u8; 4];
+        inner.read_exact(&mut stored_crc)?;
+        if crc32fast::hash(&index).to_le_bytes() != stored_crc {
+            return Err(invalid_data("corrupted index"));
+        }
+
+        let mut footer = [0u8; STREAM_HEADER_SIZE];
+        inner.read_exact(&mut footer)?;
+        if footer[10..] != FOOTER_MAGIC {
+            return Err(invalid_data("corrupted stream footer"));
+        }
+        Ok(())
+    }
+}
+
+impl<R: Read> Read for XzReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if let Some(block) = &mut self.block {
+                let n = block.read(buf)?;
+                if n > 0 {
+                    self.check.update(&buf[..n]);
+                    return Ok(n);
+                }
+                self.finish_block()?;
+                continue;
+            }
+            if self.finished {
+                return Ok(0);
+            }
+            self.start_block()?;
+        }
+    }
+}
+
+/// Compresses the data into an xz file of one stream with one LZMA2 block and a CRC32 check.
+pub(crate) fn xz_compress(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    let options = lzma_rust::LZMA2Options::with_preset(LZMA2_PRESET);
+    let mut out = Vec::with_capacity(data.len() / 2);
+    let stream_flags = [0, CHECK_CRC32];
+    out.extend_from_slice(&HEADER_MAGIC);
+    out.extend_from_slice(&stream_flags);
+    out.extend_from_slice(&crc32fast::hash(&stream_flags).to_le_bytes());
+
+    // Block header: its size, flags without the optional sizes, the filter and its dictionary size.
+    let mut block_header = vec![0, 0];
+    write_varint(&mut block_header, LZMA2_FILTER_ID);
+    write_varint(&mut block_header, 1);
+    block_header.push((0..=40).find(|&props| dict_size(props).is_some_and(|size| size >= options.dict_size)).unwrap_or(40));
+    pad(&mut block_header);
+    block_header[0] = (block_header.len() / 4) as u8;
+    block_header.extend_from_slice(&crc32fast::hash(&block_header).to_le_bytes());
+
+    let block_start = out.len();
+    out.extend_from_slice(&block_header);
+    let mut encoder = lzma_rust::LZMA2Writer::new(lzma_rust::CountingWriter::new(&mut out), &options);
+    encoder.write_all(data)?;
+    encoder.finish()?;
+    drop(encoder);
+    // The unpadded size of a block counts the header, the compressed data and the check.
+    let unpadded_size = (out.len() - block_start + 4) as u64;
+    pad(&mut out);
+    out.extend_from_slice(&crc32fast::hash(data).to_le_bytes());
+
+    let mut index = vec![0];
+    write_varint(&mut index, 1);
+    write_varint(&mut index, unpadded_size);
+    write_varint(&mut index, data.len() as u64);
+    pad(&mut index);
+    index.extend_from_slice(&crc32fast::hash(&index).to_le_bytes());
+    out.extend_from_slice(&index);
+
+    let mut footer = ((index.len() / 4 - 1) as u32).to_le_bytes().to_vec();
+    footer.extend_from_slice(&stream_flags);
+    out.extend_from_slice(&crc32fast::hash(&footer).to_le_bytes());
+    out.extend_from_slice(&footer);
+    out.extend_from_slice(&FOOTER_MAGIC);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Contents of the files below, made by `xz --check=none|crc32|crc64|sha256`.
+    const TEXT: &[u8] = b"Hello, xz!\nHello, xz!\n";
+    const XZ_CHECK_NONE: [u8; 80] = [
+        0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00, 0x00, 0x00, 0xff, 0x12, 0xd9, 0x41, 0x04, 0xc0, 0x19, 0x16,
+        0x21, 0x01, 0x16, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc7, 0x8f, 0x5a, 0x08,
+        0xe0, 0x00, 0x15, 0x00, 0x11, 0x5d, 0x00, 0x24, 0x19, 0x49, 0x98, 0x6f, 0x16, 0x02, 0x8d, 0x08,
+        0x8f, 0x66, 0xe1, 0xd5, 0x51, 0x57, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x2d, 0x16,
+        0x95, 0x5a, 0x78, 0xf4, 0x06, 0x72, 0x9e, 0x7a, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x59, 0x5a,
+    ];
+    const XZ_CHECK_CRC32: [u8; 84] = [
+        0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00, 0x00, 0x01, 0x69, 0x22, 0xde, 0x36, 0x04, 0xc0, 0x19, 0x16,
+        0x21, 0x01, 0x16, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc7, 0x8f, 0x5a, 0x08,
+        0xe0, 0x00, 0x15, 0x00, 0x11, 0x5d, 0x00, 0x24, 0x19, 0x49, 0x98, 0x6f, 0x16, 0x02, 0x8d, 0x08,
+        0x8f, 0x66, 0xe1, 0xd5, 0x51, 0x57, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1b, 0xeb, 0x9b, 0xbb,
+        0x00, 0x01, 0x31, 0x16, 0xc8, 0x07, 0x0f, 0x12, 0x90, 0x42, 0x99, 0x0d, 0x01, 0x00, 0x00, 0x00,
+        0x00, 0x01, 0x59, 0x5a,
+    ];
+    const XZ_CHECK_CRC64: [u8; 88] = [
+        0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00, 0x00, 0x04, 0xe6, 0xd6, 0xb4, 0x46, 0x04, 0xc0, 0x19, 0x16,
+        0x21, 0x01, 0x16, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc7, 0x8f, 0x5a, 0x08,
+        0xe0, 0x00, 0x15, 0x00, 0x11, 0x5d, 0x00, 0x24, 0x19, 0x49, 0x98, 0x6f, 0x16, 0x02, 0x8d, 0x08,
+        0x8f, 0x66, 0xe1, 0xd5, 0x51, 0x57, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x9c, 0x69, 0x74, 0x5c,
+        0x79, 0xfc, 0xd7, 0x61, 0x00, 0x01, 0x35, 0x16, 0xcc, 0xc2, 0x63, 0x76, 0x1f, 0xb6, 0xf3, 0x7d,
+        0x01, 0x00, 0x00, 0x00, 0x00, 0x04, 0x59, 0x5a,
+    ];
+    const XZ_CHECK_SHA256: [u8; 112] = [
+        0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00, 0x00, 0x0a, 0xe1, 0xfb, 0x0c, 0xa1, 0x04, 0xc0, 0x19, 0x16,
+        0x21, 0x01, 0x16, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xc7, 0x8f, 0x5a, 0x08,
+        0xe0, 0x00, 0x15, 0x00, 0x11, 0x5d, 0x00, 0x24, 0x19, 0x49, 0x98, 0x6f, 0x16, 0x02, 0x8d, 0x08,
+        0x8f, 0x66, 0xe1, 0xd5, 0x51, 0x57, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x4a, 0x4a, 0x21, 0x79,
+        0xd2, 0x56, 0x63, 0x1d, 0xb9, 0x69, 0x07, 0xa7, 0xaa, 0xc9, 0xac, 0x3f, 0x60, 0xe1, 0xf0, 0xe6,
+        0xd2, 0x8b, 0x45, 0x5e, 0xc1, 0x5b, 0x3f, 0x80, 0xdc, 0x01, 0xeb, 0x63, 0x00, 0x01, 0x4d, 0x16,
+        0x32, 0x31, 0x85, 0x91, 0x18, 0x9b, 0x4b, 0x9a, 0x01, 0x00, 0x00, 0x00, 0x00, 0x0a, 0x59, 0x5a,
+    ];
+    /// Offset of the check and of the index in the files above.
+    const CHECK_OFFSET: usize = 60;
+    const INDEX_OFFSET: usize = 64;
+
+    fn decompress(data: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut out = vec![];
+        XzReader::new(data).read_to_end(&mut out)?;
+        Ok(out)
+    }
+
+    /// Compressible text of the given number of lines.
+    fn sample_text(lines: usize) -> Vec<u8> {
+        (0..lines).flat_map(|line| format!("line {line}: {}\n", line * 7919 % 1000).into_bytes()).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        for data in [vec![], TEXT.to_vec(), sample_text(50_000)] {
+            let compressed = xz_compress(&data).unwrap();
+            assert_eq!(decompress(&compressed).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn concatenated_streams() {
+        let first = sample_text(1000);
+        let second = TEXT.to_vec();
+        let mut compressed = xz_compress(&first).unwrap();
+        // Stream padding: zero bytes in multiples of 4 between streams.
+        compressed.extend_from_slice(&[0; 8]);
+        compressed.extend_from_slice(&xz_compress(&second).unwrap());
+        compressed.extend_from_slice(&XZ_CHECK_CRC64);
+        assert_eq!(decompress(&compressed).unwrap(), [first, second, TEXT.to_vec()].concat());
+    }
+
+    #[test]
+    fn check_types() {
+        for data in [&XZ_CHECK_NONE[..], &XZ_CHECK_CRC32, &XZ_CHECK_CRC64, &XZ_CHECK_SHA256] {
+            assert_eq!(decompress(data).unwrap(), TEXT);
+        }
+    }
+
+    #[test]
+    fn corrupted_check() {
+        for data in [&XZ_CHECK_CRC32[..], &XZ_CHECK_CRC64] {
+            let mut data = data.to_vec();
+            data[CHECK_OFFSET] ^= 0xFF;
+            let err = decompress(&data).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+            assert_eq!(err.to_string(), "xz: checksum mismatch");
+        }
+    }
+
+    #[test]
+    fn corrupted_index() {
+        let mut data = XZ_CHECK_CRC32.to_vec();
+        // The unpadded size of the block.
+        data[INDEX_OFFSET + 2] ^= 0x01;
+        let err = decompress(&data).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(err.to_string(), "xz: corrupted index");
+    }
+
+    #[test]
+    fn truncated_file() {
+        let compressed = xz_compress(&sample_text(1000)).unwrap();
+        assert!(decompress(&compressed[..compressed.len() - 1]).is_err());
+    }
+}
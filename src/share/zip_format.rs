@@ -0,0 +1,36 @@
+//! Signatures, sizes and flags of the records of the ZIP format.
+
+pub const LOCAL_HEADER_SIGNATURE: [u8; 4] = *b"PK\x03\x04";
+pub const CENTRAL_HEADER_SIGNATURE: [u8; 4] = *b"PK\x01\x02";
+pub const DATA_DESCRIPTOR_SIGNATURE: [u8; 4] = *b"PK\x07\x08";
+pub const END_OF_CENTRAL_DIRECTORY_SIGNATURE: [u8; 4] = *b"PK\x05\x06";
+pub const ZIP64_END_OF_CENTRAL_DIRECTORY_SIGNATURE: [u8; 4] = *b"PK\x06\x06";
+pub const ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIGNATURE: [u8; 4] = *b"PK\x06\x07";
+
+/// Size of the fixed part of a local file header.
+pub const LOCAL_HEADER_SIZE: usize = 30;
+/// Size of the fixed part of a central directory record.
+pub const CENTRAL_HEADER_SIZE: usize = 46;
+/// Size of the end of central directory record without the comment.
+pub const END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+/// Size of the Zip64 end of central directory record after its signature and size fields.
+pub const ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE: u64 = 44;
+/// Size of the Zip64 end of central directory locator.
+pub const ZIP64_END_OF_CENTRAL_DIRECTORY_LOCATOR_SIZE: usize = 20;
+/// Version needed to extract an archive with Zip64 records: 4.5.
+pub const ZIP64_VERSION: u16 = 45;
+
+/// General purpose flag: sizes and CRC32 follow the data in a data descriptor.
+pub const FLAG_DATA_DESCRIPTOR: u16 = 1 << 3;
+/// General purpose flag: the name and the comment are stored in UTF-8.
+pub const FLAG_UTF8: u16 = 1 << 11;
+
+/// Value that marks sizes and offsets stored in the Zip64 extra field.
+pub const ZIP64_SIZE: u32 = u32::MAX;
+/// ID of the extra field with Zip64 sizes and offsets.
+pub const ZIP64_EXTRA_FIELD_ID: u16 = 0x0001;
+
+/// Whether the record at the offset starts with the signature.
+pub fn has_signature(data: &[u8], offset: usize, signature: [u8; 4]) -> bool {
+    offset.checked_add(4).and_then(|end| data.get(offset..end)) == Some(&signature[..])
+}
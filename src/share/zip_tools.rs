@@ -0,0 +1,207 @@
+//! Common settings for writing ZIP archives.
+
+use zip::unstable::write::FileOptionsExt;
+
+#[derive(PartialEq, Copy, Clone)]
+pub(crate) enum TypeEncryption {
+    ZipCrypto,
+    Aes256,
+}
+
+impl From<String> for TypeEncryption {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "Aes256" => { Self::Aes256 }
+            _ => { Self::ZipCrypto }
+        }
+    }
+}
+
+/// Options for a new archive entry, encrypted if the password is not empty.
+pub(crate) fn file_options(
+    password: &str,
+    type_encryption: TypeEncryption,
+) -> zip::write::FileOptions<'_, ()> {
+    let options = zip::write::SimpleFileOptions::default();
+    if password.is_empty() {
+        options
+    } else if type_encryption == TypeEncryption::Aes256 {
+        options.with_aes_encryption(zip::AesMode::Aes256, password)
+    } else {
+        options.with_deprecated_encryption(password.as_bytes())
+    }
+}
+
+/// Options that keep the metadata of an existing entry when its data is written again.
+pub(crate) fn entry_options<'a>(
+    file: &zip::read::ZipFile,
+    password: &'a str,
+    type_encryption: TypeEncryption,
+) -> zip::write::FileOptions<'a, ()> {
+    let compression_method = match file.compression() {
+        zip::CompressionMethod::Stored => zip::CompressionMethod::Stored,
+        _ => zip::CompressionMethod::Deflated,
+    };
+    let mut options = file_options(password, type_encryption)
+        .compression_method(compression_method)
+        .last_modified_time(file.last_modified())
+        .large_file(file.size() >= u32::MAX as u64);
+    if let Some(mode) = file.unix_mode() {
+        options = options.unix_permissions(mode);
+    }
+    options
+}
+
+/// Converts a Unix modification time to the local time stored in ZIP.
+pub(crate) fn zip_date_time(unix_time: u64) -> Option<zip::DateTime> {
+    use chrono::{Datelike, Timelike};
+    let time = chrono::DateTime::from_timestamp(unix_time as i64, 0)?.with_timezone(&chrono::Local);
+    zip::DateTime::from_date_and_time(
+        time.year().try_into().ok()?,
+        time.month() as u8,
+        time.day() as u8,
+        time.hour() as u8,
+        time.minute() as u8,
+        time.second() as u8,
+    ).ok()
+}
+
+/// Converts the local time stored in ZIP to the modification time of a tar entry.
+pub(crate) fn unix_time(date_time: zip::DateTime) -> Option<u64> {
+    let time = chrono::NaiveDate::from_ymd_opt(date_time.year() as i32, date_time.month() as u32, date_time.day() as u32)?
+        .and_hms_opt(date_time.hour() as u32, date_time.minute() as u32, date_time.second() as u32)?
+        .and_local_timezone(chrono::Local)
+        .earliest()?;
+    u64::try_from(time.timestamp()).ok()
+}
+
+/// Repacks a tar archive into a ZIP archive, keeping paths, times and modes.
+/// Entries other than files, directories and symbolic links are skipped.
+///
+/// `options` set the compression and encryption of the entries.
+pub(crate) fn tar_to_zip<R: std::io::Read>(
+    reader: R,
+    options: zip::write::FileOptions<'_, ()>,
+) -> Result<Vec<u8>, (String, String)> {
+    let read_err = |err: std::io::Error| ("Failed to read tar archive.".to_string(), format!("{}", err));
+    let write_err = |err: zip::result::ZipError| ("Failed to write ZIP archive.".to_string(), format!("{}", err));
+
+    let mut tar = tar::Archive::new(reader);
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    for entry in tar.entries().map_err(read_err)? {
+        let mut entry = entry.map_err(read_err)?;
+        let header = entry.header();
+        let name = String::from_utf8_lossy(&entry.path_bytes()).to_string();
+        let mut options = options.large_file(entry.size() >= u32::MAX as u64);
+        if let Some(last_modified) = header.mtime().ok().and_then(zip_date_time) {
+            options = options.last_modified_time(last_modified);
+        }
+        if let Ok(mode) = header.mode() {
+            options = options.unix_permissions(mode);
+        }
+
+        match header.entry_type() {
+            tar::EntryType::Regular | tar::EntryType::Continuous => {
+                zip.start_file(name, options).map_err(write_err)?;
+                std::io::copy(&mut entry, &mut zip).map_err(read_err)?;
+            }
+            tar::EntryType::Directory => {
+                zip.add_directory(name, options).map_err(write_err)?;
+            }
+            tar::EntryType::Symlink => {
+                let target = entry.link_name_bytes()
+                    .map(|target| String::from_utf8_lossy(&target).to_string())
+                    .unwrap_or_default();
+                zip.add_symlink(name, target, options).map_err(write_err)?;
+            }
+            _ => {}
+        }
+    }
+    Ok(zip.finish().map_err(write_err)?.into_inner())
+}
+
+/// Set in the Windows attributes of a 7z entry when the high 16 bits hold the Unix mode.
+const SEVENZ_UNIX_EXTENSION: u32 = 0x8000;
+
+/// Describes an error of reading a 7z archive, telling apart a missing or wrong password.
+fn sevenz_error(err: sevenz_rust::Error) -> (String, String) {
+    let msg = match err {
+        sevenz_rust::Error::PasswordRequired => "The 7z archive is encrypted, enter the password.",
+        sevenz_rust::Error::MaybeBadPassword(_) => "Wrong password for the 7z archive.",
+        _ => "Failed to read 7z archive.",
+    };
+    (msg.to_string(), format!("{}", err))
+}
+
+/// Number and total size of the entries of a 7z archive, read from its headers.
+/// Nothing is decoded except the headers, which are decrypted with the password if encrypted.
+pub(crate) fn sevenz_listing(data: &[u8], password: &str) -> Result<(usize, u64), (String, String)> {
+    let sevenz = sevenz_rust::SevenZReader::new(
+        std::io::Cursor::new(data),
+        data.len() as u64,
+        sevenz_rust::Password::from(password),
+    ).map_err(sevenz_error)?;
+    let files = sevenz.archive().files.iter().filter(|entry| !entry.is_anti_item());
+    Ok(files.fold((0, 0u64), |(entries, total_size), entry| (entries + 1, total_size.saturating_add(entry.size()))))
+}
+
+/// Repacks a 7z archive into a ZIP archive, keeping paths, times and modes.
+///
+/// Entries of an encrypted 7z archive are decrypted with the password.
+/// `options` set the compression and encryption of the entries.
+pub(crate) fn sevenz_to_zip(
+    data: &[u8],
+    password: &str,
+    options: zip::write::FileOptions<'_, ()>,
+) -> Result<Vec<u8>, (String, String)> {
+    let write_err = |err: zip::result::ZipError| ("Failed to write ZIP archive.".to_string(), format!("{}", err));
+
+    let mut sevenz = sevenz_rust::SevenZReader::new(
+        std::io::Cursor::new(data),
+        data.len() as u64,
+        sevenz_rust::Password::from(password),
+    ).map_err(sevenz_error)?;
+    let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::with_capacity(data.len())));
+    // Errors of writing are kept aside: the callback can only return errors of 7z.
+    let mut write_failure = None;
+    sevenz.for_each_entries(|entry, reader| {
+        if entry.is_anti_item() {
+            return Ok(true);
+        }
+        let mut options = options.large_file(entry.size() >= u32::MAX as u64);
+        if entry.has_last_modified_date {
+            let modified = u64::try_from(entry.last_modified_date().to_unix_time()).ok().and_then(zip_date_time);
+            if let Some(modified) = modified {
+                options = options.last_modified_time(modified);
+            }
+        }
+        let mode = (entry.has_windows_attributes && entry.windows_attributes() & SEVENZ_UNIX_EXTENSION != 0)
+            .then(|| entry.windows_attributes() >> 16);
+        if let Some(mode) = mode {
+            options = options.unix_permissions(mode);
+        }
+
+        let written = if entry.is_directory() {
+            zip.add_directory(entry.name(), options)
+        } else if mode.is_some_and(|mode| mode & 0o170000 == 0o120000) {
+            // The target of a symbolic link is stored as the entry data.
+            let mut target = String::new();
+            reader.read_to_string(&mut target)?;
+            zip.add_symlink(entry.name(), target, options)
+        } else {
+            zip.start_file(entry.name(), options)
+                .and_then(|_| std::io::copy(reader, &mut zip).map(|_| ()).map_err(zip::result::ZipError::from))
+        };
+        match written {
+            Ok(_) => Ok(true),
+            Err(err) => {
+                write_failure = Some(write_err(err));
+                Ok(false)
+            }
+        }
+    }).map_err(sevenz_error)?;
+    if let Some(err) = write_failure {
+        return Err(err);
+    }
+    Ok(zip.finish().map_err(write_err)?.into_inner())
+}
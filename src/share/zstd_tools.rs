@@ -0,0 +1,93 @@
+//! Reading of Zstandard files with the decoder of ruzstd.
+
+use std::io::{BufRead, Read};
+use ruzstd::decoding::errors::{FrameDecoderError, ReadFrameHeaderError};
+use ruzstd::decoding::{BlockDecodingStrategy, FrameDecoder};
+
+fn invalid_data(err: impl std::fmt::Display) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, format!("zstd: {err}"))
+}
+
+/// Decompresses a Zstandard file while reading.
+///
+/// `StreamingDecoder` of ruzstd stops after the first frame. This reader decodes
+/// all frames one after another, as by `zstd -d`, and skips the skippable frames.
+pub(crate) struct ZstdReader<R: Read> {
+    source: std::io::BufReader<R>,
+    decoder: FrameDecoder,
+    /// A frame has been started and its data is not read to the end yet.
+    in_frame: bool,
+    frames: usize,
+}
+
+impl<R: Read> ZstdReader<R> {
+    pub fn new(source: R) -> Self {
+        Self { source: std::io::BufReader::new(source), decoder: FrameDecoder::new(), in_frame: false, frames: 0 }
+    }
+
+    /// Reads the header of the next frame. Returns `false` at the end of the file.
+    fn start_frame(&mut self) -> std::io::Result<bool> {
+        loop {
+            if self.source.fill_buf()?.is_empty() {
+                if self.frames == 0 {
+                    return Err(std::io::ErrorKind::UnexpectedEof.into());
+                }
+                return Ok(false);
+            }
+            match self.decoder.reset(&mut self.source) {
+                Ok(()) => {
+                    self.in_frame = true;
+                    return Ok(true);
+                }
+                Err(FrameDecoderError::ReadFrameHeaderError(ReadFrameHeaderError::SkipFrame { length, .. })) => {
+                    // Skippable frames hold metadata of other tools, not data of the file.
+                    let skipped = std::io::copy(&mut (&mut self.source).take(length as u64), &mut std::io::sink())?;
+                    if skipped < length as u64 {
+                        return Err(std::io::ErrorKind::UnexpectedEof.into());
+                    }
+                    self.frames += 1;
+                }
+                Err(err) => return Err(invalid_data(err)),
+            }
+        }
+    }
+
+    /// Verifies the checksum of the frame whose data has been read to the end.
+    fn finish_frame(&mut self) -> std::io::Result<()> {
+        self.in_frame = false;
+        self.frames += 1;
+        match (self.decoder.get_checksum_from_data(), self.decoder.get_calculated_checksum()) {
+            (Some(stored), Some(calculated)) if stored != calculated => Err(invalid_data("checksum mismatch")),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<R: Read> Read for ZstdReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            if self.in_frame {
+                // The same loop as in `StreamingDecoder`: blocks are decoded until enough data can be collected.
+                while self.decoder.can_collect() < buf.len() && !self.decoder.is_finished() {
+                    let needed = buf.len() - self.decoder.can_collect();
+                    self.decoder.decode_blocks(&mut self.source, BlockDecodingStrategy::UptoBytes(needed))
+                        .map_err(invalid_data)?;
+                }
+                let n = self.decoder.read(buf)?;
+                if n > 0 {
+                    return Ok(n);
+                }
+                if !self.decoder.is_finished() {
+                    continue;
+                }
+                self.finish_frame()?;
+            }
+            if !self.start_frame()? {
+                return Ok(0);
+            }
+        }
+    }
+}
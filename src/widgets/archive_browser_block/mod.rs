@@ -0,0 +1,3 @@
+mod ui;
+
+pub use ui::ArchiveBrowserBlockComponent;
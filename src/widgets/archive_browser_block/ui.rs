@@ -0,0 +1,298 @@
+use yew::Context;
+use crate::{entities, features};
+
+pub enum ArchiveBrowserBlockMsg {
+    /// An archive has been opened.
+    OpenedArchive(entities::archive::model::OpenedArchive),
+    /// The names and comments of the archive were decoded with another charset.
+    ChangedCharset(entities::archive::model::OpenedArchive),
+    /// Password from the input field.
+    EditPassword(String),
+    /// A pending change of an entry was added or cancelled.
+    ChangeEntry((usize, Option<features::archive::edit_entries::EntryChange>)),
+    DiscardChanges,
+    CloseArchive,
+    /// The user decided to browse the archive despite the violated limits.
+    AcceptWarnings,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct ArchiveBrowserBlockProps {
+    /// Files selected for a new archive, offered for comparison.
+    #[prop_or_default]
+    pub selected_files: Vec<std::rc::Rc<entities::file::model::InfoAboutSelectedFile>>,
+    /// Limits checked before the entries of an opened archive are shown.
+    pub limits: features::archive::archive_limits::ArchiveLimits,
+    pub on_limits_change: yew::Callback<features::archive::archive_limits::ArchiveLimits>,
+}
+
+/// Component - an area for browsing the entries of an existing archive.
+pub struct ArchiveBrowserBlockComponent {
+    archive: Option<std::rc::Rc<entities::archive::model::OpenedArchive>>,
+    password: yew::AttrValue,
+    changes: std::collections::HashMap<usize, features::archive::edit_entries::EntryChange>,
+    /// Violated limits of the opened archive, the entries are hidden until the user accepts them.
+    warnings: Vec<String>,
+}
+
+impl yew::Component for ArchiveBrowserBlockComponent {
+    type Message = ArchiveBrowserBlockMsg;
+    type Properties = ArchiveBrowserBlockProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            archive: None,
+            password: yew::AttrValue::default(),
+            changes: std::collections::HashMap::new(),
+            warnings: vec![],
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ArchiveBrowserBlockMsg::OpenedArchive(archive) => {
+                self.warnings = features::archive::archive_limits::check_limits(&archive, &ctx.props().limits);
+                self.archive = Some(std::rc::Rc::new(archive));
+                self.changes = std::collections::HashMap::new();
+                true
+            }
+            ArchiveBrowserBlockMsg::ChangedCharset(archive) => {
+                // Pending renames refer to the names in the previous charset.
+                self.archive = Some(std::rc::Rc::new(archive));
+                self.changes = std::collections::HashMap::new();
+                true
+            }
+            ArchiveBrowserBlockMsg::EditPassword(password) => {
+                self.password = yew::AttrValue::from(password);
+                true
+            }
+            ArchiveBrowserBlockMsg::ChangeEntry((ind, change)) => {
+                match change {
+                    Some(change) => { self.changes.insert(ind, change); }
+                    None => { self.changes.remove(&ind); }
+                }
+                true
+            }
+            ArchiveBrowserBlockMsg::DiscardChanges => {
+                self.changes = std::collections::HashMap::new();
+                true
+            }
+            ArchiveBrowserBlockMsg::CloseArchive => {
+                self.archive = None;
+                self.changes = std::collections::HashMap::new();
+                self.password = yew::AttrValue::default();
+                self.warnings = vec![];
+                true
+            }
+            ArchiveBrowserBlockMsg::AcceptWarnings => {
+                self.warnings = vec![];
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> yew::Html {
+        let Some(archive) = &self.archive else {
+            let on_archive_opened = &ctx.link().callback(ArchiveBrowserBlockMsg::OpenedArchive);
+            let on_limits_accepted = &ctx.link().callback(|_| ArchiveBrowserBlockMsg::AcceptWarnings);
+            return yew::html! {
+                <div class={yew::classes!("row", "border", "mt-3")}>
+                    <features::archive::open_archive::OpenArchiveComponent
+                        {on_archive_opened}
+                        limits={ctx.props().limits}
+                        {on_limits_accepted}
+                    />
+                    <div class={yew::classes!("col-12", "pb-2")}>
+                        <features::archive::archive_limits::ArchiveLimitsComponent
+                            limits={ctx.props().limits}
+                            on_limits_change={ctx.props().on_limits_change.clone()}
+                        />
+                    </div>
+                </div>
+            };
+        };
+
+        let on_password_change = &ctx.link().callback(ArchiveBrowserBlockMsg::EditPassword);
+        let on_charset_change = &ctx.link().callback(ArchiveBrowserBlockMsg::ChangedCharset);
+        let on_archive_updated = &ctx.link().callback(ArchiveBrowserBlockMsg::OpenedArchive);
+        let on_change = &ctx.link().callback(ArchiveBrowserBlockMsg::ChangeEntry);
+        let on_discard = &ctx.link().callback(|_| ArchiveBrowserBlockMsg::DiscardChanges);
+        let close_onclick = &ctx.link().callback(|_| ArchiveBrowserBlockMsg::CloseArchive);
+        let on_continue = &ctx.link().callback(|_| ArchiveBrowserBlockMsg::AcceptWarnings);
+
+        yew::html! {
+            <>
+                <div class={yew::classes!("row", "border", "mt-3", "py-2", "align-items-center")}>
+                    <div class={yew::classes!("col", "text-truncate")}>
+                        <strong>{&archive.file_name}</strong>
+                        {format!(" ({} entries)", archive.entries.len())}
+                    </div>
+                    if archive.detected_charset.is_some() {
+                        <div class={yew::classes!("col")}>
+                            <features::archive::name_charset::NameCharsetComponent
+                                archive={archive.clone()}
+                                {on_charset_change}
+                            />
+                        </div>
+                    }
+                    if archive.has_encrypted_entries() {
+                        <div class={yew::classes!("col")}>
+                            <features::archive::decrypt_entry::ArchivePasswordComponent
+                                password={self.password.clone()}
+                                {on_password_change}
+                            />
+                        </div>
+                    }
+                    <div class={yew::classes!("col-auto")}>
+                        <button
+                            type="button"
+                            class={yew::classes!("btn", "btn-sm", "btn-outline-dark")}
+                            onclick={close_onclick}
+                        >
+                            {"Close"}
+                        </button>
+                    </div>
+                    if !archive.comment.is_empty() {
+                        <div class={yew::classes!("col-12", "text-muted", "text-break")} style="white-space: pre-wrap;">
+                            <small>{&archive.comment}</small>
+                        </div>
+                    }
+                </div>
+                if !self.warnings.is_empty() {
+                    <div class={yew::classes!("row", "border", "rounded-bottom", "border-top-0", "py-2")}>
+                        <div class={yew::classes!("col")}>
+                            <features::archive::archive_limits::LimitWarningsComponent
+                                warnings={self.warnings.clone()}
+                                {on_continue}
+                            />
+                        </div>
+                    </div>
+                } else {
+                <div class={yew::classes!("row", "px-2", "pt-2", "border-start", "border-end")}>
+                    <div class={yew::classes!("list-group", "overflow-auto", "pe-0")} style="max-height: 40vh;">
+                        { archive.entries.iter().map(|entry| {
+                            self.view_entry_row(ctx, archive, entry, on_change)
+                        }).collect::<yew::Html>() }
+                    </div>
+                </div>
+                <div class={yew::classes!("row", "border", "border-top-0", "py-2")}>
+                    <div class={yew::classes!("col")}>
+                        <features::archive::search_archive::SearchArchiveComponent
+                            archive={archive.clone()}
+                            password={self.password.clone()}
+                        />
+                    </div>
+                </div>
+                <div class={yew::classes!("row", "border", "border-top-0", "py-2")}>
+                    <div class={yew::classes!("col")}>
+                        <features::archive::test_archive::TestArchiveComponent
+                            archive={archive.clone()}
+                            password={self.password.clone()}
+                        />
+                    </div>
+                    <div class={yew::classes!("col", "d-flex", "flex-column", "align-items-end", "gap-2")}>
+                        <features::archive::edit_entries::SaveEditedArchiveComponent
+                            archive={archive.clone()}
+                            changes={self.changes.clone()}
+                            on_archive_saved={on_archive_updated}
+                            {on_discard}
+                        />
+                        <features::archive::append_files::AppendFilesComponent
+                            archive={archive.clone()}
+                            password={self.password.clone()}
+                            {on_archive_updated}
+                        />
+                    </div>
+                </div>
+                <div class={yew::classes!("row", "border", "border-top-0", "py-2")}>
+                    <div class={yew::classes!("col")}>
+                        <features::archive::inspect_structure::InspectStructureComponent archive={archive.clone()} />
+                    </div>
+                </div>
+                <div class={yew::classes!("row", "border", "border-top-0", "py-2")}>
+                    <div class={yew::classes!("col")}>
+                        <features::archive::extract_archive::ExtractArchiveComponent
+                            archive={archive.clone()}
+                            password={self.password.clone()}
+                        />
+                    </div>
+                </div>
+                <div class={yew::classes!("row", "border", "border-top-0", "py-2")}>
+                    <div class={yew::classes!("col")}>
+                        <features::archive::compare_archives::CompareArchivesComponent
+                            archive={archive.clone()}
+                            password={self.password.clone()}
+                            selected_files={ctx.props().selected_files.clone()}
+                            limits={ctx.props().limits}
+                        />
+                    </div>
+                </div>
+                <div class={yew::classes!("row", "border", "border-top-0", "py-2")}>
+                    <div class={yew::classes!("col")}>
+                        <features::archive::update_archive::UpdateArchiveComponent
+                            archive={archive.clone()}
+                            password={self.password.clone()}
+                            selected_files={ctx.props().selected_files.clone()}
+                            {on_archive_updated}
+                        />
+                    </div>
+                </div>
+                <div class={yew::classes!("row", "border", "border-top-0", "py-2")}>
+                    <div class={yew::classes!("col")}>
+                        <features::archive::reencrypt_archive::ReencryptArchiveComponent
+                            archive={archive.clone()}
+                            password={self.password.clone()}
+                        />
+                    </div>
+                </div>
+                <div class={yew::classes!("row", "border", "rounded-bottom", "border-top-0", "py-2")}>
+                    <div class={yew::classes!("col")}>
+                        <features::archive::recompress_archive::RecompressArchiveComponent
+                            archive={archive.clone()}
+                            password={self.password.clone()}
+                        />
+                    </div>
+                </div>
+                }
+            </>
+        }
+    }
+}
+
+impl ArchiveBrowserBlockComponent {
+    fn view_entry_row(
+        &self,
+        ctx: &Context<Self>,
+        archive: &std::rc::Rc<entities::archive::model::OpenedArchive>,
+        entry: &entities::archive::model::InfoAboutArchiveEntry,
+        on_change: &yew::Callback<(usize, Option<features::archive::edit_entries::EntryChange>)>,
+    ) -> yew::Html {
+        yew::html! {
+            <div class={yew::classes!("list-group-item", "p-2")} key={entry.ind}>
+                <entities::archive::ui::ArchiveEntryRowComponent info_about_archive_entry={entry.clone()} />
+                <features::archive::decrypt_entry::DecryptEntryComponent
+                    archive={archive.clone()}
+                    entry={entry.clone()}
+                    password={self.password.clone()}
+                />
+                <features::archive::preview_entry::PreviewEntryComponent
+                    archive={archive.clone()}
+                    entry={entry.clone()}
+                    password={self.password.clone()}
+                />
+                <features::archive::nested_archive::NestedArchiveComponent
+                    archive={archive.clone()}
+                    entry={entry.clone()}
+                    password={self.password.clone()}
+                    limits={ctx.props().limits}
+                    depth={1}
+                />
+                <features::archive::edit_entries::EditEntryComponent
+                    entry={entry.clone()}
+                    change={self.changes.get(&entry.ind).cloned()}
+                    {on_change}
+                />
+            </div>
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use yew::Context;
+use crate::entities::archive::model::OpenedArchive;
+use crate::features::archive::dual_pane::{Pane, PaneSide};
+use crate::{entities, features};
+
+pub enum ArchiveManagerBlockMsg {
+    OpenedArchive(PaneSide, OpenedArchive),
+    UseSelection(PaneSide),
+    NewArchive(PaneSide),
+    ClosePane(PaneSide),
+    /// An item of the pane on the first side is copied to the other pane.
+    CopyItem(PaneSide, usize),
+    RemoveItem(PaneSide, usize),
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct ArchiveManagerBlockProps {
+    /// Files currently selected for a new archive.
+    pub selected_files: Vec<std::rc::Rc<entities::file::model::InfoAboutSelectedFile>>,
+    /// Limits checked before the entries of an opened archive are shown.
+    pub limits: features::archive::archive_limits::ArchiveLimits,
+}
+
+/// Component - two panes with archives or selected files and copying of entries between them.
+pub struct ArchiveManagerBlockComponent {
+    left: Option<Pane>,
+    right: Option<Pane>,
+}
+
+impl ArchiveManagerBlockComponent {
+    fn pane_mut(&mut self, side: PaneSide) -> &mut Option<Pane> {
+        match side {
+            PaneSide::Left => &mut self.left,
+            PaneSide::Right => &mut self.right,
+        }
+    }
+
+    fn pane(&self, side: PaneSide) -> &Option<Pane> {
+        match side {
+            PaneSide::Left => &self.left,
+            PaneSide::Right => &self.right,
+        }
+    }
+
+    fn view_pane(&self, ctx: &Context<Self>, side: PaneSide) -> yew::Html {
+        let Some(pane) = self.pane(side) else {
+            let on_archive_opened = ctx.link().callback(move |archive| ArchiveManagerBlockMsg::OpenedArchive(side, archive));
+            let selection_onclick = ctx.link().callback(move |_| ArchiveManagerBlockMsg::UseSelection(side));
+            let new_onclick = ctx.link().callback(move |_| ArchiveManagerBlockMsg::NewArchive(side));
+            return yew::html! {
+                <div class={yew::classes!("d-flex", "flex-column", "align-items-center", "gap-2", "py-2")}>
+                    <features::archive::open_archive::OpenArchiveComponent {on_archive_opened} limits={ctx.props().limits} />
+                    <div>
+                        <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark", "me-2")}
+                            disabled={ctx.props().selected_files.is_empty()}
+                            onclick={selection_onclick}
+                        >
+                            {format!("Selected files ({})", ctx.props().selected_files.len())}
+                        </button>
+                        <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={new_onclick}>
+                            {"New empty archive"}
+                        </button>
+                    </div>
+                </div>
+            };
+        };
+
+        let on_drop = ctx.link().callback(|(from, ind)| ArchiveManagerBlockMsg::CopyItem(from, ind));
+        let on_copy = ctx.link().callback(move |ind| ArchiveManagerBlockMsg::CopyItem(side, ind));
+        let on_remove = ctx.link().callback(move |ind| ArchiveManagerBlockMsg::RemoveItem(side, ind));
+        let close_onclick = ctx.link().callback(move |_| ArchiveManagerBlockMsg::ClosePane(side));
+        yew::html! {
+            <div class={yew::classes!("d-flex", "flex-column", "gap-2", "py-2")}>
+                <div class={yew::classes!("d-flex", "align-items-center", "gap-2")}>
+                    <h6 class={yew::classes!("mb-0", "flex-fill", "text-truncate")}>{&pane.title}</h6>
+                    <small class={yew::classes!("text-muted")}>{format!("{} entries", pane.items.len())}</small>
+                    <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={close_onclick}>
+                        {"Close"}
+                    </button>
+                </div>
+                <features::archive::dual_pane::PaneComponent pane={pane.clone()} {side} {on_drop} {on_copy} {on_remove} />
+                <features::archive::dual_pane::SavePaneComponent pane={pane.clone()} />
+            </div>
+        }
+    }
+}
+
+impl yew::Component for ArchiveManagerBlockComponent {
+    type Message = ArchiveManagerBlockMsg;
+    type Properties = ArchiveManagerBlockProps;
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self { left: None, right: None }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            ArchiveManagerBlockMsg::OpenedArchive(side, archive) => {
+                *self.pane_mut(side) = Some(Pane::from_archive(std::rc::Rc::new(archive)));
+                true
+            }
+            ArchiveManagerBlockMsg::UseSelection(side) => {
+                *self.pane_mut(side) = Some(Pane::from_files(&ctx.props().selected_files));
+                true
+            }
+            ArchiveManagerBlockMsg::NewArchive(side) => {
+                *self.pane_mut(side) = Some(Pane::empty());
+                true
+            }
+            ArchiveManagerBlockMsg::ClosePane(side) => {
+                *self.pane_mut(side) = None;
+                true
+            }
+            ArchiveManagerBlockMsg::CopyItem(from, ind) => {
+                let Some(items) = self.pane(from).as_ref().map(|pane| pane.take_copies(ind)) else { return false; };
+                let Some(target) = self.pane_mut(from.other()) else { return false; };
+                target.add_items(items);
+                true
+            }
+            ArchiveManagerBlockMsg::RemoveItem(side, ind) => {
+                let Some(pane) = self.pane_mut(side) else { return false; };
+                pane.remove(ind);
+                true
+            }
+        }
+    }
+
+    fn view(&self, ctx: &Context<Self>) -> yew::Html {
+        yew::html! {
+            <div class={yew::classes!("row", "border", "rounded-bottom", "border-top-0")}>
+                <div class={yew::classes!("col-md-6", "border-end")}>
+                    {self.view_pane(ctx, PaneSide::Left)}
+                </div>
+                <div class={yew::classes!("col-md-6")}>
+                    {self.view_pane(ctx, PaneSide::Right)}
+                </div>
+            </div>
+        }
+    }
+}
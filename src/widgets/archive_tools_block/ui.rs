@@ -0,0 +1,26 @@
+use crate::features;
+
+#[derive(yew::Properties, PartialEq)]
+pub struct ArchiveToolsBlockProps {
+    /// Limits checked before the entries of the archives are decompressed.
+    pub limits: features::archive::archive_limits::ArchiveLimits,
+}
+
+/// Component - operations on archives that are not opened in the browser.
+#[yew::function_component(ArchiveToolsBlockComponent)]
+pub fn archive_tools_block(props: &ArchiveToolsBlockProps) -> yew::Html {
+    yew::html! {
+        <>
+            <div class={yew::classes!("row", "border", "border-top-0", "py-2")}>
+                <div class={yew::classes!("col")}>
+                    <features::archive::merge_archives::MergeArchivesComponent limits={props.limits} />
+                </div>
+            </div>
+            <div class={yew::classes!("row", "border", "rounded-bottom", "border-top-0", "py-2")}>
+                <div class={yew::classes!("col")}>
+                    <features::archive::convert_archive::ConvertArchiveComponent limits={props.limits} />
+                </div>
+            </div>
+        </>
+    }
+}
@@ -1,30 +1,64 @@
 use yew::Context;
+use crate::entities::file::model::SortColumn;
 use crate::{entities, features, share};
 
+/// Prefix of the drag data with the id of a dragged file row.
+const DRAG_DATA_PREFIX: &str = "selected-file:";
+
 pub enum FileSelectionBlockMsg {
     /// New file list received.
     NewFileList(gloo_file::FileList),
+    /// Remove the file with the id.
     RemoveFile(usize),
+    /// Move the file with the id to the position in the list.
+    MoveFile { id: usize, to: usize },
     /// Message about the need to block the interface
     NeedToBlock,
     /// Message with the index of the compressed file.
     SuccessCompressFile(usize),
+    /// Sort the table by the column, or reverse the order if it is already sorted by it.
+    SortBy(SortColumn),
+    /// Show the files in the order of the archive entries again.
+    ClearSort,
+    /// Make the current sort the order of the archive entries.
+    ApplySort,
+}
+
+#[derive(yew::Properties, PartialEq)]
+pub struct FileSelectionBlockProps {
+    /// Callback with the selected files, in the order of the archive entries.
+    #[prop_or_default]
+    pub on_files_change: yew::Callback<Vec<std::rc::Rc<entities::file::model::InfoAboutSelectedFile>>>,
+    /// Limits of the size and of the compression ratio of decompressed files.
+    #[prop_or_default]
+    pub limits: features::archive::archive_limits::ArchiveLimits,
 }
 
 /// Component - an area for adding and compressing files.
 pub struct FileSelectionBlockComponent {
-    files: std::collections::HashMap<usize, std::rc::Rc<entities::file::model::InfoAboutSelectedFile>>,
+    /// Selected files with their ids, in the order of the archive entries.
+    files: Vec<(usize, std::rc::Rc<entities::file::model::InfoAboutSelectedFile>)>,
+    /// Id for the next added file.
+    next_id: usize,
+    /// Id of the file row to focus after it has been moved with the keyboard.
+    focus_after_render: Option<usize>,
+    /// Column and direction (`true` for ascending) by which the table is sorted.
+    /// The sort only changes the view until it is applied to the archive order.
+    sort: Option<(SortColumn, bool)>,
     need_to_block_action: bool,
     list_success_ind: Vec<usize>,
 }
 
 impl yew::Component for FileSelectionBlockComponent {
     type Message = FileSelectionBlockMsg;
-    type Properties = ();
+    type Properties = FileSelectionBlockProps;
 
     fn create(_ctx: &Context<Self>) -> Self {
         Self {
-            files: std::collections::HashMap::new(),
+            files: vec![],
+            next_id: 0,
+            focus_after_render: None,
+            sort: None,
             need_to_block_action: false,
             list_success_ind: vec![],
         }
@@ -32,7 +66,7 @@ impl yew::Component for FileSelectionBlockComponent {
 
 
 
-    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
         match msg {
             FileSelectionBlockMsg::SuccessCompressFile(ind) => {
                 self.list_success_ind.push(ind);
@@ -43,33 +77,78 @@ impl yew::Component for FileSelectionBlockComponent {
                 true
             }
             FileSelectionBlockMsg::NewFileList(file_list) => {
-                let count = self.files.len();
                 for ind in 0..file_list.len() {
                     if let Some(file) = file_list.get(ind) {
-                        self.files.insert(
-                            count + ind,
+                        self.files.push((
+                            self.next_id,
                             std::rc::Rc::from(
                                 entities::file::model::InfoAboutSelectedFile::from_js_file(file.clone())
                             ),
-                        );
+                        ));
+                        self.next_id += 1;
                     }
                 }
+                self.notify_files_change(ctx);
+                true
+            }
+            FileSelectionBlockMsg::RemoveFile(id) => {
+                self.files.retain(|(file_id, _)| *file_id != id);
+                self.notify_files_change(ctx);
                 true
             }
-            FileSelectionBlockMsg::RemoveFile(ind) => {
-                self.files.remove(&ind);
+            FileSelectionBlockMsg::MoveFile { id, to } => {
+                if self.need_to_block_action || self.sort.is_some() { return false; }
+                let Some(from) = self.files.iter().position(|(file_id, _)| *file_id == id) else { return false; };
+                let to = to.min(self.files.len() - 1);
+                if from == to { return false; }
+                let file = self.files.remove(from);
+                self.files.insert(to, file);
+                self.focus_after_render = Some(id);
+                self.notify_files_change(ctx);
+                true
+            }
+            FileSelectionBlockMsg::SortBy(column) => {
+                let ascending = !matches!(self.sort, Some((sorted, true)) if sorted == column);
+                self.sort = Some((column, ascending));
+                true
+            }
+            FileSelectionBlockMsg::ClearSort => {
+                self.sort = None;
+                true
+            }
+            FileSelectionBlockMsg::ApplySort => {
+                if self.need_to_block_action { return false; }
+                self.files = self.sorted_files().into_iter().map(|(_, file)| file.clone()).collect();
+                self.sort = None;
+                self.notify_files_change(ctx);
                 true
             }
         }
     }
 
+    fn rendered(&mut self, _ctx: &Context<Self>, _first_render: bool) {
+        use wasm_bindgen::JsCast;
+        // The moved row keeps the focus, so that it can be moved again with the keyboard.
+        let Some(id) = self.focus_after_render.take() else { return; };
+        let row = web_sys::window()
+            .and_then(|window| window.document())
+            .and_then(|document| document.get_element_by_id(&Self::row_element_id(id)))
+            .and_then(|element| element.dyn_into::<web_sys::HtmlElement>().ok());
+        if let Some(row) = row {
+            let _ = row.focus();
+        }
+    }
+
     fn view(&self, ctx: &Context<Self>) -> yew::Html {
 
         let on_files_selection = &ctx.link().callback(FileSelectionBlockMsg::NewFileList);
         let on_file_remove = &ctx.link().callback(FileSelectionBlockMsg::RemoveFile);
         let on_start_compress = &ctx.link().callback(|_| FileSelectionBlockMsg::NeedToBlock);
         let on_add_success_compress_file = &ctx.link().callback(FileSelectionBlockMsg::SuccessCompressFile);
-        let size = self.files.values().map(|info_file| {
+        let on_file_move = &ctx.link().callback(|(id, to)| FileSelectionBlockMsg::MoveFile { id, to });
+        let clear_sort_onclick = &ctx.link().callback(|_| FileSelectionBlockMsg::ClearSort);
+        let apply_sort_onclick = &ctx.link().callback(|_| FileSelectionBlockMsg::ApplySort);
+        let size = self.files.iter().map(|(_, info_file)| {
             info_file.raw_size as f64
         }).reduce(|acc, e| acc + e).map(share::size_to_string).unwrap_or("???".to_string());
 
@@ -83,23 +162,50 @@ impl yew::Component for FileSelectionBlockComponent {
                     </div>
                 }
                 <div class={yew::classes!("row", "px-2", "pt-2", "border-start", "border-end")}>
-                    <div class={yew::classes!("list-group", "list-group-numbered", "overflow-auto", "pe-0")} style="max-height: 25vh;">
-                        { self.files.iter().map(|(ind, info_file)| {
-                            Self::view_file_row(
-                                *ind,
-                                info_file,
-                                on_file_remove,
-                                self.need_to_block_action,
-                                self.list_success_ind.contains(ind)
-                            )
-                        }).collect::<yew::Html>() }
-                    </div>
+                    if !self.files.is_empty() {
+                        <div class={yew::classes!("overflow-auto", "px-0")} style="max-height: 25vh;">
+                            <table class={yew::classes!("table", "table-sm", "table-hover", "align-middle", "mb-0")}>
+                                <thead class={yew::classes!("sticky-top", "bg-white")}>
+                                    <tr>
+                                        <th scope="col" title="Position in the archive">{"#"}</th>
+                                        { for SortColumn::ALL.into_iter().map(|column| self.view_sort_header(ctx, column)) }
+                                        <th scope="col"></th>
+                                    </tr>
+                                </thead>
+                                <tbody>
+                                    { self.sorted_files().into_iter().map(|(position, (id, info_file))| {
+                                        self.view_file_row(
+                                            *id,
+                                            position,
+                                            info_file,
+                                            on_file_remove,
+                                            on_file_move,
+                                            ctx.props().limits,
+                                        )
+                                    }).collect::<yew::Html>() }
+                                </tbody>
+                            </table>
+                        </div>
+                        if self.sort.is_some() && !self.need_to_block_action {
+                            <div class={yew::classes!("d-flex", "justify-content-end", "gap-2", "px-0", "py-1")}>
+                                <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")} onclick={clear_sort_onclick}>
+                                    {"Show archive order"}
+                                </button>
+                                <button type="button" class={yew::classes!("btn", "btn-sm", "btn-outline-dark")}
+                                    title="Write the files into the archive in the order shown."
+                                    onclick={apply_sort_onclick}
+                                >
+                                    {"Apply to archive order"}
+                                </button>
+                            </div>
+                        }
+                    }
                 </div>
                 if !self.files.is_empty() {
                 <div class={yew::classes!("row", "border", "rounded-bottom", "border-top-0", "py-2")}>
                     <div class={yew::classes!("col", "col-4")}>{format!("Total size: {}",size)}</div>
                     <div class={yew::classes!("col", "col-8", "d-flex", "justify-content-end")}>
-                        <features::file::compress_files::CompressionFilesComponent files={self.files.clone()} on_start_compress={on_start_compress} on_add_success_compress_file={on_add_success_compress_file}/>
+                        <features::file::compress_files::CompressionFilesComponent files={self.ordered_files()} on_start_compress={on_start_compress} on_add_success_compress_file={on_add_success_compress_file}/>
                     </div>
 
                 </div>
@@ -111,30 +217,117 @@ impl yew::Component for FileSelectionBlockComponent {
 }
 
 impl FileSelectionBlockComponent {
+    fn ordered_files(&self) -> Vec<std::rc::Rc<entities::file::model::InfoAboutSelectedFile>> {
+        self.files.iter().map(|(_, file)| file.clone()).collect()
+    }
+
+    fn notify_files_change(&self, ctx: &Context<Self>) {
+        ctx.props().on_files_change.emit(self.ordered_files());
+    }
+
+    /// Files with their positions in the archive, in the order of the current sort.
+    fn sorted_files(&self) -> Vec<(usize, &(usize, std::rc::Rc<entities::file::model::InfoAboutSelectedFile>))> {
+        let mut files: Vec<_> = self.files.iter().enumerate().collect();
+        if let Some((column, ascending)) = self.sort {
+            files.sort_by(|(_, (_, a)), (_, (_, b))| {
+                let ordering = a.compare_by(b, column);
+                if ascending { ordering } else { ordering.reverse() }
+            });
+        }
+        files
+    }
+
+    fn row_element_id(id: usize) -> String {
+        format!("selected-file-{id}")
+    }
+
+    fn view_sort_header(&self, ctx: &Context<Self>, column: SortColumn) -> yew::Html {
+        let (indicator, aria_sort) = match self.sort {
+            Some((sorted, true)) if sorted == column => (" ▲", "ascending"),
+            Some((sorted, false)) if sorted == column => (" ▼", "descending"),
+            _ => ("", "none"),
+        };
+        let onclick = ctx.link().callback(move |_| FileSelectionBlockMsg::SortBy(column));
+        yew::html! {
+            <th scope="col" class={yew::classes!("text-nowrap")} role="button" aria-sort={aria_sort} {onclick}>
+                {format!("{column}{indicator}")}
+            </th>
+        }
+    }
+
     fn view_file_row(
-        ind: usize,
-        file: &entities::file::model::InfoAboutSelectedFile,
+        &self,
+        id: usize,
+        position: usize,
+        file: &std::rc::Rc<entities::file::model::InfoAboutSelectedFile>,
         on_file_remove: &yew::Callback<usize>,
-        need_to_block_action: bool,
-        is_success_compress: bool,
+        on_file_move: &yew::Callback<(usize, usize)>,
+        limits: features::archive::archive_limits::ArchiveLimits,
     ) -> yew::Html {
+        let need_to_block_action = self.need_to_block_action;
+        // Rows are moved only while they are shown in the order of the archive.
+        let can_move = !need_to_block_action && self.sort.is_none();
+        let ondragstart = yew::Callback::from(move |event: web_sys::DragEvent| {
+            if let Some(dt) = event.data_transfer() {
+                let _ = dt.set_data("text/plain", &format!("{DRAG_DATA_PREFIX}{id}"));
+            }
+        });
+        let ondragover = yew::Callback::from(|event: web_sys::DragEvent| event.prevent_default());
+        let ondrop = {
+            let on_file_move = on_file_move.clone();
+            yew::Callback::from(move |event: web_sys::DragEvent| {
+                event.prevent_default();
+                let dragged = event.data_transfer()
+                    .and_then(|dt| dt.get_data("text/plain").ok())
+                    .and_then(|data| data.strip_prefix(DRAG_DATA_PREFIX)?.parse().ok());
+                if let Some(dragged) = dragged {
+                    on_file_move.emit((dragged, position));
+                }
+            })
+        };
+        let onkeydown = {
+            let on_file_move = on_file_move.clone();
+            yew::Callback::from(move |event: web_sys::KeyboardEvent| {
+                if !event.alt_key() { return; }
+                let to = match event.key().as_str() {
+                    "ArrowUp" => position.checked_sub(1),
+                    "ArrowDown" => Some(position + 1),
+                    _ => None,
+                };
+                if let Some(to) = to {
+                    event.prevent_default();
+                    on_file_move.emit((id, to));
+                }
+            })
+        };
 
-        let classes = if is_success_compress {
-            yew::classes!("list-group-item", "p-0", "text-success")
+        let classes = if self.list_success_ind.contains(&position) {
+            yew::classes!("table-success")
         } else {
-            yew::classes!("list-group-item", "p-0")
+            yew::classes!()
         };
 
         yew::html! {
-            <div class={classes} aria-current="true">
-                <div class={yew::classes!("position-relative", "p-2")}>
-                    <entities::file::ui::SelectedFileFileRowComponent info_about_selected_file={(*file).clone()} />
+            <tr class={classes}
+                key={id}
+                id={Self::row_element_id(id)}
+                draggable={can_move.to_string()}
+                tabindex={can_move.then_some("0")}
+                title={can_move.then_some("Drag or press Alt+↑/Alt+↓ to change the order of the archive entries.")}
+                ondragstart={can_move.then_some(ondragstart)}
+                ondragover={can_move.then_some(ondragover)}
+                ondrop={can_move.then_some(ondrop)}
+                onkeydown={can_move.then_some(onkeydown)}
+            >
+                <td class={yew::classes!("text-muted")}>{position + 1}</td>
+                <entities::file::ui::SelectedFileFileRowComponent info_about_selected_file={(**file).clone()} />
+                <td class={yew::classes!("position-relative")} style="min-width: 40px; padding-right: 2.5rem;">
                     if !need_to_block_action {
-                        <features::file::delete_file::DeleteFileRowComponent ind={ind} on_clicked={on_file_remove} />
+                        <features::file::delete_file::DeleteFileRowComponent ind={id} on_clicked={on_file_remove} />
+                        <features::file::decompress_file::DecompressFileComponent file={file.clone()} {limits} />
                     }
-                </div>
-            </div>
-
+                </td>
+            </tr>
         }
     }
 }
\ No newline at end of file
@@ -1 +1,4 @@
 pub mod file_selection_block;
+pub mod archive_browser_block;
+pub mod archive_tools_block;
+pub mod archive_manager_block;